@@ -3,7 +3,7 @@ use anyhow::Result;
 use chrono::TimeZone;
 use chrono::{Local, NaiveDate};
 use clap::{Args, Subcommand};
-use org_core::{OrgMode, Priority, org_mode::AgendaViewType};
+use org_core::{AgendaOptions, AgendaSortKey, OrgMode, Priority, org_mode::AgendaViewType};
 
 #[derive(Args)]
 pub struct AgendaCommand {
@@ -17,6 +17,11 @@ pub struct AgendaCommand {
     /// Maximum number of results to return
     #[arg(short, long, global = true)]
     limit: Option<usize>,
+
+    /// Sorting strategy overriding org_agenda_sorting_strategy
+    /// (comma-separated, e.g. priority-down,deadline-up)
+    #[arg(long, global = true, value_delimiter = ',')]
+    sort: Option<Vec<AgendaSortKey>>,
 }
 
 #[derive(Subcommand)]
@@ -34,6 +39,10 @@ enum AgendaSubcommand {
         /// Filter by priority (A, B, C)
         #[arg(short = 'p', long)]
         priority: Option<PriorityArg>,
+
+        /// Emacs tags/property match string, e.g. '+work-someday+EFFORT>1/!TODO|WAIT'
+        #[arg(short = 'm', long = "match")]
+        match_string: Option<String>,
     },
 
     /// Show today's tasks
@@ -106,13 +115,15 @@ impl AgendaCommand {
                 states,
                 tags,
                 priority,
+                match_string,
             } => {
-                let tasks = org_mode.list_tasks(
-                    states.as_ref().map(|v| v.as_slice()),
-                    tags.as_ref().map(|v| v.as_slice()),
-                    priority.clone().map(Into::into),
-                    self.limit,
-                )?;
+                let tasks = org_mode.list_tasks_with_options(&AgendaOptions {
+                    todo_states: states.clone(),
+                    tags: tags.clone(),
+                    priority: priority.clone().map(Into::into),
+                    match_string: match_string.clone(),
+                    ..self.options()
+                })?;
 
                 match format {
                     OutputFormat::Plain => {
@@ -170,22 +181,24 @@ impl AgendaCommand {
             }
 
             AgendaSubcommand::Today { tags } => {
-                let view = org_mode.get_agenda_view(
+                let view = org_mode.get_agenda_view_with_options(
                     AgendaViewType::Today,
-                    None,
-                    tags.as_ref().map(|v| v.as_slice()),
-                    self.limit,
+                    &AgendaOptions {
+                        tags: tags.clone(),
+                        ..self.options()
+                    },
                 )?;
 
                 self.print_agenda_view(view, format, &org_mode)?;
             }
 
             AgendaSubcommand::Week { tags } => {
-                let view = org_mode.get_agenda_view(
+                let view = org_mode.get_agenda_view_with_options(
                     AgendaViewType::CurrentWeek,
-                    None,
-                    tags.as_ref().map(|v| v.as_slice()),
-                    self.limit,
+                    &AgendaOptions {
+                        tags: tags.clone(),
+                        ..self.options()
+                    },
                 )?;
 
                 self.print_agenda_view(view, format, &org_mode)?;
@@ -212,11 +225,12 @@ impl AgendaCommand {
                     anyhow::anyhow!("Failed to convert end date '{}' to local timezone", end)
                 })?;
 
-                let view = org_mode.get_agenda_view(
+                let view = org_mode.get_agenda_view_with_options(
                     AgendaViewType::Custom { from, to },
-                    None,
-                    tags.as_ref().map(|v| v.as_slice()),
-                    self.limit,
+                    &AgendaOptions {
+                        tags: tags.clone(),
+                        ..self.options()
+                    },
                 )?;
 
                 self.print_agenda_view(view, format, &org_mode)?;
@@ -226,6 +240,15 @@ impl AgendaCommand {
         Ok(())
     }
 
+    /// Options carrying the arguments shared by every subcommand.
+    fn options(&self) -> AgendaOptions {
+        AgendaOptions {
+            sort: self.sort.clone(),
+            limit: self.limit,
+            ..AgendaOptions::default()
+        }
+    }
+
     fn print_agenda_view(
         &self,
         view: org_core::AgendaView,
@@ -248,49 +271,71 @@ impl AgendaCommand {
                     );
                 } else {
                     println!("Agenda{date_range} - {} task(s):", view.items.len());
-                    for task in view.items {
-                        let state_str = task
-                            .todo_state
-                            .as_ref()
-                            .map(|s| format!("{s:?}"))
-                            .unwrap_or_default();
-                        let priority_str = task
-                            .priority
-                            .as_ref()
-                            .map(|p| format!("[#{p:?}]"))
+                    for day in view.days.iter().filter(|day| !day.entries.is_empty()) {
+                        let weekday = NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")
+                            .map(|date| date.format(" %a").to_string())
                             .unwrap_or_default();
+                        println!("  {}{weekday}", day.date);
+
+                        for entry in &day.entries {
+                            let task = &view.items[entry.item];
+                            let time_str = entry
+                                .time
+                                .as_ref()
+                                .map(|t| format!("{t} "))
+                                .unwrap_or_default();
+                            let state_str = task
+                                .todo_state
+                                .as_ref()
+                                .map(|s| format!("{s:?}"))
+                                .unwrap_or_default();
+                            let priority_str = task
+                                .priority
+                                .as_ref()
+                                .map(|p| format!("[#{p:?}]"))
+                                .unwrap_or_default();
+
+                            let date_suffix = match (task.days_overdue, task.days_until_deadline) {
+                                (Some(d), _) if task.scheduled.is_some() => {
+                                    format!("  (Sched. {d}d ago)")
+                                }
+                                (Some(d), _) => format!("  ({d}d ago)"),
+                                (None, Some(0)) => "  (due today)".to_string(),
+                                (None, Some(d)) if d > 0 => format!("  (due in {d}d)"),
+                                _ => String::new(),
+                            };
+
+                            let date_info = match (&task.scheduled, &task.deadline) {
+                                (Some(s), Some(d)) if s == d => {
+                                    format!("SCHEDULED+DEADLINE: {s}{date_suffix}")
+                                }
+                                (Some(s), Some(d)) => {
+                                    format!("SCHEDULED: {s}, DEADLINE: {d}{date_suffix}")
+                                }
+                                (Some(s), None) => format!("SCHEDULED: {s}{date_suffix}"),
+                                (None, Some(d)) => format!("DEADLINE: {d}{date_suffix}"),
+                                (None, None) => match (&task.start, &task.end) {
+                                    (Some(start), Some(end)) => format!("{start} to {end}"),
+                                    _ => String::new(),
+                                },
+                            };
 
-                        let overdue_suffix = match task.days_overdue {
-                            Some(d) if task.scheduled.is_some() => format!("  (Sched. {d}d ago)"),
-                            Some(d) => format!("  ({d}d ago)"),
-                            None => String::new(),
-                        };
-
-                        let date_info = match (&task.scheduled, &task.deadline) {
-                            (Some(s), Some(d)) if s == d => {
-                                format!("SCHEDULED+DEADLINE: {s}{overdue_suffix}")
-                            }
-                            (Some(s), Some(d)) => {
-                                format!("SCHEDULED: {s}, DEADLINE: {d}{overdue_suffix}")
+                            println!(
+                                "    {}{}{} {} ({}) [{}]",
+                                time_str,
+                                state_str,
+                                if priority_str.is_empty() {
+                                    String::new()
+                                } else {
+                                    format!(" {priority_str}")
+                                },
+                                task.heading,
+                                task.file_path,
+                                entry.reason
+                            );
+                            if !date_info.is_empty() {
+                                println!("      {date_info}");
                             }
-                            (Some(s), None) => format!("SCHEDULED: {s}{overdue_suffix}"),
-                            (None, Some(d)) => format!("DEADLINE: {d}{overdue_suffix}"),
-                            (None, None) => String::new(),
-                        };
-
-                        println!(
-                            "  {}{} {} ({})",
-                            state_str,
-                            if priority_str.is_empty() {
-                                String::new()
-                            } else {
-                                format!(" {priority_str}")
-                            },
-                            task.heading,
-                            task.file_path
-                        );
-                        if !date_info.is_empty() {
-                            println!("    {date_info}");
                         }
                     }
                 }
@@ -301,7 +346,8 @@ impl AgendaCommand {
                     "start_date": view.start_date,
                     "end_date": view.end_date,
                     "count": view.items.len(),
-                    "items": view.items
+                    "items": view.items,
+                    "days": view.days
                 });
                 println!("{}", serde_json::to_string_pretty(&json)?);
             }
@@ -0,0 +1,76 @@
+use crate::config::CliConfig;
+use anyhow::Result;
+use clap::{ArgGroup, Args};
+use org_core::{ArchiveMode, HeadingTarget, OrgMode};
+
+#[derive(Args)]
+#[command(group(
+    ArgGroup::new("target")
+        .args(["id", "file"])
+        .required(true)
+        .multiple(true),
+))]
+pub struct ArchiveCommand {
+    /// Org ID property of the heading to archive (wins over --file/--heading)
+    #[arg(long)]
+    id: Option<String>,
+
+    /// File containing the heading to archive (requires --heading)
+    #[arg(short = 'F', long, requires = "heading")]
+    file: Option<String>,
+
+    /// Slash-separated path of the heading to archive (requires --file)
+    #[arg(long, requires = "file")]
+    heading: Option<String>,
+
+    /// subtree moves it to the archive location, tag toggles :ARCHIVE: in place
+    #[arg(short = 'm', long, default_value = "subtree")]
+    mode: ArchiveMode,
+
+    /// Output format
+    #[arg(short = 'f', long)]
+    format: Option<OutputFormat>,
+}
+
+#[derive(clap::ValueEnum, Clone)]
+enum OutputFormat {
+    Plain,
+    Json,
+}
+
+impl ArchiveCommand {
+    pub fn execute(&self, org_mode: OrgMode, cli: CliConfig) -> Result<()> {
+        let target = HeadingTarget {
+            id: self.id.clone(),
+            file: self.file.clone(),
+            heading_path: self.heading.clone(),
+        };
+
+        let result = org_mode.archive(&target, self.mode)?;
+
+        let format = self.format.as_ref().unwrap_or({
+            match cli.default_format.as_str() {
+                "json" => &OutputFormat::Json,
+                _ => &OutputFormat::Plain,
+            }
+        });
+
+        match format {
+            OutputFormat::Plain => {
+                match (&result.archive_file, result.archived) {
+                    (Some(archive_file), _) => {
+                        println!("Archived from {} to {archive_file}", result.file_path)
+                    }
+                    (None, true) => println!("Tagged as ARCHIVE: {}", result.file_path),
+                    (None, false) => println!("Removed ARCHIVE tag: {}", result.file_path),
+                }
+                println!("  {}", result.heading_line);
+            }
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -36,7 +36,8 @@ pub struct CaptureCommand {
     #[arg(long)]
     target_heading: Option<String>,
 
-    /// SCHEDULED timestamp (ISO YYYY-MM-DD[ HH:MM] [repeater] [warning])
+    /// SCHEDULED timestamp (ISO YYYY-MM-DD[ HH:MM[-HH:MM]] [repeater] [warning],
+    /// or a date range YYYY-MM-DD[ HH:MM]--YYYY-MM-DD[ HH:MM])
     #[arg(long)]
     scheduled: Option<String>,
 
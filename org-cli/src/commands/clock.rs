@@ -0,0 +1,176 @@
+use crate::config::CliConfig;
+use anyhow::Result;
+use clap::{ArgGroup, Args, Subcommand};
+use org_core::org_mode::AgendaViewType;
+use org_core::{ClockAction, ClockGroupBy, ClockReport, HeadingTarget, OrgMode};
+
+#[derive(Args)]
+pub struct ClockCommand {
+    #[command(subcommand)]
+    subcommand: ClockSubcommand,
+
+    /// Output format
+    #[arg(short = 'f', long, global = true)]
+    format: Option<OutputFormat>,
+}
+
+#[derive(Subcommand)]
+enum ClockSubcommand {
+    /// Start a clock on a heading (fails if another clock is running)
+    In {
+        #[command(flatten)]
+        target: TargetArgs,
+
+        /// Clock-in time (ISO YYYY-MM-DD HH:MM, defaults to now)
+        #[arg(long)]
+        at: Option<String>,
+    },
+
+    /// Stop the running clock on a heading
+    Out {
+        #[command(flatten)]
+        target: TargetArgs,
+
+        /// Clock-out time (ISO YYYY-MM-DD HH:MM, defaults to now)
+        #[arg(long)]
+        at: Option<String>,
+    },
+
+    /// Discard the running clock on a heading
+    Cancel {
+        #[command(flatten)]
+        target: TargetArgs,
+    },
+
+    /// Sum clocked time across agenda files
+    Report {
+        /// Agenda range: today, week, month, day/YYYY-MM-DD, week/N or month/N
+        #[arg(short = 'r', long, default_value = "week", conflicts_with = "start")]
+        range: String,
+
+        /// Start date (ISO 8601 format: YYYY-MM-DD; requires --end)
+        #[arg(short = 's', long, requires = "end")]
+        start: Option<String>,
+
+        /// End date (ISO 8601 format: YYYY-MM-DD; requires --start)
+        #[arg(short = 'e', long, requires = "start")]
+        end: Option<String>,
+
+        /// Group totals by heading, file, or tag
+        #[arg(short = 'g', long, default_value = "heading")]
+        group_by: ClockGroupBy,
+    },
+}
+
+#[derive(Args)]
+#[command(group(
+    ArgGroup::new("target")
+        .args(["id", "file"])
+        .required(true)
+        .multiple(true),
+))]
+struct TargetArgs {
+    /// Org ID property of the target heading (wins over --file/--heading)
+    #[arg(long)]
+    id: Option<String>,
+
+    /// File path relative to org directory (requires --heading)
+    #[arg(short = 'F', long, requires = "heading")]
+    file: Option<String>,
+
+    /// Slash-separated heading path (e.g., 'Projects/Work'; requires --file)
+    #[arg(long, requires = "file")]
+    heading: Option<String>,
+}
+
+impl From<&TargetArgs> for HeadingTarget {
+    fn from(args: &TargetArgs) -> Self {
+        HeadingTarget {
+            id: args.id.clone(),
+            file: args.file.clone(),
+            heading_path: args.heading.clone(),
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone)]
+enum OutputFormat {
+    Plain,
+    Json,
+}
+
+impl ClockCommand {
+    pub fn execute(&self, org_mode: OrgMode, cli: CliConfig) -> Result<()> {
+        let format = self.format.as_ref().unwrap_or({
+            match cli.default_format.as_str() {
+                "json" => &OutputFormat::Json,
+                _ => &OutputFormat::Plain,
+            }
+        });
+
+        let result = match &self.subcommand {
+            ClockSubcommand::In { target, at } => {
+                org_mode.clock_in(&target.into(), at.as_deref())?
+            }
+            ClockSubcommand::Out { target, at } => {
+                org_mode.clock_out(&target.into(), at.as_deref())?
+            }
+            ClockSubcommand::Cancel { target } => org_mode.clock_cancel(&target.into())?,
+            ClockSubcommand::Report {
+                range,
+                start,
+                end,
+                group_by,
+            } => {
+                let range = match (start, end) {
+                    (Some(start), Some(end)) => {
+                        AgendaViewType::try_from(format!("query/from/{start}/to/{end}").as_str())?
+                    }
+                    _ => AgendaViewType::try_from(range.as_str())?,
+                };
+                let report = org_mode.clock_report(range, *group_by)?;
+                return Self::print_report(&report, format);
+            }
+        };
+
+        match format {
+            OutputFormat::Plain => {
+                let verb = match result.action {
+                    ClockAction::In => "Clocked in",
+                    ClockAction::Out => "Clocked out",
+                    ClockAction::Cancel => "Cancelled clock",
+                };
+                println!("{verb}: {}", result.file_path);
+                println!("  {}", result.heading_line.trim());
+                println!("  {}", result.clock_line);
+            }
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn print_report(report: &ClockReport, format: &OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Plain => {
+                println!(
+                    "Clock report ({} to {}) by {} - total {}",
+                    report.start_date, report.end_date, report.group_by, report.total
+                );
+                for row in &report.rows {
+                    match &row.file_path {
+                        Some(file) => println!("  {:>6}  {} ({file})", row.duration, row.key),
+                        None => println!("  {:>6}  {}", row.duration, row.key),
+                    }
+                }
+            }
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(report)?);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::config::CliConfig;
+use anyhow::Result;
+use clap::{ArgGroup, Args};
+use org_core::{HeadingTarget, OrgMode};
+
+/// Shared arguments of `promote` and `demote`.
+#[derive(Args)]
+#[command(group(
+    ArgGroup::new("target")
+        .args(["id", "file"])
+        .required(true)
+        .multiple(true),
+))]
+pub struct LevelCommand {
+    /// Org ID property of the target heading (wins over --file/--heading)
+    #[arg(long)]
+    id: Option<String>,
+
+    /// File containing the heading (requires --heading)
+    #[arg(short = 'F', long, requires = "heading")]
+    file: Option<String>,
+
+    /// Slash-separated heading path (requires --file)
+    #[arg(long, requires = "file")]
+    heading: Option<String>,
+
+    /// Number of levels to shift
+    #[arg(short = 'n', long, default_value_t = 1)]
+    levels: u32,
+
+    /// Shift every descendant heading too
+    #[arg(short = 's', long)]
+    subtree: bool,
+
+    /// Output format
+    #[arg(short = 'f', long)]
+    format: Option<OutputFormat>,
+}
+
+#[derive(clap::ValueEnum, Clone)]
+enum OutputFormat {
+    Plain,
+    Json,
+}
+
+impl LevelCommand {
+    /// Runs the shift; `sign` is -1 for promote and 1 for demote.
+    pub fn execute(&self, org_mode: OrgMode, cli: CliConfig, sign: isize) -> Result<()> {
+        let target = HeadingTarget {
+            id: self.id.clone(),
+            file: self.file.clone(),
+            heading_path: self.heading.clone(),
+        };
+
+        let result = org_mode.shift_level(&target, sign * self.levels as isize, self.subtree)?;
+
+        let format = self.format.as_ref().unwrap_or({
+            match cli.default_format.as_str() {
+                "json" => &OutputFormat::Json,
+                _ => &OutputFormat::Plain,
+            }
+        });
+
+        match format {
+            OutputFormat::Plain => {
+                println!("Updated {}", result.file_path);
+                println!("  {}", result.heading_line);
+                for change in &result.changes {
+                    println!("  {change}");
+                }
+            }
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+        }
+
+        Ok(())
+    }
+}
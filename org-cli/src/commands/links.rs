@@ -0,0 +1,114 @@
+use crate::config::CliConfig;
+use anyhow::Result;
+use clap::{ArgGroup, Args};
+use org_core::{Backlink, OrgMode};
+
+#[derive(Args)]
+#[command(group(
+    ArgGroup::new("scope")
+        .args(["file", "broken", "to"])
+        .required(true)
+        .multiple(true),
+))]
+pub struct LinksCommand {
+    /// File to list the outgoing links of, relative to org directory
+    file: Option<String>,
+
+    /// Only links inside this slash-separated heading path (requires FILE)
+    #[arg(long, requires = "file")]
+    heading: Option<String>,
+
+    /// Only report links whose target does not exist (across the whole org directory unless FILE is given)
+    #[arg(short = 'b', long)]
+    broken: bool,
+
+    /// List backlinks to an ID, a file, file::*Heading or *Heading instead
+    #[arg(long, conflicts_with_all = ["file", "broken"])]
+    to: Option<String>,
+
+    /// Output format
+    #[arg(short = 'f', long)]
+    format: Option<OutputFormat>,
+}
+
+#[derive(clap::ValueEnum, Clone)]
+enum OutputFormat {
+    Plain,
+    Json,
+}
+
+impl LinksCommand {
+    pub fn execute(&self, org_mode: OrgMode, cli: CliConfig) -> Result<()> {
+        let format = self.format.as_ref().unwrap_or({
+            match cli.default_format.as_str() {
+                "json" => &OutputFormat::Json,
+                _ => &OutputFormat::Plain,
+            }
+        });
+
+        if let Some(ref target) = self.to {
+            let backlinks = org_mode.backlinks(target)?;
+            return Self::print_backlinks(target, &backlinks, format);
+        }
+
+        let links = match self.file {
+            Some(ref file) => {
+                let mut links = org_mode.links_in(file, self.heading.as_deref())?;
+                if self.broken {
+                    links.retain(|link| link.resolves == Some(false));
+                }
+                links
+            }
+            None => org_mode.broken_links()?,
+        };
+
+        match format {
+            OutputFormat::Plain => {
+                if links.is_empty() {
+                    let what = if self.broken { "broken links" } else { "links" };
+                    println!("No {what} found");
+                }
+                for link in &links {
+                    let status = match link.resolves {
+                        Some(true) => "ok",
+                        Some(false) => "broken",
+                        None => "unchecked",
+                    };
+                    println!(
+                        "{}:{} [{}] {status}: {}",
+                        link.file_path, link.line, link.kind, link.target
+                    );
+                }
+            }
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&links)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn print_backlinks(target: &str, backlinks: &[Backlink], format: &OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Plain => {
+                if backlinks.is_empty() {
+                    println!("No backlinks to '{target}'");
+                }
+                for backlink in backlinks {
+                    match &backlink.heading_path {
+                        Some(heading) => {
+                            println!("{}:{} ({heading})", backlink.file_path, backlink.line)
+                        }
+                        None => println!("{}:{}", backlink.file_path, backlink.line),
+                    }
+                    println!("  {}", backlink.context);
+                }
+            }
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(backlinks)?);
+            }
+        }
+
+        Ok(())
+    }
+}
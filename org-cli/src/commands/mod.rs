@@ -1,21 +1,33 @@
 pub mod agenda;
+pub mod archive;
 pub mod capture;
+pub mod clock;
 pub mod config;
 pub mod element_by_id;
 pub mod heading;
+pub mod level;
+pub mod links;
 pub mod list;
 pub mod outline;
+pub mod query;
 pub mod read;
+pub mod refile;
 pub mod search;
 pub mod update_todo;
 
 pub use agenda::AgendaCommand;
+pub use archive::ArchiveCommand;
 pub use capture::CaptureCommand;
+pub use clock::ClockCommand;
 pub use config::ConfigCommand;
 pub use element_by_id::ElementByIdCommand;
 pub use heading::HeadingCommand;
+pub use level::LevelCommand;
+pub use links::LinksCommand;
 pub use list::ListCommand;
 pub use outline::OutlineCommand;
+pub use query::QueryCommand;
 pub use read::ReadCommand;
+pub use refile::RefileCommand;
 pub use search::SearchCommand;
 pub use update_todo::UpdateTodoCommand;
@@ -0,0 +1,67 @@
+use crate::config::CliConfig;
+use anyhow::Result;
+use clap::Args;
+use org_core::OrgMode;
+
+#[derive(Args)]
+pub struct QueryCommand {
+    /// Query expression, e.g. '(and (todo) (tags "work") (scheduled :to today))'
+    query: String,
+
+    /// Maximum number of results to return
+    #[arg(short, long)]
+    limit: Option<usize>,
+
+    /// Output format
+    #[arg(short = 'f', long)]
+    format: Option<OutputFormat>,
+}
+
+#[derive(clap::ValueEnum, Clone)]
+enum OutputFormat {
+    Plain,
+    Json,
+}
+
+impl QueryCommand {
+    pub fn execute(&self, org_mode: OrgMode, cli: CliConfig) -> Result<()> {
+        let format = self.format.as_ref().unwrap_or({
+            match cli.default_format.as_str() {
+                "json" => &OutputFormat::Json,
+                _ => &OutputFormat::Plain,
+            }
+        });
+
+        let items = org_mode.query(&self.query, self.limit)?;
+
+        match format {
+            OutputFormat::Plain => {
+                if items.is_empty() {
+                    println!("No headings match the query");
+                }
+                for item in &items {
+                    let mut prefix = String::new();
+                    if let Some(ref state) = item.todo_state {
+                        prefix.push_str(state);
+                        prefix.push(' ');
+                    }
+                    if let Some(ref priority) = item.priority {
+                        prefix.push_str(&format!("[#{priority}] "));
+                    }
+                    println!("  {prefix}{} ({})", item.heading, item.file_path);
+                    if let Some(ref deadline) = item.deadline {
+                        println!("    DEADLINE: {deadline}");
+                    }
+                    if let Some(ref scheduled) = item.scheduled {
+                        println!("    SCHEDULED: {scheduled}");
+                    }
+                }
+            }
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&items)?);
+            }
+        }
+
+        Ok(())
+    }
+}
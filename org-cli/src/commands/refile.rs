@@ -0,0 +1,82 @@
+use crate::config::CliConfig;
+use anyhow::Result;
+use clap::{ArgGroup, Args};
+use org_core::{HeadingTarget, OrgMode, RefileTarget};
+
+#[derive(Args)]
+#[command(group(
+    ArgGroup::new("source")
+        .args(["id", "file"])
+        .required(true)
+        .multiple(true),
+))]
+pub struct RefileCommand {
+    /// Org ID property of the heading to move (wins over --file/--heading)
+    #[arg(long)]
+    id: Option<String>,
+
+    /// File containing the heading to move (requires --heading)
+    #[arg(short = 'F', long, requires = "heading")]
+    file: Option<String>,
+
+    /// Slash-separated path of the heading to move (requires --file)
+    #[arg(long, requires = "file")]
+    heading: Option<String>,
+
+    /// Destination file relative to org directory (must exist)
+    #[arg(long)]
+    to_file: String,
+
+    /// Slash-separated path of the new parent heading (default: top level)
+    #[arg(long)]
+    to_heading: Option<String>,
+
+    /// Output format
+    #[arg(short = 'f', long)]
+    format: Option<OutputFormat>,
+}
+
+#[derive(clap::ValueEnum, Clone)]
+enum OutputFormat {
+    Plain,
+    Json,
+}
+
+impl RefileCommand {
+    pub fn execute(&self, org_mode: OrgMode, cli: CliConfig) -> Result<()> {
+        let source = HeadingTarget {
+            id: self.id.clone(),
+            file: self.file.clone(),
+            heading_path: self.heading.clone(),
+        };
+        let target = RefileTarget {
+            file: self.to_file.clone(),
+            heading_path: self.to_heading.clone(),
+        };
+
+        let result = org_mode.refile(&source, &target)?;
+
+        let format = self.format.as_ref().unwrap_or({
+            match cli.default_format.as_str() {
+                "json" => &OutputFormat::Json,
+                _ => &OutputFormat::Plain,
+            }
+        });
+
+        match format {
+            OutputFormat::Plain => {
+                let destination = match &result.under_target {
+                    Some(parent) => format!("{}#{parent}", result.target_file),
+                    None => result.target_file.clone(),
+                };
+                println!("Refiled from {} to {destination}", result.source_file);
+                println!("  {}", result.heading_line);
+            }
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+        }
+
+        Ok(())
+    }
+}
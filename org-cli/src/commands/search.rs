@@ -1,7 +1,7 @@
 use crate::config::CliConfig;
 use anyhow::Result;
 use clap::Args;
-use org_core::OrgMode;
+use org_core::{OrgMode, SearchElement, SearchMode, SearchOptions};
 
 #[derive(Args)]
 pub struct SearchCommand {
@@ -23,6 +23,27 @@ pub struct SearchCommand {
     /// Filter by tags (comma-separated)
     #[arg(short = 't', long, value_delimiter = ',')]
     tags: Option<Vec<String>>,
+
+    /// Matching: fuzzy, exact, regex, words ('+must -mustnot "some phrase"'),
+    /// or ranked (whole sections by BM25 relevance)
+    #[arg(short = 'm', long, default_value = "fuzzy")]
+    mode: SearchMode,
+
+    /// Match letter case exactly
+    #[arg(long)]
+    case_sensitive: bool,
+
+    /// File, directory or glob to search instead of every org file
+    #[arg(long)]
+    files: Option<String>,
+
+    /// Only search the subtree of this heading path (e.g. "Projects/Website")
+    #[arg(long = "heading")]
+    heading_path: Option<String>,
+
+    /// Only search these line kinds: headline, body, property, src_block, table (comma-separated)
+    #[arg(short = 'e', long, value_delimiter = ',')]
+    elements: Vec<SearchElement>,
 }
 
 #[derive(clap::ValueEnum, Clone)]
@@ -33,16 +54,21 @@ enum OutputFormat {
 
 impl SearchCommand {
     pub fn execute(&self, org_mode: OrgMode, cli: CliConfig) -> Result<()> {
-        let results = if let Some(ref tags) = self.tags {
-            org_mode.search_with_tags(
-                &self.query,
-                Some(tags.as_slice()),
-                self.limit,
-                Some(self.snippet_size),
-            )?
-        } else {
-            org_mode.search(&self.query, self.limit, Some(self.snippet_size))?
+        let options = SearchOptions {
+            mode: self.mode,
+            case_sensitive: self.case_sensitive,
+            tags: self.tags.clone(),
+            files: self.files.clone(),
+            heading_path: self.heading_path.clone(),
+            elements: self.elements.clone(),
+            ..SearchOptions::default()
         };
+        let results = org_mode.search_with_options(
+            &self.query,
+            &options,
+            self.limit,
+            Some(self.snippet_size),
+        )?;
 
         let format = self.format.as_ref().unwrap_or({
             match cli.default_format.as_str() {
@@ -67,10 +93,13 @@ impl SearchCommand {
                         org_mode.config().org_directory
                     );
                     for result in results {
-                        println!(
-                            "{}: {} (score: {})",
-                            result.file_path, result.snippet, result.score
-                        );
+                        let location = match result.heading_path {
+                            Some(ref path) => {
+                                format!("{}:{} [{path}]", result.file_path, result.line)
+                            }
+                            None => format!("{}:{}", result.file_path, result.line),
+                        };
+                        println!("{location}: {} (score: {})", result.snippet, result.score);
                     }
                 }
             }
@@ -53,7 +53,8 @@ pub struct UpdateTodoCommand {
     #[arg(short = 't', long, value_delimiter = ',')]
     tags: Option<Vec<String>>,
 
-    /// New SCHEDULED timestamp (ISO YYYY-MM-DD[ HH:MM] [repeater] [warning])
+    /// New SCHEDULED timestamp (ISO YYYY-MM-DD[ HH:MM[-HH:MM]] [repeater] [warning],
+    /// or a date range YYYY-MM-DD[ HH:MM]--YYYY-MM-DD[ HH:MM])
     #[arg(long)]
     scheduled: Option<String>,
 
@@ -85,6 +86,10 @@ pub struct UpdateTodoCommand {
     #[arg(long = "remove-property", value_name = "KEY")]
     remove_properties: Vec<String>,
 
+    /// Note logged with the state change (requires a todo-state change)
+    #[arg(long)]
+    note: Option<String>,
+
     /// Output format
     #[arg(short = 'f', long)]
     format: Option<OutputFormat>,
@@ -147,6 +152,7 @@ impl UpdateTodoCommand {
             body: self.body.clone(),
             properties,
             remove_properties,
+            note: self.note.clone(),
         };
 
         let result = org_mode.update_todo(entry)?;
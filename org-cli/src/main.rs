@@ -5,8 +5,9 @@ use org_core::OrgMode;
 mod commands;
 mod config;
 use commands::{
-    AgendaCommand, CaptureCommand, ConfigCommand, ElementByIdCommand, HeadingCommand, ListCommand,
-    OutlineCommand, ReadCommand, SearchCommand, UpdateTodoCommand,
+    AgendaCommand, ArchiveCommand, CaptureCommand, ClockCommand, ConfigCommand, ElementByIdCommand,
+    HeadingCommand, LevelCommand, LinksCommand, ListCommand, OutlineCommand, QueryCommand,
+    ReadCommand, RefileCommand, SearchCommand, UpdateTodoCommand,
 };
 use config::CliAppConfig;
 
@@ -31,10 +32,16 @@ struct Cli {
 enum Commands {
     /// Agenda views and task management
     Agenda(AgendaCommand),
+    /// Archive a heading subtree or toggle its ARCHIVE tag
+    Archive(ArchiveCommand),
     /// Capture a new heading into an org file
     Capture(Box<CaptureCommand>),
+    /// Clock time on a heading (in, out, cancel)
+    Clock(ClockCommand),
     /// Configuration management
     Config(ConfigCommand),
+    /// List outgoing links, broken links, or backlinks
+    Links(LinksCommand),
     /// List all .org files in a directory
     List(ListCommand),
     /// Read the contents of an org file
@@ -45,6 +52,14 @@ enum Commands {
     Heading(HeadingCommand),
     /// Extract content from an element by ID across all org files
     ElementById(ElementByIdCommand),
+    /// Promote a heading (and optionally its subtree) by one or more levels
+    Promote(LevelCommand),
+    /// Demote a heading (and optionally its subtree) by one or more levels
+    Demote(LevelCommand),
+    /// Find agenda headings matching a query expression
+    Query(QueryCommand),
+    /// Move a heading subtree under another heading or file
+    Refile(RefileCommand),
     /// Search for text content across all org files using fuzzy matching
     Search(SearchCommand),
     /// Update TODO state and planning metadata of an existing heading
@@ -74,13 +89,20 @@ fn run() -> Result<()> {
             let org_mode = OrgMode::new(config.org)?;
             match cli.command {
                 Commands::Agenda(cmd) => cmd.execute(org_mode, config.cli),
+                Commands::Archive(cmd) => cmd.execute(org_mode, config.cli),
                 Commands::Capture(cmd) => cmd.execute(org_mode, config.cli),
+                Commands::Clock(cmd) => cmd.execute(org_mode, config.cli),
                 Commands::Config(_) => unreachable!(),
+                Commands::Links(cmd) => cmd.execute(org_mode, config.cli),
                 Commands::List(cmd) => cmd.execute(org_mode, config.cli),
                 Commands::Read(cmd) => cmd.execute(org_mode, config.cli),
                 Commands::Outline(cmd) => cmd.execute(org_mode, config.cli),
                 Commands::Heading(cmd) => cmd.execute(org_mode, config.cli),
                 Commands::ElementById(cmd) => cmd.execute(org_mode, config.cli),
+                Commands::Promote(cmd) => cmd.execute(org_mode, config.cli, -1),
+                Commands::Demote(cmd) => cmd.execute(org_mode, config.cli, 1),
+                Commands::Query(cmd) => cmd.execute(org_mode, config.cli),
+                Commands::Refile(cmd) => cmd.execute(org_mode, config.cli),
                 Commands::Search(cmd) => cmd.execute(org_mode, config.cli),
                 Commands::UpdateTodo(cmd) => cmd.execute(org_mode, config.cli),
             }
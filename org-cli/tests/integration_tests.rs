@@ -4,11 +4,21 @@ use std::fs;
 use tempfile::TempDir;
 use test_utils::fixtures::setup_test_org_files_with_dates;
 
+/// `org-cli` keeping its metadata cache in memory, so test runs never write
+/// to the cache directory under `$HOME`.
+macro_rules! org_cli {
+    () => {{
+        let mut cmd = cargo::cargo_bin_cmd!("org-cli");
+        cmd.env("ORG_ORG__ORG_CACHE_DIRECTORY", "");
+        cmd
+    }};
+}
+
 #[test]
 fn test_list_command_basic() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("list")
@@ -26,7 +36,7 @@ fn test_list_command_basic() {
 fn test_list_command_json_format() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("list")
@@ -44,7 +54,7 @@ fn test_list_command_json_format() {
 fn test_list_command_empty_directory() {
     let temp_dir = TempDir::new().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("list")
@@ -55,7 +65,7 @@ fn test_list_command_empty_directory() {
 
 #[test]
 fn test_list_command_invalid_directory() {
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg("/nonexistent/directory")
         .arg("list")
@@ -68,7 +78,7 @@ fn test_list_command_invalid_directory() {
 fn test_read_command_basic() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("read")
@@ -85,7 +95,7 @@ fn test_read_command_basic() {
 fn test_read_command_nonexistent_file() {
     let temp_dir = TempDir::new().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("read")
@@ -98,7 +108,7 @@ fn test_read_command_nonexistent_file() {
 fn test_outline_command_basic() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("outline")
@@ -114,7 +124,7 @@ fn test_outline_command_basic() {
 fn test_outline_command_json_format() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("outline")
@@ -132,7 +142,7 @@ fn test_outline_command_json_format() {
 fn test_heading_command_basic() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("heading")
@@ -150,7 +160,7 @@ fn test_heading_command_basic() {
 fn test_heading_command_nested() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("heading")
@@ -166,7 +176,7 @@ fn test_heading_command_nested() {
 fn test_heading_command_nonexistent() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("heading")
@@ -181,7 +191,7 @@ fn test_heading_command_nonexistent() {
 fn test_element_by_id_command_heading() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("element-by-id")
@@ -198,7 +208,7 @@ fn test_element_by_id_command_heading() {
 fn test_element_by_id_command_document_level() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("element-by-id")
@@ -213,7 +223,7 @@ fn test_element_by_id_command_document_level() {
 fn test_element_by_id_command_nonexistent() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("element-by-id")
@@ -225,7 +235,7 @@ fn test_element_by_id_command_nonexistent() {
 
 #[test]
 fn test_help_command() {
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--help")
         .assert()
         .success()
@@ -240,7 +250,7 @@ fn test_help_command() {
 
 #[test]
 fn test_version_command() {
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--version")
         .assert()
         .success()
@@ -249,7 +259,7 @@ fn test_version_command() {
 
 #[test]
 fn test_invalid_command() {
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("invalid-command")
         .assert()
         .failure()
@@ -260,7 +270,7 @@ fn test_invalid_command() {
 fn test_search_command_basic() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -276,7 +286,7 @@ fn test_search_command_basic() {
 fn test_search_command_with_limit() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -292,7 +302,7 @@ fn test_search_command_with_limit() {
 fn test_search_command_json_format() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -314,7 +324,7 @@ fn test_search_command_json_format() {
 fn test_search_command_custom_snippet_size() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -332,7 +342,7 @@ fn test_search_command_custom_snippet_size() {
 fn test_search_command_no_results() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -346,7 +356,7 @@ fn test_search_command_no_results() {
 fn test_search_command_empty_query() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -358,7 +368,7 @@ fn test_search_command_empty_query() {
 
 #[test]
 fn test_search_command_invalid_directory() {
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg("/nonexistent/directory")
         .arg("search")
@@ -372,7 +382,7 @@ fn test_search_command_invalid_directory() {
 fn test_search_command_all_parameters() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -392,7 +402,7 @@ fn test_search_command_all_parameters() {
 
 #[test]
 fn test_search_command_help() {
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("search")
         .arg("--help")
         .assert()
@@ -409,7 +419,7 @@ fn test_config_init_creates_file() {
     let temp_dir = TempDir::new().unwrap();
     let config_path = temp_dir.path().join("org-mcp/config.toml");
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .env("XDG_CONFIG_HOME", temp_dir.path().to_str().unwrap())
         .arg("config")
         .arg("init")
@@ -430,7 +440,7 @@ fn test_config_init_file_already_exists() {
 
     fs::write(&config_path, "[org]\norg_directory = \"/test\"").unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .env("XDG_CONFIG_HOME", temp_dir.path().to_str().unwrap())
         .arg("config")
         .arg("init")
@@ -444,7 +454,7 @@ fn test_config_init_file_already_exists() {
 fn test_config_show_displays_config() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .env("ORG_ORG__ORG_DIRECTORY", temp_dir.path().to_str().unwrap())
         .arg("config")
         .arg("show")
@@ -459,7 +469,7 @@ fn test_config_show_displays_config() {
 #[test]
 #[cfg(target_os = "linux")]
 fn test_config_show_fallback_to_default() {
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .env("XDG_CONFIG_HOME", "/nonexistent/path")
         .env("HOME", "/nonexistent/home")
         .arg("config")
@@ -475,7 +485,7 @@ fn test_config_show_fallback_to_default() {
 fn test_config_path_shows_location() {
     let temp_dir = TempDir::new().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .env("XDG_CONFIG_HOME", temp_dir.path().to_str().unwrap())
         .arg("config")
         .arg("path")
@@ -491,7 +501,7 @@ fn test_config_init_creates_file_with_explicit_path() {
     let temp_dir = TempDir::new().unwrap();
     let config_path = temp_dir.path().join("custom-config.toml");
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("config")
@@ -510,7 +520,7 @@ fn test_config_init_file_already_exists_with_explicit_path() {
 
     fs::write(&config_path, "[org]\norg_directory = \"/test\"").unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("config")
@@ -543,7 +553,7 @@ default_format = "plain"
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("config")
@@ -560,7 +570,7 @@ fn test_config_path_with_explicit_flag() {
     let temp_dir = TempDir::new().unwrap();
     let config_path = temp_dir.path().join("my-config.toml");
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("config")
@@ -589,7 +599,7 @@ default_format = "json"
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("list")
@@ -624,7 +634,7 @@ default_format = "plain"
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .env("ORG_LOGGING__LEVEL", "debug")
         .env("ORG_ORG__ORG_DIRECTORY", temp_dir.path().to_str().unwrap())
         .arg("--config")
@@ -652,7 +662,7 @@ fn test_config_respects_home_env() {
     fs::create_dir_all(&config_dir).unwrap();
 
     // Test that config path changes when HOME changes
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .env("HOME", temp_home.path().to_str().unwrap())
         .env_remove("XDG_CONFIG_HOME") // Remove to test HOME fallback
         .arg("config")
@@ -678,7 +688,7 @@ fn test_config_init_with_home_env() {
 
     let expected_config_path = config_dir.join("org-mcp/config.toml");
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .env("HOME", temp_home.path().to_str().unwrap())
         .env_remove("XDG_CONFIG_HOME")
         .arg("config")
@@ -723,7 +733,7 @@ level = "trace"
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .env("HOME", temp_home.path().to_str().unwrap())
         .env_remove("XDG_CONFIG_HOME")
         .arg("config")
@@ -740,7 +750,7 @@ level = "trace"
 fn test_list_command_with_single_tag() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("list")
@@ -758,7 +768,7 @@ fn test_list_command_with_single_tag() {
 fn test_list_command_with_multiple_tags() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("list")
@@ -773,7 +783,7 @@ fn test_list_command_with_multiple_tags() {
 fn test_list_command_with_tags_json_format() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("list")
@@ -792,7 +802,7 @@ fn test_list_command_with_tags_json_format() {
 fn test_list_command_with_nonexistent_tag() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("list")
@@ -806,11 +816,106 @@ fn test_list_command_with_nonexistent_tag() {
         );
 }
 
+#[test]
+fn test_search_command_modes() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("search")
+        .arg("bug in (authentication|dashboard)")
+        .arg("--mode")
+        .arg("regex")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Critical bug in authentication"))
+        .stdout(predicate::str::contains("Minor UI bug in dashboard"));
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("search")
+        .arg("CRITICAL BUG")
+        .arg("--mode")
+        .arg("exact")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Critical bug in authentication"));
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("search")
+        .arg("CRITICAL BUG")
+        .arg("--mode")
+        .arg("exact")
+        .arg("--case-sensitive")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No results found"));
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("search")
+        .arg("bug")
+        .arg("--mode")
+        .arg("soundex")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid search mode"));
+}
+
+#[test]
+fn test_search_command_scopes() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("search")
+        .arg("bug")
+        .arg("--mode")
+        .arg("exact")
+        .arg("--elements")
+        .arg("headline")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 1 results"))
+        .stdout(predicate::str::contains("search_test.org:10 [Bug Reports]"));
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("search")
+        .arg("bug")
+        .arg("--mode")
+        .arg("exact")
+        .arg("--files")
+        .arg("search_test.org")
+        .arg("--heading")
+        .arg("Project Planning")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No results found"));
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("search")
+        .arg("bug")
+        .arg("--heading")
+        .arg("Nowhere")
+        .assert()
+        .failure();
+}
+
 #[test]
 fn test_search_command_with_single_tag() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -826,7 +931,7 @@ fn test_search_command_with_single_tag() {
 fn test_search_command_with_multiple_tags() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -841,7 +946,7 @@ fn test_search_command_with_multiple_tags() {
 fn test_search_command_with_tags_and_limit() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -858,7 +963,7 @@ fn test_search_command_with_tags_and_limit() {
 fn test_search_command_with_tags_json_format() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -878,7 +983,7 @@ fn test_search_command_with_tags_json_format() {
 fn test_search_command_with_tags_no_match() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -894,7 +999,7 @@ fn test_search_command_with_tags_no_match() {
 fn test_search_command_with_tags_all_parameters() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("search")
@@ -929,7 +1034,7 @@ org_agenda_files = ["agenda.org", "project.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -956,7 +1061,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -988,7 +1093,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1016,7 +1121,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1042,7 +1147,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1074,7 +1179,7 @@ org_agenda_files = ["agenda.org"]
     let start_date = today.format("%Y-%m-%d").to_string();
     let end_date = week_later.format("%Y-%m-%d").to_string();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1088,6 +1193,108 @@ org_agenda_files = ["agenda.org"]
         .stdout(predicate::str::contains("Agenda"));
 }
 
+#[test]
+fn test_agenda_range_groups_entries_by_day() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("week.org"),
+        "* TODO Weekly sync\n  SCHEDULED: <2030-01-07 Mon 10:00 +1w>\n\
+         * TODO Ship release\n  DEADLINE: <2030-01-09 Wed>\n",
+    )
+    .unwrap();
+
+    let output = org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("agenda")
+        .arg("range")
+        .arg("--start")
+        .arg("2030-01-07")
+        .arg("--end")
+        .arg("2030-01-16")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+
+    let positions: Vec<usize> = [
+        "  2030-01-07 Mon",
+        "    10:00 \"TODO\" Weekly sync",
+        "  2030-01-09 Wed",
+        "Ship release (week.org) [deadline]",
+        "  2030-01-14 Mon",
+        "Weekly sync (week.org) [repeat]",
+    ]
+    .iter()
+    .map(|needle| {
+        stdout
+            .find(needle)
+            .unwrap_or_else(|| panic!("{needle:?} missing from:\n{stdout}"))
+    })
+    .collect();
+    assert!(positions.is_sorted(), "Unexpected order:\n{stdout}");
+    assert!(
+        !stdout.contains("2030-01-08"),
+        "Empty days are skipped:\n{stdout}"
+    );
+}
+
+#[test]
+fn test_agenda_list_command_with_sort() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("tasks.org"),
+        "* TODO Apply for visa\n* TODO [#A] Renew passport\n* TODO Book hotel\n",
+    )
+    .unwrap();
+
+    let list = |sort: &str| {
+        let output = org_cli!()
+            .arg("--root-directory")
+            .arg(temp_dir.path().to_str().unwrap())
+            .arg("agenda")
+            .arg("list")
+            .arg("--sort")
+            .arg(sort)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let stdout = String::from_utf8(output).unwrap();
+        let mut headings: Vec<(usize, &str)> = ["Apply for visa", "Renew passport", "Book hotel"]
+            .into_iter()
+            .map(|heading| (stdout.find(heading).unwrap(), heading))
+            .collect();
+        headings.sort();
+        headings.into_iter().map(|(_, h)| h).collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        list("alpha-down"),
+        ["Renew passport", "Book hotel", "Apply for visa"]
+    );
+    assert_eq!(
+        list("priority-down,alpha-up"),
+        ["Renew passport", "Apply for visa", "Book hotel"]
+    );
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("agenda")
+        .arg("list")
+        .arg("--sort")
+        .arg("alpha-sideways")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "invalid sort key 'alpha-sideways'",
+        ));
+}
+
 #[test]
 fn test_agenda_range_command_invalid_start() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
@@ -1104,7 +1311,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1134,7 +1341,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1164,7 +1371,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1192,7 +1399,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1220,7 +1427,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1231,6 +1438,46 @@ org_agenda_files = ["agenda.org"]
         .success();
 }
 
+#[test]
+fn test_agenda_list_match_string() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    let config_path = temp_dir.path().join("config.toml");
+    let path_str = temp_dir.path().to_str().unwrap().replace('\\', "/");
+    let config_content = format!(
+        r#"
+[org]
+org_directory = "{}"
+org_agenda_files = ["agenda.org"]
+"#,
+        path_str
+    );
+    fs::write(&config_path, config_content).unwrap();
+
+    org_cli!()
+        .arg("--config")
+        .arg(config_path.to_str().unwrap())
+        .arg("agenda")
+        .arg("list")
+        .arg("--match")
+        .arg("+work+review-urgent/!TODO")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Review documentation"))
+        .stdout(predicate::str::contains("Code review session").not());
+
+    org_cli!()
+        .arg("--config")
+        .arg(config_path.to_str().unwrap())
+        .arg("agenda")
+        .arg("list")
+        .arg("--match")
+        .arg("EFFORT>")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid query"));
+}
+
 #[test]
 fn test_agenda_list_empty_agenda_files() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
@@ -1247,7 +1494,7 @@ org_agenda_files = ["empty.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1276,7 +1523,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1291,7 +1538,7 @@ org_agenda_files = ["agenda.org"]
 
 #[test]
 fn test_agenda_help() {
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("agenda")
         .arg("--help")
         .assert()
@@ -1319,7 +1566,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1348,7 +1595,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1382,7 +1629,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1412,7 +1659,7 @@ org_agenda_files = ["agenda.org"]
 
     // "Team meeting" has an active body timestamp for today but no SCHEDULED or DEADLINE,
     // so it exercises the (None, None) arm in print_agenda_view.
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1438,7 +1685,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1468,7 +1715,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1497,7 +1744,7 @@ org_agenda_files = ["agenda.org"]
     fs::write(&config_path, config_content).unwrap();
 
     // Test with limit of 1
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1532,7 +1779,7 @@ default_format = "plain"
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1560,7 +1807,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1588,7 +1835,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1617,7 +1864,7 @@ org_agenda_files = ["agenda.org"]
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -1635,7 +1882,7 @@ org_agenda_files = ["agenda.org"]
 fn test_capture_command_basic() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1655,7 +1902,7 @@ fn test_capture_command_basic() {
 fn test_capture_command_json_format() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1674,7 +1921,7 @@ fn test_capture_command_json_format() {
 fn test_capture_command_with_todo_state() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1692,7 +1939,7 @@ fn test_capture_command_with_todo_state() {
 fn test_capture_command_with_priority() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1710,7 +1957,7 @@ fn test_capture_command_with_priority() {
 fn test_capture_command_with_tags() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1728,7 +1975,7 @@ fn test_capture_command_with_tags() {
 fn test_capture_command_with_body() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1749,7 +1996,7 @@ fn test_capture_command_with_body() {
 fn test_capture_command_with_scheduled() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1769,7 +2016,7 @@ fn test_capture_command_with_scheduled() {
 fn test_capture_command_with_deadline() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1789,7 +2036,7 @@ fn test_capture_command_with_deadline() {
 fn test_capture_command_with_properties() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1815,7 +2062,7 @@ fn test_capture_command_with_target_heading() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
     fs::write(temp_dir.path().join("target.org"), "* Projects\nIntro.\n").unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1833,7 +2080,7 @@ fn test_capture_command_with_target_heading() {
 fn test_capture_command_with_datetree() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1857,7 +2104,7 @@ fn test_capture_command_with_datetree() {
 fn test_capture_command_invalid_todo() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1875,7 +2122,7 @@ fn test_capture_command_invalid_todo() {
 fn test_capture_command_invalid_property() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1893,7 +2140,7 @@ fn test_capture_command_invalid_property() {
 fn test_capture_command_with_level() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1911,7 +2158,7 @@ fn test_capture_command_with_level() {
 fn test_capture_command_with_closed() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("capture")
@@ -1945,7 +2192,7 @@ default_format = "json"
     );
     fs::write(&config_path, config_content).unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("capture")
@@ -1960,7 +2207,7 @@ default_format = "json"
 
 #[test]
 fn test_capture_help() {
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("capture")
         .arg("--help")
         .assert()
@@ -1988,7 +2235,7 @@ org_agenda_files = ["agenda.org"]
 
     // The agenda.org fixture has a task scheduled @TODAY-21@ — it must appear
     // in today's output with a "Sched. Xd ago" annotation.
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -2018,7 +2265,7 @@ org_agenda_files = ["agenda.org"]
     // SCHEDULED.  It must appear in today's output with a plain "Xd ago"
     // annotation (no "Sched." prefix), exercising the deadline-only overdue
     // branch of the CLI formatter.
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--config")
         .arg(config_path.to_str().unwrap())
         .arg("agenda")
@@ -2029,11 +2276,40 @@ org_agenda_files = ["agenda.org"]
         .stdout(predicate::str::contains("14d ago").or(predicate::str::contains("15d ago")));
 }
 
+#[test]
+fn test_agenda_today_shows_upcoming_deadline_annotation() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    let config_path = temp_dir.path().join("config.toml");
+    let path_str = temp_dir.path().to_str().unwrap().replace('\\', "/");
+    let config_content = format!(
+        r#"
+[org]
+org_directory = "{path_str}"
+org_agenda_files = ["agenda.org"]
+org_deadline_warning_days = 3
+"#
+    );
+    fs::write(&config_path, config_content).unwrap();
+
+    // "Buy groceries" is due in two days, "Complete quarterly report" in six.
+    org_cli!()
+        .arg("--config")
+        .arg(config_path.to_str().unwrap())
+        .arg("agenda")
+        .arg("today")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Buy groceries"))
+        .stdout(predicate::str::contains("(due in 2d)"))
+        .stdout(predicate::str::contains("Complete quarterly report").not());
+}
+
 #[test]
 fn test_update_todo_by_id_plain_output() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("update-todo")
@@ -2055,7 +2331,7 @@ fn test_update_todo_by_id_plain_output() {
 fn test_update_todo_json_output() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("update-todo")
@@ -2078,7 +2354,7 @@ fn test_update_todo_json_output() {
 fn test_update_todo_not_found_fails() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("update-todo")
@@ -2094,7 +2370,7 @@ fn test_update_todo_not_found_fails() {
 fn test_update_todo_missing_target_is_usage_error() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("update-todo")
@@ -2111,7 +2387,7 @@ fn test_update_todo_missing_target_is_usage_error() {
 fn test_update_todo_missing_mutation_is_usage_error() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("update-todo")
@@ -2130,7 +2406,7 @@ fn test_update_todo_missing_mutation_is_usage_error() {
 fn test_update_todo_domain_error_has_no_backtrace() {
     let temp_dir = setup_test_org_files_with_dates().unwrap();
 
-    cargo::cargo_bin_cmd!("org-cli")
+    org_cli!()
         .arg("--root-directory")
         .arg(temp_dir.path().to_str().unwrap())
         .arg("update-todo")
@@ -2144,3 +2420,358 @@ fn test_update_todo_domain_error_has_no_backtrace() {
         .stderr(predicate::str::contains("Error: Heading not found"))
         .stderr(predicate::str::contains("Stack backtrace").not());
 }
+
+#[test]
+fn test_clock_in_and_out_by_id() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("clock")
+        .arg("in")
+        .arg("--id")
+        .arg("task-groceries-456")
+        .arg("--at")
+        .arg("2026-05-15 09:00")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Clocked in: notes.org"))
+        .stdout(predicate::str::contains("CLOCK: [2026-05-15 Fri 09:00]"));
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("clock")
+        .arg("out")
+        .arg("--file")
+        .arg("notes.org")
+        .arg("--heading")
+        .arg("Daily Tasks/Buy groceries")
+        .arg("--at")
+        .arg("2026-05-15 10:30")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"action\": \"out\""))
+        .stdout(predicate::str::contains("\"duration_minutes\": 90"));
+
+    let content = fs::read_to_string(temp_dir.path().join("notes.org")).unwrap();
+    assert!(content.contains(
+        "   :LOGBOOK:\n   CLOCK: [2026-05-15 Fri 09:00]--[2026-05-15 Fri 10:30] =>  1:30\n   :END:"
+    ));
+}
+
+#[test]
+fn test_clock_in_twice_fails() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("clock")
+        .arg("in")
+        .arg("--id")
+        .arg("task-groceries-456")
+        .assert()
+        .success();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("clock")
+        .arg("in")
+        .arg("--id")
+        .arg("task-book-789")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("A clock is already running"));
+}
+
+#[test]
+fn test_clock_cancel_without_running_clock_fails() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("clock")
+        .arg("cancel")
+        .arg("--id")
+        .arg("task-groceries-456")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No running clock"));
+}
+
+#[test]
+fn test_clock_report_groups_by_file() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    for (action, at) in [("in", "2026-05-15 09:00"), ("out", "2026-05-15 10:30")] {
+        org_cli!()
+            .arg("--root-directory")
+            .arg(temp_dir.path().to_str().unwrap())
+            .arg("clock")
+            .arg(action)
+            .arg("--id")
+            .arg("task-groceries-456")
+            .arg("--at")
+            .arg(at)
+            .assert()
+            .success();
+    }
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("clock")
+        .arg("report")
+        .arg("--start")
+        .arg("2026-05-11")
+        .arg("--end")
+        .arg("2026-05-17")
+        .arg("--group-by")
+        .arg("file")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Clock report (2026-05-11 to 2026-05-17) by file - total 1:30",
+        ))
+        .stdout(predicate::str::contains("1:30  notes.org"));
+}
+
+#[test]
+fn test_clock_report_invalid_group_by_fails() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("clock")
+        .arg("report")
+        .arg("--group-by")
+        .arg("project")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid group_by 'project'"));
+}
+
+#[test]
+fn test_refile_under_heading_in_same_file() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("refile")
+        .arg("--id")
+        .arg("task-groceries-456")
+        .arg("--to-file")
+        .arg("notes.org")
+        .arg("--to-heading")
+        .arg("Meeting Notes/2024-01-15 Team Standup")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Refiled from notes.org to notes.org#Meeting Notes/2024-01-15 Team Standup",
+        ))
+        .stdout(predicate::str::contains("*** TODO Buy groceries"));
+
+    let content = fs::read_to_string(temp_dir.path().join("notes.org")).unwrap();
+    let standup = content.find("** 2024-01-15 Team Standup").unwrap();
+    let moved = content.find("*** TODO Buy groceries").unwrap();
+    assert!(moved > standup);
+    assert_eq!(content.matches("Buy groceries").count(), 1);
+}
+
+#[test]
+fn test_refile_missing_target_file_fails() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("refile")
+        .arg("--id")
+        .arg("task-groceries-456")
+        .arg("--to-file")
+        .arg("does-not-exist.org")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("file does not exist"));
+}
+
+#[test]
+fn test_archive_subtree_to_archive_file() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("archive")
+        .arg("--id")
+        .arg("task-book-789")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Archived from notes.org to notes.org_archive",
+        ))
+        .stdout(predicate::str::contains("* DONE Read book"));
+
+    let notes = fs::read_to_string(temp_dir.path().join("notes.org")).unwrap();
+    assert!(!notes.contains("Read book"));
+    let archive = fs::read_to_string(temp_dir.path().join("notes.org_archive")).unwrap();
+    assert!(archive.contains(":ARCHIVE_TODO: DONE"));
+    assert!(archive.contains(":ARCHIVE_CATEGORY: notes"));
+}
+
+#[test]
+fn test_archive_tag_mode_toggles_tag() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("archive")
+        .arg("--file")
+        .arg("notes.org")
+        .arg("--heading")
+        .arg("Daily Tasks/Read book")
+        .arg("--mode")
+        .arg("tag")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tagged as ARCHIVE: notes.org"))
+        .stdout(predicate::str::contains("** DONE Read book :ARCHIVE:"));
+
+    let notes = fs::read_to_string(temp_dir.path().join("notes.org")).unwrap();
+    assert!(notes.contains("** DONE Read book :ARCHIVE:"));
+    assert!(!temp_dir.path().join("notes.org_archive").exists());
+}
+
+#[test]
+fn test_promote_subtree() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("promote")
+        .arg("--id")
+        .arg("task-groceries-456")
+        .arg("--subtree")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Updated notes.org"))
+        .stdout(predicate::str::contains("level: 2 -> 1"));
+
+    let content = fs::read_to_string(temp_dir.path().join("notes.org")).unwrap();
+    assert!(content.contains("\n* TODO Buy groceries\n"));
+}
+
+#[test]
+fn test_promote_past_level_one_fails() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("promote")
+        .arg("--file")
+        .arg("notes.org")
+        .arg("--heading")
+        .arg("Daily Tasks")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid heading level: 0"));
+}
+
+#[test]
+fn test_demote_heading_by_two_levels() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("demote")
+        .arg("--id")
+        .arg("task-book-789")
+        .arg("--levels")
+        .arg("2")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("**** DONE Read book"));
+}
+
+#[test]
+fn test_links_broken_across_directory() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+    fs::write(
+        temp_dir.path().join("links.org"),
+        "* Refs\n[[id:task-book-789][Book]] [[file:missing.org]] [[id:nope]]\n",
+    )
+    .unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("links")
+        .arg("--broken")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("links.org:2"))
+        .stdout(predicate::str::contains("file:missing.org"))
+        .stdout(predicate::str::contains("id:nope"))
+        .stdout(predicate::str::contains("id:task-book-789").not());
+}
+
+#[test]
+fn test_links_backlinks_to_id() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+    fs::write(
+        temp_dir.path().join("links.org"),
+        "* Refs\nSee [[id:task-book-789][the book]].\n",
+    )
+    .unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("links")
+        .arg("--to")
+        .arg("task-book-789")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("links.org:2 (Refs)"))
+        .stdout(predicate::str::contains(
+            "See [[id:task-book-789][the book]].",
+        ));
+}
+
+#[test]
+fn test_query_command() {
+    let temp_dir = setup_test_org_files_with_dates().unwrap();
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("query")
+        .arg("(and (todo) (tags-all \"work\" \"review\") (heading \"code\"))")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "TODO [#A] Code review session (agenda.org)",
+        ))
+        .stdout(predicate::str::contains("Review documentation").not());
+
+    org_cli!()
+        .arg("--root-directory")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("query")
+        .arg("(todo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid query: missing ')'"));
+}
@@ -44,8 +44,15 @@ impl Traverser for TodoList {
                 deadline: headline.deadline().map(|d| d.raw()),
                 scheduled: headline.scheduled().map(|d| d.raw()),
                 tags: vec![],
+                inherited_tags: vec![],
+                category: "notes".to_string(),
+                properties: Default::default(),
                 position: Some(Position { start: 10, end: 20 }),
                 days_overdue: None,
+                days_until_deadline: None,
+                start: None,
+                end: None,
+                duration_minutes: None,
             };
             self.tasks.push(task);
         }
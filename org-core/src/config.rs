@@ -1,6 +1,7 @@
-use std::{fs, io, path::PathBuf};
+use std::{fs, io, path::PathBuf, str::FromStr};
 
 use crate::OrgModeError;
+use crate::org_mode::AgendaSortKey;
 use config::{
     Config as ConfigRs, ConfigError, Environment, File,
     builder::{ConfigBuilder, DefaultState},
@@ -21,10 +22,93 @@ pub struct OrgConfig {
     pub org_agenda_text_search_extra_files: Vec<String>,
     #[serde(default = "default_todo_keywords")]
     pub org_todo_keywords: Vec<String>,
+    /// Tags that stay on the heading carrying them instead of passing down
+    /// to its children.
+    #[serde(default)]
+    pub org_tags_exclude_from_inheritance: Vec<String>,
+    /// Property keys whose values pass down from a heading to its children.
+    #[serde(default)]
+    pub org_use_property_inheritance: Vec<String>,
     #[serde(default = "default_org_auto_created_property")]
     pub org_auto_created_property: bool,
     #[serde(default = "default_org_auto_closed_timestamp")]
     pub org_auto_closed_timestamp: bool,
+    #[serde(default = "default_org_archive_location")]
+    pub org_archive_location: String,
+    #[serde(default = "default_org_cache_directory")]
+    pub org_cache_directory: String,
+    #[serde(default = "default_org_log_into_drawer")]
+    pub org_log_into_drawer: bool,
+    /// Days before a deadline its open task shows up in agenda views that
+    /// include today, unless the timestamp sets its own `-Nd` period.
+    #[serde(default = "default_org_deadline_warning_days")]
+    pub org_deadline_warning_days: u32,
+    /// How agenda views and task lists are ordered, key by key.
+    #[serde(default = "default_org_agenda_sorting_strategy")]
+    pub org_agenda_sorting_strategy: Vec<AgendaSortKey>,
+}
+
+/// What a state change records, from the `!` and `@` markers of a keyword
+/// spec such as `WAIT(w@/!)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StateLogging {
+    #[default]
+    None,
+    /// `!`: a timestamped state note.
+    Time,
+    /// `@`: a timestamped state note carrying the update's `note`.
+    Note,
+}
+
+impl StateLogging {
+    fn parse(marker: &str) -> Option<Self> {
+        match marker {
+            "" => Some(Self::None),
+            "!" => Some(Self::Time),
+            "@" | "@!" | "!@" => Some(Self::Note),
+            _ => None,
+        }
+    }
+}
+
+/// An `org_todo_keywords` entry: `WAIT(w@/!)` is the keyword `WAIT` with
+/// fast-access key `w`, logging a note on entry and a timestamp on exit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoKeyword {
+    pub name: String,
+    pub key: Option<char>,
+    pub on_enter: StateLogging,
+    pub on_leave: StateLogging,
+}
+
+impl FromStr for TodoKeyword {
+    type Err = OrgModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || OrgModeError::ConfigError(format!("Invalid TODO keyword '{s}'"));
+
+        let (name, spec) = match s.split_once('(') {
+            Some((name, rest)) => (name, rest.strip_suffix(')').ok_or_else(invalid)?),
+            None => (s, ""),
+        };
+        if name.is_empty() || name.chars().any(|c| c.is_whitespace() || c == ')') {
+            return Err(invalid());
+        }
+
+        let key = spec
+            .chars()
+            .next()
+            .filter(|c| !matches!(c, '!' | '@' | '/'));
+        let spec = &spec[key.map_or(0, char::len_utf8)..];
+        let (enter, leave) = spec.split_once('/').unwrap_or((spec, ""));
+
+        Ok(Self {
+            name: name.to_string(),
+            key,
+            on_enter: StateLogging::parse(enter).ok_or_else(invalid)?,
+            on_leave: StateLogging::parse(leave).ok_or_else(invalid)?,
+        })
+    }
 }
 
 /// Logging configuration (shared across CLI and server)
@@ -44,8 +128,15 @@ impl Default for OrgConfig {
             org_agenda_files: default_agenda_files(),
             org_agenda_text_search_extra_files: Vec::default(),
             org_todo_keywords: default_todo_keywords(),
+            org_tags_exclude_from_inheritance: Vec::default(),
+            org_use_property_inheritance: Vec::default(),
             org_auto_created_property: default_org_auto_created_property(),
             org_auto_closed_timestamp: default_org_auto_closed_timestamp(),
+            org_archive_location: default_org_archive_location(),
+            org_cache_directory: default_org_cache_directory(),
+            org_log_into_drawer: default_org_log_into_drawer(),
+            org_deadline_warning_days: default_org_deadline_warning_days(),
+            org_agenda_sorting_strategy: default_org_agenda_sorting_strategy(),
         }
     }
 }
@@ -112,6 +203,10 @@ impl OrgConfig {
             }
         }
 
+        for keyword in self.org_todo_keywords.iter().filter(|k| *k != "|") {
+            keyword.parse::<TodoKeyword>()?;
+        }
+
         match fs::read_dir(&root_path) {
             Ok(_) => {}
             Err(e) => {
@@ -129,25 +224,58 @@ impl OrgConfig {
     }
 
     pub fn unfinished_keywords(&self) -> Vec<String> {
-        if let Some(pos) = self.org_todo_keywords.iter().position(|x| x == "|") {
-            self.org_todo_keywords[..pos].to_vec()
+        let keywords = if let Some(pos) = self.org_todo_keywords.iter().position(|x| x == "|") {
+            &self.org_todo_keywords[..pos]
         } else {
-            self.org_todo_keywords[..self.org_todo_keywords.len() - 1].to_vec()
-        }
+            &self.org_todo_keywords[..self.org_todo_keywords.len().saturating_sub(1)]
+        };
+        keywords.iter().map(|k| keyword_name(k)).collect()
     }
 
     pub fn finished_keywords(&self) -> Vec<String> {
-        if let Some(pos) = self.org_todo_keywords.iter().position(|x| x == "|") {
-            self.org_todo_keywords[pos + 1..self.org_todo_keywords.len()].to_vec()
+        let keywords = if let Some(pos) = self.org_todo_keywords.iter().position(|x| x == "|") {
+            &self.org_todo_keywords[pos + 1..]
         } else {
+            let len = self.org_todo_keywords.len();
+            &self.org_todo_keywords[len.saturating_sub(1)..]
+        };
+        keywords.iter().map(|k| keyword_name(k)).collect()
+    }
+
+    /// Every configured keyword name, without separator or logging specs.
+    pub fn todo_keywords(&self) -> Vec<String> {
+        self.org_todo_keywords
+            .iter()
+            .filter(|k| *k != "|")
+            .map(|k| keyword_name(k))
+            .collect()
+    }
+
+    /// What a change from `from` to `to` should log: the target keyword's
+    /// entry setting, falling back to the exit setting of the old one.
+    pub fn state_logging(&self, from: Option<&str>, to: Option<&str>) -> StateLogging {
+        let spec = |name: Option<&str>| {
+            let name = name?;
             self.org_todo_keywords
-                .last()
-                .map(|e| vec![e.clone()])
-                .unwrap_or_default()
+                .iter()
+                .filter_map(|k| k.parse::<TodoKeyword>().ok())
+                .find(|k| k.name == name)
+        };
+        match spec(to).map(|k| k.on_enter) {
+            Some(logging) if logging != StateLogging::None => logging,
+            _ => spec(from).map(|k| k.on_leave).unwrap_or_default(),
         }
     }
 }
 
+/// `WAIT(w@/!)` -> `WAIT`.
+fn keyword_name(keyword: &str) -> String {
+    keyword
+        .split_once('(')
+        .map_or(keyword, |(name, _)| name)
+        .to_string()
+}
+
 /// Get the default configuration file path
 pub fn default_config_path() -> Result<PathBuf, OrgModeError> {
     Ok(default_config_dir()?.join("config"))
@@ -227,6 +355,20 @@ pub fn load_org_config(
         .set_default(
             "org.org_auto_closed_timestamp",
             default_org_auto_closed_timestamp(),
+        )?
+        .set_default("org.org_archive_location", default_org_archive_location())?
+        .set_default("org.org_cache_directory", default_org_cache_directory())?
+        .set_default("org.org_log_into_drawer", default_org_log_into_drawer())?
+        .set_default(
+            "org.org_deadline_warning_days",
+            default_org_deadline_warning_days(),
+        )?
+        .set_default(
+            "org.org_agenda_sorting_strategy",
+            default_org_agenda_sorting_strategy()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
         )?;
 
     let config = build_config_with_file_and_env(config_file, builder)?;
@@ -304,6 +446,30 @@ pub fn default_org_auto_closed_timestamp() -> bool {
     true
 }
 
+pub fn default_org_archive_location() -> String {
+    "%s_archive::".to_string()
+}
+
+pub fn default_org_cache_directory() -> String {
+    "~/.local/share/org-mcp-server/cache".to_string()
+}
+
+pub fn default_org_log_into_drawer() -> bool {
+    true
+}
+
+pub fn default_org_deadline_warning_days() -> u32 {
+    14
+}
+
+pub fn default_org_agenda_sorting_strategy() -> Vec<AgendaSortKey> {
+    vec![
+        AgendaSortKey::TimeUp,
+        AgendaSortKey::PriorityDown,
+        AgendaSortKey::CategoryKeep,
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -324,6 +490,15 @@ mod tests {
         assert_eq!(config.org_default_notes_file, "notes.org");
         assert_eq!(config.org_agenda_files, vec!["**/*.org"]);
         assert!(config.org_agenda_text_search_extra_files.is_empty());
+        assert_eq!(config.org_deadline_warning_days, 14);
+        assert_eq!(
+            config.org_agenda_sorting_strategy,
+            [
+                AgendaSortKey::TimeUp,
+                AgendaSortKey::PriorityDown,
+                AgendaSortKey::CategoryKeep
+            ]
+        );
     }
 
     #[test]
@@ -795,4 +970,155 @@ org_auto_closed_timestamp = false
         let config = load_org_config(Some(config_path.to_str().unwrap()), None).unwrap();
         assert!(!config.org_auto_closed_timestamp);
     }
+
+    #[test]
+    fn test_parse_todo_keyword_specs() {
+        let wait: TodoKeyword = "WAIT(w@/!)".parse().unwrap();
+        assert_eq!(
+            wait,
+            TodoKeyword {
+                name: "WAIT".to_string(),
+                key: Some('w'),
+                on_enter: StateLogging::Note,
+                on_leave: StateLogging::Time,
+            }
+        );
+
+        let todo: TodoKeyword = "TODO(t!)".parse().unwrap();
+        assert_eq!(todo.key, Some('t'));
+        assert_eq!(todo.on_enter, StateLogging::Time);
+        assert_eq!(todo.on_leave, StateLogging::None);
+
+        let done: TodoKeyword = "DONE".parse().unwrap();
+        assert_eq!((done.key, done.on_enter), (None, StateLogging::None));
+
+        assert!("WAIT(w?)".parse::<TodoKeyword>().is_err());
+        assert!("WAIT(w".parse::<TodoKeyword>().is_err());
+        assert!("(w!)".parse::<TodoKeyword>().is_err());
+    }
+
+    #[test]
+    fn test_keyword_lists_strip_specs_and_resolve_logging() {
+        let config = OrgConfig {
+            org_todo_keywords: vec![
+                "TODO(t)".to_string(),
+                "WAIT(w@/!)".to_string(),
+                "|".to_string(),
+                "DONE(d!)".to_string(),
+                "CANCELLED(c)".to_string(),
+            ],
+            ..OrgConfig::default()
+        };
+
+        assert_eq!(config.unfinished_keywords(), vec!["TODO", "WAIT"]);
+        assert_eq!(config.finished_keywords(), vec!["DONE", "CANCELLED"]);
+        assert_eq!(
+            config.todo_keywords(),
+            vec!["TODO", "WAIT", "DONE", "CANCELLED"]
+        );
+
+        let logging = |from, to| config.state_logging(from, to);
+        assert_eq!(logging(Some("TODO"), Some("WAIT")), StateLogging::Note);
+        assert_eq!(logging(Some("TODO"), Some("DONE")), StateLogging::Time);
+        // CANCELLED logs nothing itself, so leaving WAIT decides.
+        assert_eq!(logging(Some("WAIT"), Some("CANCELLED")), StateLogging::Time);
+        assert_eq!(logging(Some("TODO"), Some("CANCELLED")), StateLogging::None);
+        assert_eq!(logging(None, Some("TODO")), StateLogging::None);
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_keyword_spec() {
+        let temp_dir = tempdir().unwrap();
+        let config = OrgConfig {
+            org_directory: temp_dir.path().to_str().unwrap().to_string(),
+            org_todo_keywords: vec!["TODO(t#)".to_string(), "DONE".to_string()],
+            ..OrgConfig::default()
+        };
+
+        assert!(matches!(
+            config.validate(),
+            Err(OrgModeError::ConfigError(msg)) if msg.contains("TODO(t#)")
+        ));
+    }
+
+    #[test]
+    fn test_org_archive_location_default() {
+        let config = OrgConfig::default();
+        assert_eq!(config.org_archive_location, "%s_archive::");
+    }
+
+    #[test]
+    #[serial]
+    fn test_load_archive_location_from_toml() {
+        let temp_dir = tempdir().unwrap();
+        let path_str = test_utils::config::normalize_path(temp_dir.path());
+        let toml_config = format!(
+            r#"
+[org]
+org_directory = "{path_str}"
+org_archive_location = "archive/%s::* Archived Tasks"
+"#,
+        );
+
+        let config_path = test_utils::config::create_toml_config(&temp_dir, &toml_config).unwrap();
+
+        let config = load_org_config(Some(config_path.to_str().unwrap()), None).unwrap();
+        assert_eq!(config.org_archive_location, "archive/%s::* Archived Tasks");
+    }
+
+    #[test]
+    #[serial]
+    fn test_load_cache_directory_from_toml() {
+        let temp_dir = tempdir().unwrap();
+        let path_str = test_utils::config::normalize_path(temp_dir.path());
+        let toml_config = format!(
+            r#"
+[org]
+org_directory = "{path_str}"
+org_cache_directory = ""
+"#,
+        );
+
+        let config_path = test_utils::config::create_toml_config(&temp_dir, &toml_config).unwrap();
+
+        let config = load_org_config(Some(config_path.to_str().unwrap()), None).unwrap();
+        assert_eq!(config.org_cache_directory, "");
+        assert_eq!(
+            OrgConfig::default().org_cache_directory,
+            "~/.local/share/org-mcp-server/cache"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_load_agenda_sorting_strategy_from_toml() {
+        let temp_dir = tempdir().unwrap();
+        let path_str = test_utils::config::normalize_path(temp_dir.path());
+        let toml_config = format!(
+            r#"
+[org]
+org_directory = "{path_str}"
+org_agenda_sorting_strategy = ["deadline-up", "effort-down"]
+"#,
+        );
+
+        let config_path = test_utils::config::create_toml_config(&temp_dir, &toml_config).unwrap();
+
+        let config = load_org_config(Some(config_path.to_str().unwrap()), None).unwrap();
+        assert_eq!(
+            config.org_agenda_sorting_strategy,
+            [AgendaSortKey::DeadlineUp, AgendaSortKey::EffortDown]
+        );
+
+        let toml_config = format!(
+            r#"
+[org]
+org_directory = "{path_str}"
+org_agenda_sorting_strategy = ["time-sideways"]
+"#,
+        );
+        let config_path = test_utils::config::create_toml_config(&temp_dir, &toml_config).unwrap();
+        let err = load_org_config(Some(config_path.to_str().unwrap()), None).unwrap_err();
+        assert!(err.to_string().contains("invalid sort key 'time-sideways'"));
+    }
 }
@@ -25,6 +25,9 @@ pub enum OrgModeError {
     HeadingNotFound(String),
     AmbiguousTarget(String),
     InvalidUpdate(String),
+    ClockAlreadyRunning(String),
+    NoRunningClock(String),
+    InvalidQuery(String),
 }
 
 impl fmt::Display for OrgModeError {
@@ -90,6 +93,13 @@ impl fmt::Display for OrgModeError {
             OrgModeError::InvalidUpdate(reason) => {
                 write!(f, "Invalid update: {reason}")
             }
+            OrgModeError::ClockAlreadyRunning(running) => {
+                write!(f, "A clock is already running: {running}")
+            }
+            OrgModeError::NoRunningClock(target) => {
+                write!(f, "No running clock on heading: {target}")
+            }
+            OrgModeError::InvalidQuery(reason) => write!(f, "Invalid query: {reason}"),
         }
     }
 }
@@ -251,4 +261,31 @@ mod tests {
         );
         assert_eq!(s, "Invalid update: nothing to update");
     }
+
+    #[test]
+    fn test_display_clock_already_running() {
+        let s = format!(
+            "{}",
+            OrgModeError::ClockAlreadyRunning("work.org: * TODO Write report".to_string())
+        );
+        assert_eq!(
+            s,
+            "A clock is already running: work.org: * TODO Write report"
+        );
+    }
+
+    #[test]
+    fn test_display_no_running_clock() {
+        let s = format!("{}", OrgModeError::NoRunningClock("task-123".to_string()));
+        assert_eq!(s, "No running clock on heading: task-123");
+    }
+
+    #[test]
+    fn test_display_invalid_query() {
+        let s = format!(
+            "{}",
+            OrgModeError::InvalidQuery("unknown predicate 'foo'".to_string())
+        );
+        assert_eq!(s, "Invalid query: unknown predicate 'foo'");
+    }
 }
@@ -6,6 +6,7 @@ use tempfile::TempDir;
 fn test_invalid_directory_error() {
     let result = OrgMode::new(OrgConfig {
         org_directory: "/completely/nonexistent/directory/path".to_string(),
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     });
 
@@ -25,6 +26,7 @@ fn test_file_not_found_error() {
     let temp_dir = TempDir::new().unwrap();
     let config = OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
 
@@ -61,6 +63,7 @@ More content.
 
     let config = OrgConfig {
         org_directory: temp_path.to_str().unwrap().to_string(),
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
 
@@ -98,6 +101,7 @@ Some content.
 
     let config = OrgConfig {
         org_directory: temp_path.to_str().unwrap().to_string(),
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
 
@@ -128,6 +132,7 @@ fn test_parsing_error_handling() {
     .unwrap();
     let config = OrgConfig {
         org_directory: temp_path.to_str().unwrap().to_string(),
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
     let org_mode = OrgMode::new(config).unwrap();
@@ -146,6 +151,7 @@ fn test_empty_file_handling() {
     fs::write(temp_path.join("empty.org"), "").unwrap();
     let config = OrgConfig {
         org_directory: temp_path.to_str().unwrap().to_string(),
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
 
@@ -172,6 +178,7 @@ fn test_io_error_propagation() {
     fs::write(temp_path.join("test.org"), "* Test\nContent").unwrap();
     let config = OrgConfig {
         org_directory: temp_path.to_str().unwrap().to_string(),
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
 
@@ -9,6 +9,9 @@ mod error_tests;
 pub use config::{LoggingConfig, OrgConfig};
 pub use error::OrgModeError;
 pub use org_mode::{
-    AgendaItem, AgendaView, CaptureEntry, CaptureResult, ClearField, OrgMode, Priority,
-    PropertyPair, TodoState, UpdateEntry, UpdateResult,
+    AgendaDay, AgendaEntry, AgendaItem, AgendaOptions, AgendaReason, AgendaSortKey, AgendaView,
+    ArchiveMode, ArchiveResult, Backlink, CaptureEntry, CaptureResult, ClearField, ClockAction,
+    ClockGroupBy, ClockReport, ClockReportRow, ClockResult, FileChanges, HeadingTarget, LinkKind,
+    OrgLink, OrgMode, Priority, PropertyPair, RefileResult, RefileTarget, SearchElement,
+    SearchMode, SearchOptions, TodoState, UpdateEntry, UpdateResult,
 };
@@ -1,8 +1,36 @@
-use chrono::{DateTime, Datelike, Days, Duration, Local, Months, NaiveDate, TimeZone};
+use chrono::{DateTime, Datelike, Days, Duration, Local, Months, NaiveDate, NaiveTime, TimeZone};
 
 use crate::OrgModeError;
 use crate::org_mode::{AgendaViewType, OrgMode};
 
+/// Where a timestamp starts and ends. A plain timestamp ends on its start
+/// date without an end time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TimestampSpan {
+    pub(crate) start: NaiveDate,
+    pub(crate) start_time: Option<NaiveTime>,
+    pub(crate) end: NaiveDate,
+    pub(crate) end_time: Option<NaiveTime>,
+}
+
+impl TimestampSpan {
+    /// Whether the timestamp is a time range or a date range.
+    pub(crate) fn is_range(&self) -> bool {
+        self.end != self.start || self.end_time.is_some()
+    }
+
+    /// Minutes between start and end, all-day ranges counting every day
+    /// they cover in full.
+    pub(crate) fn duration_minutes(&self) -> i64 {
+        match (self.start_time, self.end_time) {
+            (Some(start), Some(end)) => {
+                (self.end.and_time(end) - self.start.and_time(start)).num_minutes()
+            }
+            _ => ((self.end - self.start).num_days() + 1) * 24 * 60,
+        }
+    }
+}
+
 impl OrgMode {
     pub(crate) fn to_start_of_day(date: DateTime<Local>) -> DateTime<Local> {
         date.date_naive()
@@ -104,6 +132,70 @@ impl OrgMode {
         .unwrap_or(date)
     }
 
+    /// Days before a deadline its task starts showing up: the `-Nu` warning
+    /// period of the raw timestamp, e.g. `<2025-06-20 Fri -3d>`, else
+    /// `default`. Units convert as in Emacs, a month being 30.4 days.
+    pub(crate) fn deadline_warning_days(raw: &str, default: u32) -> i64 {
+        raw.trim_matches(['<', '>', '[', ']'])
+            .split_whitespace()
+            .find_map(|tok| {
+                let body = tok.strip_prefix("--").or_else(|| tok.strip_prefix('-'))?;
+                let factor = match body.chars().last()? {
+                    'h' => 1.0 / 24.0,
+                    'd' => 1.0,
+                    'w' => 7.0,
+                    'm' => 30.4,
+                    'y' => 365.25,
+                    _ => return None,
+                };
+                let value: u32 = body[..body.len() - 1].parse().ok()?;
+                Some((f64::from(value) * factor).floor() as i64)
+            })
+            .unwrap_or(i64::from(default))
+    }
+
+    /// The span of a raw timestamp, including time ranges such as
+    /// `<2026-05-01 Fri 10:00-11:30>` and date ranges such as
+    /// `<2026-05-01 Fri>--<2026-05-05 Tue>`.
+    pub(crate) fn timestamp_span(raw: &str) -> Option<TimestampSpan> {
+        let raw = raw.trim();
+        let (first, second) = match raw.find("--<").or_else(|| raw.find("--[")) {
+            Some(split) => (&raw[..split], Some(&raw[split + 2..])),
+            None => (raw, None),
+        };
+        let (start, start_time, range_end) = Self::timestamp_bound(first)?;
+        let (end, end_time) = match second {
+            Some(second) => {
+                let (end, end_time, _) = Self::timestamp_bound(second)?;
+                (end, end_time)
+            }
+            None => (start, range_end),
+        };
+        Some(TimestampSpan {
+            start,
+            start_time,
+            end,
+            end_time,
+        })
+    }
+
+    /// Date, time and end time of a single `<...>` timestamp.
+    fn timestamp_bound(raw: &str) -> Option<(NaiveDate, Option<NaiveTime>, Option<NaiveTime>)> {
+        let mut tokens = raw.trim_matches(['<', '>', '[', ']']).split_whitespace();
+        let date = NaiveDate::parse_from_str(tokens.next()?, "%Y-%m-%d").ok()?;
+        let time = |t: &str| NaiveTime::parse_from_str(t, "%H:%M").ok();
+        let (start, end) = match tokens
+            .find(|tok| tok.starts_with(|c: char| c.is_ascii_digit()) && tok.contains(':'))
+        {
+            Some(times) => match times.split_once('-') {
+                Some((start, end)) => (time(start), time(end)),
+                None => (time(times), None),
+            },
+            None => (None, None),
+        };
+        Some((date, start, end))
+    }
+
     pub(crate) fn parse_date_string(
         date_str: &str,
         context: &str,
@@ -318,6 +410,57 @@ mod tests {
         assert_eq!(result.day(), 15);
     }
 
+    #[test]
+    fn test_deadline_warning_days() {
+        assert_eq!(OrgMode::deadline_warning_days("<2025-06-20 Fri>", 14), 14);
+        assert_eq!(
+            OrgMode::deadline_warning_days("<2025-06-20 Fri -3d>", 14),
+            3
+        );
+        assert_eq!(
+            OrgMode::deadline_warning_days("<2025-06-20 Fri 10:00-11:00 +1w --2w>", 0),
+            14
+        );
+        assert_eq!(
+            OrgMode::deadline_warning_days("<2025-06-20 Fri -1m>", 0),
+            30
+        );
+        assert_eq!(
+            OrgMode::deadline_warning_days("<2025-06-20 Fri -36h>", 0),
+            1
+        );
+        assert_eq!(
+            OrgMode::deadline_warning_days("<2025-06-20 Fri -0d>", 14),
+            0
+        );
+    }
+
+    #[test]
+    fn test_timestamp_span() {
+        let date = |d: u32| NaiveDate::from_ymd_opt(2026, 5, d).unwrap();
+        let time = |h: u32, m: u32| NaiveTime::from_hms_opt(h, m, 0);
+
+        let plain = OrgMode::timestamp_span("<2026-05-01 Fri 10:00 +1w>").unwrap();
+        assert_eq!((plain.start, plain.start_time), (date(1), time(10, 0)));
+        assert_eq!((plain.end, plain.end_time), (date(1), None));
+        assert!(!plain.is_range());
+
+        let meeting = OrgMode::timestamp_span("<2026-05-01 Fri 10:00-11:30>").unwrap();
+        assert_eq!(meeting.end_time, time(11, 30));
+        assert!(meeting.is_range());
+        assert_eq!(meeting.duration_minutes(), 90);
+
+        let conference = OrgMode::timestamp_span("<2026-05-01 Fri>--<2026-05-05 Tue>").unwrap();
+        assert_eq!((conference.start, conference.end), (date(1), date(5)));
+        assert_eq!(conference.duration_minutes(), 5 * 24 * 60);
+
+        let trip =
+            OrgMode::timestamp_span("<2026-05-01 Fri 22:00>--<2026-05-02 Sat 06:15>").unwrap();
+        assert_eq!(trip.duration_minutes(), 8 * 60 + 15);
+
+        assert!(OrgMode::timestamp_span("<garbage>").is_none());
+    }
+
     #[test]
     fn test_last_day_of_month_from_day_31() {
         let date = Local.with_ymd_and_hms(2025, 1, 31, 12, 0, 0).unwrap();
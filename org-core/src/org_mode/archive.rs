@@ -0,0 +1,531 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::Local;
+use orgize::export::{Container, Event, from_fn};
+
+use crate::OrgModeError;
+use crate::org_mode::logbook::leading_whitespace;
+use crate::org_mode::properties::{PropertyInheritance, file_category};
+use crate::org_mode::update::{TargetHeadline, detect_newline, join_lines, line_index_at};
+use crate::org_mode::{ArchiveMode, ArchiveResult, HeadingTarget, OrgMode, RefileTarget};
+
+/// Tag toggled by [`OrgMode::toggle_archive_tag`], as in Emacs.
+const ARCHIVE_TAG: &str = "ARCHIVE";
+
+/// Where `org_archive_location` sends the subtrees of one source file.
+#[derive(Debug, PartialEq, Eq)]
+struct ArchiveLocation {
+    file: String,
+    heading: Option<String>,
+}
+
+/// Expands an Emacs-style `file::heading` location for `source_rel`. `%s`
+/// stands for the source file name, a relative file part is resolved next
+/// to the source file, and an empty one means the source file itself. The
+/// heading part names a top-level heading; leading stars are ignored.
+fn parse_archive_location(location: &str, source_rel: &str) -> ArchiveLocation {
+    let (file_part, heading_part) = location.split_once("::").unwrap_or((location, ""));
+    let source = Path::new(source_rel);
+    let name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let file = if file_part.trim().is_empty() {
+        source_rel.to_string()
+    } else {
+        let file = file_part.trim().replace("%s", &name);
+        match source.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => {
+                dir.join(file).to_string_lossy().into_owned()
+            }
+            _ => file,
+        }
+    };
+
+    let heading = heading_part
+        .trim_start_matches('*')
+        .trim()
+        .replace("%s", &name);
+
+    ArchiveLocation {
+        file,
+        heading: (!heading.is_empty()).then_some(heading),
+    }
+}
+
+/// Upserts `props` into the property drawer of the first heading in `lines`
+/// (a detached subtree), creating the drawer after the planning line when
+/// the heading has none.
+fn stamp_properties(lines: &mut Vec<String>, headline: &TargetHeadline, props: &[(&str, String)]) {
+    let first = (headline.property_drawer_first_line - headline.line_idx).min(lines.len());
+    let count = headline.property_drawer_line_count;
+    let indent = if count > 0 {
+        leading_whitespace(&lines[first]).to_string()
+    } else {
+        String::new()
+    };
+
+    let mut merged = headline.existing_properties.clone();
+    for (key, value) in props {
+        match merged.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(key)) {
+            Some(existing) => existing.1 = value.clone(),
+            None => merged.push((key.to_string(), value.clone())),
+        }
+    }
+
+    let mut drawer = vec![format!("{indent}:PROPERTIES:")];
+    drawer.extend(merged.iter().map(|(k, v)| format!("{indent}:{k}: {v}")));
+    drawer.push(format!("{indent}:END:"));
+    lines.splice(first..first + count, drawer);
+}
+
+impl OrgMode {
+    /// Archives the target heading with the given `mode`.
+    pub fn archive(
+        &self,
+        target: &HeadingTarget,
+        mode: ArchiveMode,
+    ) -> Result<ArchiveResult, OrgModeError> {
+        match mode {
+            ArchiveMode::Subtree => self.archive_subtree(target),
+            ArchiveMode::Tag => self.toggle_archive_tag(target),
+        }
+    }
+
+    /// The files `org_archive_location` sends the agenda files' subtrees
+    /// to, as far as they exist.
+    pub(crate) fn agenda_archive_files(&self) -> Vec<String> {
+        let mut files: Vec<String> = self
+            .agenda_files()
+            .iter()
+            .map(|path| {
+                let source_rel = self.relative_to_org_directory(path);
+                parse_archive_location(&self.config.org_archive_location, &source_rel).file
+            })
+            .filter(|file| self.org_path(file).is_file())
+            .collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// Moves the target heading and its subtree to the archive location
+    /// configured by `org_archive_location` (`<file>_archive` by default),
+    /// stamping the `ARCHIVE_*` context properties Emacs records. A missing
+    /// archive file or archive heading is created.
+    pub fn archive_subtree(&self, target: &HeadingTarget) -> Result<ArchiveResult, OrgModeError> {
+        Self::validate_heading_target(target)?;
+        let (source_rel, source_path) = self.resolve_target_file(target)?;
+
+        let location = parse_archive_location(&self.config.org_archive_location, &source_rel);
+        Self::validate_relative_file_path(&location.file).map_err(|e| {
+            OrgModeError::ConfigError(format!(
+                "Invalid org_archive_location '{}': {e}",
+                self.config.org_archive_location
+            ))
+        })?;
+        let archive_path = self.prepare_target_path(&location.file)?;
+
+        Self::with_capture_locks(&source_path, &archive_path, || {
+            let source_content = fs::read_to_string(&source_path).map_err(OrgModeError::IoError)?;
+            let same_file = source_path == archive_path;
+
+            let mut detached = self.detach_subtree(&source_content, target)?;
+            if same_file {
+                let refile_target = RefileTarget {
+                    file: location.file.clone(),
+                    heading_path: location.heading.clone(),
+                };
+                self.check_not_into_itself(&source_content, &detached.headline, &refile_target)?;
+            }
+
+            let props = self.archive_properties(
+                &source_content,
+                &source_rel,
+                &source_path,
+                &detached.headline,
+            );
+            stamp_properties(&mut detached.lines, &detached.headline, &props);
+
+            let remaining = join_lines(&detached.remaining, &source_content);
+            let archive_content = if same_file {
+                remaining.clone()
+            } else if archive_path.is_file() {
+                fs::read_to_string(&archive_path).map_err(OrgModeError::IoError)?
+            } else {
+                let newline = detect_newline(&source_content);
+                format!(
+                    "#\t-*- mode: org -*-{newline}{newline}{newline}\
+                     Archived entries from file {}{newline}{newline}",
+                    source_path.display()
+                )
+            };
+            let archive_content = match location.heading {
+                Some(ref heading) => self.ensure_top_level_heading(archive_content, heading),
+                None => archive_content,
+            };
+
+            let (new_archive, moved) = self.insert_subtree(
+                &archive_content,
+                location.heading.as_deref(),
+                detached.lines,
+            )?;
+
+            // Same ordering as refile: the archive copy lands before the
+            // source loses the subtree.
+            Self::atomic_write(&archive_path, new_archive.as_bytes())?;
+            if !same_file {
+                Self::atomic_write(&source_path, remaining.as_bytes())?;
+            }
+
+            Ok(ArchiveResult {
+                file_path: source_rel.clone(),
+                heading_line: moved.heading_line,
+                mode: ArchiveMode::Subtree,
+                archived: true,
+                archive_file: Some(location.file.clone()),
+            })
+        })
+    }
+
+    /// Adds the `:ARCHIVE:` tag to the target heading, or removes it when
+    /// already present, leaving the subtree where it is.
+    pub fn toggle_archive_tag(
+        &self,
+        target: &HeadingTarget,
+    ) -> Result<ArchiveResult, OrgModeError> {
+        Self::validate_heading_target(target)?;
+        let (file_rel, full_path) = self.resolve_target_file(target)?;
+
+        Self::with_capture_lock(&full_path, || {
+            let content = fs::read_to_string(&full_path).map_err(OrgModeError::IoError)?;
+            let headline = self.locate_target(&content, target)?;
+
+            let mut tags = headline.tags.clone();
+            let archived = match tags.iter().position(|t| t == ARCHIVE_TAG) {
+                Some(idx) => {
+                    tags.remove(idx);
+                    false
+                }
+                None => {
+                    tags.push(ARCHIVE_TAG.to_string());
+                    true
+                }
+            };
+
+            let heading_line = Self::format_heading(
+                headline.level,
+                headline.keyword.as_deref(),
+                headline.priority.as_deref(),
+                &headline.title,
+                Some(&tags),
+            );
+            let mut lines: Vec<String> = content.lines().map(String::from).collect();
+            lines[headline.line_idx] = heading_line.clone();
+            Self::atomic_write(&full_path, join_lines(&lines, &content).as_bytes())?;
+
+            Ok(ArchiveResult {
+                file_path: file_rel.clone(),
+                heading_line,
+                mode: ArchiveMode::Tag,
+                archived,
+                archive_file: None,
+            })
+        })
+    }
+
+    /// Context properties recorded on an archived heading, in Emacs' order.
+    /// Empty values (no parent, no TODO keyword) are left out.
+    fn archive_properties(
+        &self,
+        content: &str,
+        source_rel: &str,
+        source_path: &Path,
+        headline: &TargetHeadline,
+    ) -> Vec<(&'static str, String)> {
+        let olpath = self
+            .headline_sections(content)
+            .into_iter()
+            .find(|section| section.line == headline.line_idx)
+            .and_then(|section| {
+                section
+                    .path
+                    .rsplit_once('/')
+                    .map(|(parents, _)| parents.to_string())
+            });
+        let file_stem = Path::new(source_rel)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let category = self.heading_category(content, &file_stem, headline.line_idx);
+
+        let mut props = vec![
+            (
+                "ARCHIVE_TIME",
+                Local::now().format("%Y-%m-%d %a %H:%M").to_string(),
+            ),
+            ("ARCHIVE_FILE", source_path.display().to_string()),
+        ];
+        if let Some(olpath) = olpath {
+            props.push(("ARCHIVE_OLPATH", olpath));
+        }
+        props.push(("ARCHIVE_CATEGORY", category));
+        if let Some(ref keyword) = headline.keyword {
+            props.push(("ARCHIVE_TODO", keyword.clone()));
+        }
+        props
+    }
+
+    /// The category of the heading on line `line_idx`: its own or its
+    /// nearest ancestor's `:CATEGORY:`, then `#+CATEGORY:`, then `file_stem`.
+    fn heading_category(&self, content: &str, file_stem: &str, line_idx: usize) -> String {
+        let org = self.parse_config().parse(content);
+        let mut inheritance = PropertyInheritance::new(
+            content,
+            file_stem,
+            &self.config.org_use_property_inheritance,
+        );
+        let mut category = None;
+        let mut handler = from_fn(|event| {
+            if let Event::Enter(Container::Headline(ref h)) = event {
+                let properties = h
+                    .properties()
+                    .map(|drawer| {
+                        drawer
+                            .iter()
+                            .map(|(k, v)| (k.to_string(), v.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let (heading_category, _) = inheritance.enter(h.level(), properties);
+                if category.is_none() && line_index_at(content, h.start().into()) == line_idx {
+                    category = Some(heading_category);
+                }
+            }
+        });
+        org.traverse(&mut handler);
+        category
+            .or_else(|| file_category(content))
+            .unwrap_or_else(|| file_stem.to_string())
+    }
+
+    /// Appends `* heading` to `content` unless a top-level heading with that
+    /// title already exists.
+    fn ensure_top_level_heading(&self, content: String, heading: &str) -> String {
+        let org = self.parse_config().parse(&content);
+        let search = self.find_heading_path(&org, heading, content.len() as u32);
+        if search.remaining_parts.is_empty() {
+            return content;
+        }
+
+        let newline = detect_newline(&content);
+        let mut out = content;
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push_str(newline);
+        }
+        out.push_str(&format!("* {heading}{newline}"));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OrgConfig;
+
+    const WORK: &str = "\
+#+TITLE: Work
+* Projects
+** DONE Website redesign :web:
+:PROPERTIES:
+:ID: website-1
+:END:
+Shipped in May.
+*** DONE Pick colours
+** TODO Quarterly report
+";
+
+    fn make_org_mode(temp_dir: &tempfile::TempDir, location: Option<&str>) -> OrgMode {
+        fs::write(temp_dir.path().join("work.org"), WORK).unwrap();
+        let mut config = OrgConfig {
+            org_directory: temp_dir.path().to_str().unwrap().to_string(),
+            org_cache_directory: String::new(),
+            ..OrgConfig::default()
+        };
+        if let Some(location) = location {
+            config.org_archive_location = location.to_string();
+        }
+        OrgMode::new(config).unwrap()
+    }
+
+    fn by_id(id: &str) -> HeadingTarget {
+        HeadingTarget {
+            id: Some(id.to_string()),
+            ..HeadingTarget::default()
+        }
+    }
+
+    fn by_path(path: &str) -> HeadingTarget {
+        HeadingTarget {
+            id: None,
+            file: Some("work.org".to_string()),
+            heading_path: Some(path.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_archive_location() {
+        assert_eq!(
+            parse_archive_location("%s_archive::", "projects/work.org"),
+            ArchiveLocation {
+                file: "projects/work.org_archive".to_string(),
+                heading: None,
+            }
+        );
+        assert_eq!(
+            parse_archive_location("::* Archived Tasks", "work.org"),
+            ArchiveLocation {
+                file: "work.org".to_string(),
+                heading: Some("Archived Tasks".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_archive_location("archive.org::** From %s", "work.org"),
+            ArchiveLocation {
+                file: "archive.org".to_string(),
+                heading: Some("From work.org".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_archive_subtree_to_default_location() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir, None);
+
+        let result = org_mode.archive_subtree(&by_id("website-1")).unwrap();
+        assert_eq!(result.file_path, "work.org");
+        assert_eq!(result.archive_file.as_deref(), Some("work.org_archive"));
+        assert_eq!(result.heading_line, "* DONE Website redesign :web:");
+        assert!(result.archived);
+
+        let work = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert_eq!(
+            work,
+            "#+TITLE: Work\n* Projects\n** TODO Quarterly report\n"
+        );
+
+        let archive = fs::read_to_string(temp_dir.path().join("work.org_archive")).unwrap();
+        assert!(archive.starts_with("#\t-*- mode: org -*-\n\n\nArchived entries from file "));
+        assert!(archive.contains(":ID: website-1\n:ARCHIVE_TIME: "));
+        assert!(archive.contains(":ARCHIVE_OLPATH: Projects\n"));
+        assert!(archive.contains(":ARCHIVE_CATEGORY: work\n"));
+        assert!(
+            archive.contains(":ARCHIVE_TODO: DONE\n:END:\nShipped in May.\n** DONE Pick colours\n")
+        );
+        let file_line = archive
+            .lines()
+            .find(|l| l.starts_with(":ARCHIVE_FILE: "))
+            .unwrap();
+        assert!(file_line.ends_with("work.org"));
+    }
+
+    #[test]
+    fn test_archive_category_is_inherited_from_ancestors() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir, None);
+        fs::write(
+            temp_dir.path().join("work.org"),
+            "#+CATEGORY: office\n* Clients\n:PROPERTIES:\n:CATEGORY: acme\n:END:\n** DONE Kickoff\n* DONE Standup\n",
+        )
+        .unwrap();
+
+        org_mode
+            .archive_subtree(&by_path("Clients/Kickoff"))
+            .unwrap();
+        org_mode.archive_subtree(&by_path("Standup")).unwrap();
+
+        let archive = fs::read_to_string(temp_dir.path().join("work.org_archive")).unwrap();
+        let categories: Vec<&str> = archive
+            .lines()
+            .filter_map(|l| l.strip_prefix(":ARCHIVE_CATEGORY: "))
+            .collect();
+        assert_eq!(categories, ["acme", "office"]);
+    }
+
+    #[test]
+    fn test_archive_subtree_under_configured_heading() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir, Some("archive/%s::* Archived Tasks"));
+
+        org_mode
+            .archive_subtree(&by_path("Projects/Website redesign"))
+            .unwrap();
+        let result = org_mode
+            .archive_subtree(&by_path("Projects/Quarterly report"))
+            .unwrap();
+        assert_eq!(result.archive_file.as_deref(), Some("archive/work.org"));
+        assert_eq!(result.heading_line, "** TODO Quarterly report");
+
+        let archive = fs::read_to_string(temp_dir.path().join("archive/work.org")).unwrap();
+        assert_eq!(archive.matches("* Archived Tasks").count(), 1);
+        let website = archive.find("** DONE Website redesign").unwrap();
+        let report = archive.find("** TODO Quarterly report").unwrap();
+        assert!(website < report);
+        assert!(archive.contains("*** DONE Pick colours\n"));
+        assert!(archive.contains(":ARCHIVE_TODO: TODO\n"));
+    }
+
+    #[test]
+    fn test_archive_subtree_within_same_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir, Some("::* Archive"));
+
+        let result = org_mode.archive_subtree(&by_id("website-1")).unwrap();
+        assert_eq!(result.archive_file.as_deref(), Some("work.org"));
+
+        let work = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert!(
+            work.starts_with("#+TITLE: Work\n* Projects\n** TODO Quarterly report\n* Archive\n")
+        );
+        assert!(work.contains("** DONE Website redesign :web:\n:PROPERTIES:\n:ID: website-1\n"));
+    }
+
+    #[test]
+    fn test_archive_location_outside_org_directory_rejected() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir, Some("/tmp/%s_archive::"));
+
+        let err = org_mode.archive_subtree(&by_id("website-1")).unwrap_err();
+        assert!(matches!(err, OrgModeError::ConfigError(_)));
+        let work = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert_eq!(work, WORK);
+    }
+
+    #[test]
+    fn test_toggle_archive_tag() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir, None);
+
+        let result = org_mode
+            .archive(&by_id("website-1"), ArchiveMode::Tag)
+            .unwrap();
+        assert!(result.archived);
+        assert_eq!(
+            result.heading_line,
+            "** DONE Website redesign :web:ARCHIVE:"
+        );
+        assert!(result.archive_file.is_none());
+
+        let result = org_mode
+            .archive(&by_id("website-1"), ArchiveMode::Tag)
+            .unwrap();
+        assert!(!result.archived);
+        assert_eq!(result.heading_line, "** DONE Website redesign :web:");
+
+        let work = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert_eq!(work, WORK);
+    }
+}
@@ -14,13 +14,13 @@ use std::os::unix::fs::MetadataExt;
 use crate::OrgModeError;
 use crate::org_mode::{CaptureEntry, CaptureResult, OrgMode, PropertyPair};
 
-const MAX_HEADING_LEVEL: usize = 19;
+pub(crate) const MAX_HEADING_LEVEL: usize = 19;
 
-struct HeadingSearchResult {
-    insert_pos: TextSize,
-    matched_depth: usize,
-    last_matched_level: usize,
-    remaining_parts: Vec<String>,
+pub(crate) struct HeadingSearchResult {
+    pub(crate) insert_pos: TextSize,
+    pub(crate) matched_depth: usize,
+    pub(crate) last_matched_level: usize,
+    pub(crate) remaining_parts: Vec<String>,
 }
 
 struct ResolvedCapture {
@@ -52,6 +52,10 @@ pub(crate) struct ParsedTimestamp {
     pub(crate) time: Option<chrono::NaiveTime>,
     pub(crate) repeater: Option<String>,
     pub(crate) warning: Option<String>,
+    /// Second date of a `<...>--<...>` range.
+    pub(crate) end_date: Option<NaiveDate>,
+    /// End time of a `10:00-11:30` range, or the time of `end_date`.
+    pub(crate) end_time: Option<chrono::NaiveTime>,
 }
 
 impl OrgMode {
@@ -60,10 +64,20 @@ impl OrgMode {
         let full_path = self.prepare_target_path(&resolved.file_rel)?;
         let file_rel = resolved.file_rel.as_str();
 
-        let lock_path = Self::lock_path_for(&full_path)?;
+        Self::with_capture_lock(&full_path, || {
+            self.write_entry(&full_path, file_rel, &entry, &resolved)
+        })
+    }
+
+    /// Runs `f` while holding the per-file capture lock for `target`.
+    pub(crate) fn with_capture_lock<T>(
+        target: &Path,
+        f: impl FnOnce() -> Result<T, OrgModeError>,
+    ) -> Result<T, OrgModeError> {
+        let lock_path = Self::lock_path_for(target)?;
         let lock_file = Self::acquire_capture_lock(&lock_path)?;
 
-        let result = self.write_entry(&full_path, file_rel, &entry, &resolved);
+        let result = f();
 
         // On Unix, unlink while holding the lock so a racing locker that opens the same
         // path gets a different inode and retries (stat-after-lock invariant).
@@ -408,17 +422,10 @@ impl OrgMode {
             }
         }
 
-        if let Some(ref kw) = entry.todo_state {
-            let valid_keywords: Vec<&str> = self
-                .config
-                .org_todo_keywords
-                .iter()
-                .filter(|k| k.as_str() != "|")
-                .map(|k| k.as_str())
-                .collect();
-            if !valid_keywords.contains(&kw.as_str()) {
-                return Err(OrgModeError::InvalidTodoKeyword(kw.clone()));
-            }
+        if let Some(ref kw) = entry.todo_state
+            && !self.config.todo_keywords().contains(kw)
+        {
+            return Err(OrgModeError::InvalidTodoKeyword(kw.clone()));
         }
 
         if let Some(ref p) = entry.priority
@@ -668,11 +675,17 @@ impl OrgMode {
             value: value.to_string(),
         };
 
-        let mut tokens = value.split_whitespace();
+        let (start, range_end) = match value.split_once("--") {
+            Some((start, end)) => (start, Some(end)),
+            None => (value, None),
+        };
+
+        let mut tokens = start.split_whitespace();
         let date_tok = tokens.next().ok_or_else(invalid)?;
         let date = NaiveDate::parse_from_str(date_tok, "%Y-%m-%d").map_err(|_| invalid())?;
 
         let mut time: Option<chrono::NaiveTime> = None;
+        let mut end_time: Option<chrono::NaiveTime> = None;
         let mut repeater: Option<String> = None;
         let mut warning: Option<String> = None;
 
@@ -680,9 +693,10 @@ impl OrgMode {
             if time.is_none()
                 && repeater.is_none()
                 && warning.is_none()
-                && let Ok(t) = chrono::NaiveTime::parse_from_str(tok, "%H:%M")
+                && let Some((start, end)) = Self::parse_time_range(tok)
             {
-                time = Some(t);
+                time = Some(start);
+                end_time = end;
                 continue;
             }
 
@@ -703,14 +717,54 @@ impl OrgMode {
             return Err(invalid());
         }
 
+        let mut end_date = None;
+        if let Some(range_end) = range_end {
+            if end_time.is_some() {
+                return Err(invalid());
+            }
+            let mut tokens = range_end.split_whitespace();
+            let date_tok = tokens.next().ok_or_else(invalid)?;
+            end_date =
+                Some(NaiveDate::parse_from_str(date_tok, "%Y-%m-%d").map_err(|_| invalid())?);
+            if let Some(tok) = tokens.next() {
+                end_time =
+                    Some(chrono::NaiveTime::parse_from_str(tok, "%H:%M").map_err(|_| invalid())?);
+            }
+            if tokens.next().is_some() {
+                return Err(invalid());
+            }
+        }
+
+        // A range has to end after it starts.
+        let start_at = date.and_time(time.unwrap_or_default());
+        let end_at = match (end_date, end_time) {
+            (Some(end_date), end_time) => Some(end_date.and_time(end_time.unwrap_or_default())),
+            (None, Some(end_time)) => Some(date.and_time(end_time)),
+            (None, None) => None,
+        };
+        if end_at.is_some_and(|end_at| end_at <= start_at) {
+            return Err(invalid());
+        }
+
         Ok(ParsedTimestamp {
             date,
             time,
             repeater,
             warning,
+            end_date,
+            end_time,
         })
     }
 
+    /// `HH:MM`, or `HH:MM-HH:MM` for a time range.
+    fn parse_time_range(tok: &str) -> Option<(chrono::NaiveTime, Option<chrono::NaiveTime>)> {
+        let parse = |t: &str| chrono::NaiveTime::parse_from_str(t, "%H:%M").ok();
+        match tok.split_once('-') {
+            Some((start, end)) => Some((parse(start)?, Some(parse(end)?))),
+            None => Some((parse(tok)?, None)),
+        }
+    }
+
     pub(crate) fn datetree_segments(date: NaiveDate) -> Vec<String> {
         vec![
             date.format("%Y").to_string(),
@@ -725,6 +779,9 @@ impl OrgMode {
         let mut s = format!("{open}{} {dow}", ts.date.format("%Y-%m-%d"));
         if let Some(t) = ts.time {
             s.push_str(&format!(" {}", t.format("%H:%M")));
+            if let (None, Some(end)) = (ts.end_date, ts.end_time) {
+                s.push_str(&format!("-{}", end.format("%H:%M")));
+            }
         }
         if let Some(r) = &ts.repeater {
             s.push(' ');
@@ -735,6 +792,14 @@ impl OrgMode {
             s.push_str(w);
         }
         s.push(close);
+        if let Some(end_date) = ts.end_date {
+            let dow = end_date.format("%a");
+            s.push_str(&format!("--{open}{} {dow}", end_date.format("%Y-%m-%d")));
+            if let Some(t) = ts.end_time {
+                s.push_str(&format!(" {}", t.format("%H:%M")));
+            }
+            s.push(close);
+        }
         s
     }
 
@@ -778,7 +843,7 @@ impl OrgMode {
         )
     }
 
-    fn find_heading_path(
+    pub(crate) fn find_heading_path(
         &self,
         org: &Org,
         heading_path: &str,
@@ -815,7 +880,7 @@ impl OrgMode {
                             .map(|&(_, d)| d == Some(matched - 1))
                             .unwrap_or(false)
                     };
-                    if parent_ok && h.title_raw() == part {
+                    if parent_ok && h.title_raw().trim_end() == part {
                         insert_pos = h.end();
                         last_level = level;
                         step_matched_depth = Some(matched);
@@ -1007,6 +1072,8 @@ mod tests {
             time: time.map(|(h, m)| chrono::NaiveTime::from_hms_opt(h, m, 0).unwrap()),
             repeater: rep.map(String::from),
             warning: warn.map(String::from),
+            end_date: None,
+            end_time: None,
         }
     }
 
@@ -1044,6 +1111,53 @@ mod tests {
         assert_eq!(s, "<2026-05-15 Fri 14:30 ++1w -3d>");
     }
 
+    #[test]
+    fn test_parse_and_format_time_range() {
+        let ts = OrgMode::parse_iso_timestamp("scheduled", "2026-05-01 10:00-11:30 +1w").unwrap();
+        assert_eq!(ts.end_date, None);
+        assert_eq!(ts.end_time, chrono::NaiveTime::from_hms_opt(11, 30, 0));
+        assert_eq!(
+            OrgMode::format_org_timestamp(&ts, true),
+            "<2026-05-01 Fri 10:00-11:30 +1w>"
+        );
+    }
+
+    #[test]
+    fn test_parse_and_format_date_range() {
+        let ts = OrgMode::parse_iso_timestamp("scheduled", "2026-05-01--2026-05-05").unwrap();
+        assert_eq!(ts.end_date, NaiveDate::from_ymd_opt(2026, 5, 5));
+        assert_eq!(
+            OrgMode::format_org_timestamp(&ts, true),
+            "<2026-05-01 Fri>--<2026-05-05 Tue>"
+        );
+
+        let ts = OrgMode::parse_iso_timestamp("scheduled", "2026-05-01 22:00--2026-05-02 06:15")
+            .unwrap();
+        assert_eq!(
+            OrgMode::format_org_timestamp(&ts, true),
+            "<2026-05-01 Fri 22:00>--<2026-05-02 Sat 06:15>"
+        );
+    }
+
+    #[test]
+    fn test_parse_iso_timestamp_rejects_bad_ranges() {
+        for bad in [
+            "2026-05-01 11:30-10:00",
+            "2026-05-05--2026-05-01",
+            "2026-05-01--2026-05-01",
+            "2026-05-01 10:00-11:00--2026-05-02",
+            "2026-05-01--2026-05-02 +1w",
+            "2026-05-01--garbage",
+            "2026-05-01 10:00-",
+        ] {
+            let err = OrgMode::parse_iso_timestamp("scheduled", bad).unwrap_err();
+            assert!(
+                matches!(err, OrgModeError::InvalidTimestamp { .. }),
+                "{bad}"
+            );
+        }
+    }
+
     #[test]
     fn test_datetree_segments_format() {
         let date = NaiveDate::from_ymd_opt(2026, 5, 10).unwrap();
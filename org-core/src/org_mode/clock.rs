@@ -0,0 +1,681 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{Days, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use orgize::export::{Container, Event, from_fn};
+use serde::{Deserialize, Serialize};
+
+use crate::OrgModeError;
+use crate::org_mode::capture::ParsedTimestamp;
+use crate::org_mode::logbook::{
+    find_logbook, leading_whitespace, push_logbook_entry, remove_logbook_entry,
+};
+use crate::org_mode::update::{TargetHeadline, headline_level, join_lines, line_index_at};
+use crate::org_mode::{
+    AgendaViewType, ClockAction, ClockGroupBy, ClockReport, ClockReportRow, ClockResult,
+    HeadingTarget, OrgMode, Position,
+};
+
+/// A parsed `CLOCK:` line. `end` is `None` while the clock is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ClockLine {
+    pub start: NaiveDateTime,
+    pub end: Option<NaiveDateTime>,
+}
+
+/// Parses `CLOCK: [start]` or `CLOCK: [start]--[end] => H:MM`. The trailing
+/// duration is ignored; it is recomputed from the two timestamps.
+pub(crate) fn parse_clock_line(line: &str) -> Option<ClockLine> {
+    let rest = line.trim().strip_prefix("CLOCK:")?.trim_start();
+    let (start_raw, rest) = split_inactive(rest)?;
+    let start = parse_inactive_datetime(start_raw)?;
+    let rest = rest.trim_start();
+    if rest.is_empty() {
+        return Some(ClockLine { start, end: None });
+    }
+    let (end_raw, _) = split_inactive(rest.strip_prefix("--")?)?;
+    let end = parse_inactive_datetime(end_raw)?;
+    Some(ClockLine {
+        start,
+        end: Some(end),
+    })
+}
+
+fn split_inactive(s: &str) -> Option<(&str, &str)> {
+    let inner = s.strip_prefix('[')?;
+    let close = inner.find(']')?;
+    Some((&inner[..close], &inner[close + 1..]))
+}
+
+/// Parses the inside of an inactive `[YYYY-MM-DD Dow HH:MM]` timestamp.
+fn parse_inactive_datetime(s: &str) -> Option<NaiveDateTime> {
+    let mut tokens = s.split_whitespace();
+    let date = NaiveDate::parse_from_str(tokens.next()?, "%Y-%m-%d").ok()?;
+    let time = tokens.find_map(|t| NaiveTime::parse_from_str(t, "%H:%M").ok())?;
+    Some(date.and_time(time))
+}
+
+fn format_clock_stamp(at: NaiveDateTime) -> String {
+    OrgMode::format_org_timestamp(
+        &ParsedTimestamp {
+            date: at.date(),
+            time: Some(at.time()),
+            repeater: None,
+            warning: None,
+            end_date: None,
+            end_time: None,
+        },
+        false,
+    )
+}
+
+/// Same `%2d:%02d` layout Emacs writes after `=>`.
+pub(crate) fn format_clock_duration(minutes: i64) -> String {
+    format!("{:>2}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Minutes of a closed clock that fall inside `[from, to)`.
+fn clipped_minutes(clock: ClockLine, from: NaiveDateTime, to: NaiveDateTime) -> Option<i64> {
+    let start = clock.start.max(from);
+    let end = clock.end?.min(to);
+    (end > start).then(|| (end - start).num_minutes())
+}
+
+/// A headline and the line where its own section starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct HeadlineSection {
+    pub line: usize,
+    pub level: usize,
+    /// Byte span of the headline's subtree.
+    pub position: Position,
+    pub title: String,
+    pub path: String,
+    pub todo_keyword: Option<String>,
+    pub tags: Vec<String>,
+    pub id: Option<String>,
+}
+
+impl OrgMode {
+    /// Starts a clock on the target heading by prepending an open `CLOCK:`
+    /// line to its `:LOGBOOK:` drawer. Fails if any clock is already running.
+    pub fn clock_in(
+        &self,
+        target: &HeadingTarget,
+        at: Option<&str>,
+    ) -> Result<ClockResult, OrgModeError> {
+        Self::validate_heading_target(target)?;
+        let start = Self::resolve_clock_time(at)?;
+        let (file_rel, full_path) = self.resolve_target_file(target)?;
+
+        Self::with_capture_lock(&full_path, || {
+            if let Some(running) = self.running_clock()? {
+                return Err(OrgModeError::ClockAlreadyRunning(running));
+            }
+
+            let content = fs::read_to_string(&full_path).map_err(OrgModeError::IoError)?;
+            let headline = self.locate_target(&content, target)?;
+            let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+            let clock_line = format!("CLOCK: {}", format_clock_stamp(start));
+            let indent = if headline.property_drawer_line_count > 0 {
+                leading_whitespace(&lines[headline.property_drawer_first_line]).to_string()
+            } else {
+                String::new()
+            };
+            push_logbook_entry(
+                &mut lines,
+                headline.body_first_line,
+                headline.body_last_line,
+                &indent,
+                &clock_line,
+            );
+            Self::atomic_write(&full_path, join_lines(&lines, &content).as_bytes())?;
+
+            Ok(ClockResult {
+                file_path: file_rel.clone(),
+                heading_line: lines[headline.line_idx].clone(),
+                action: ClockAction::In,
+                clock_line,
+                duration_minutes: None,
+            })
+        })
+    }
+
+    /// Closes the running clock on the target heading, appending the
+    /// `=> H:MM` duration.
+    pub fn clock_out(
+        &self,
+        target: &HeadingTarget,
+        at: Option<&str>,
+    ) -> Result<ClockResult, OrgModeError> {
+        Self::validate_heading_target(target)?;
+        let end = Self::resolve_clock_time(at)?;
+        let (file_rel, full_path) = self.resolve_target_file(target)?;
+
+        Self::with_capture_lock(&full_path, || {
+            let content = fs::read_to_string(&full_path).map_err(OrgModeError::IoError)?;
+            let headline = self.locate_target(&content, target)?;
+            let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+            let (idx, open) = Self::find_open_clock(&lines, &headline)
+                .ok_or_else(|| OrgModeError::NoRunningClock(target.to_string()))?;
+            if end < open.start {
+                return Err(OrgModeError::InvalidUpdate(format!(
+                    "clock out time {} is before clock in time {}",
+                    format_clock_stamp(end),
+                    format_clock_stamp(open.start)
+                )));
+            }
+
+            let minutes = (end - open.start).num_minutes();
+            let clock_line = format!(
+                "CLOCK: {}--{} => {}",
+                format_clock_stamp(open.start),
+                format_clock_stamp(end),
+                format_clock_duration(minutes)
+            );
+            let indent = leading_whitespace(&lines[idx]).to_string();
+            lines[idx] = format!("{indent}{clock_line}");
+            Self::atomic_write(&full_path, join_lines(&lines, &content).as_bytes())?;
+
+            Ok(ClockResult {
+                file_path: file_rel.clone(),
+                heading_line: lines[headline.line_idx].clone(),
+                action: ClockAction::Out,
+                clock_line,
+                duration_minutes: Some(minutes),
+            })
+        })
+    }
+
+    /// Discards the running clock on the target heading, removing the
+    /// `:LOGBOOK:` drawer if it ends up empty.
+    pub fn clock_cancel(&self, target: &HeadingTarget) -> Result<ClockResult, OrgModeError> {
+        Self::validate_heading_target(target)?;
+        let (file_rel, full_path) = self.resolve_target_file(target)?;
+
+        Self::with_capture_lock(&full_path, || {
+            let content = fs::read_to_string(&full_path).map_err(OrgModeError::IoError)?;
+            let headline = self.locate_target(&content, target)?;
+            let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+            let (idx, _) = Self::find_open_clock(&lines, &headline)
+                .ok_or_else(|| OrgModeError::NoRunningClock(target.to_string()))?;
+            let clock_line = lines[idx].trim().to_string();
+            let heading_line = lines[headline.line_idx].clone();
+
+            match find_logbook(&lines, headline.body_first_line, headline.body_last_line)
+                .filter(|span| span.entries().contains(&idx))
+            {
+                Some(span) => remove_logbook_entry(&mut lines, span, idx),
+                None => {
+                    lines.remove(idx);
+                }
+            }
+            Self::atomic_write(&full_path, join_lines(&lines, &content).as_bytes())?;
+
+            Ok(ClockResult {
+                file_path: file_rel.clone(),
+                heading_line,
+                action: ClockAction::Cancel,
+                clock_line,
+                duration_minutes: None,
+            })
+        })
+    }
+
+    /// Sums closed `CLOCK:` durations in the agenda files over `range`,
+    /// grouped per heading, file or tag. Clocks straddling the range
+    /// boundaries only count the overlapping minutes; running clocks are
+    /// ignored.
+    pub fn clock_report(
+        &self,
+        range: AgendaViewType,
+        group_by: ClockGroupBy,
+    ) -> Result<ClockReport, OrgModeError> {
+        let from = range.start_date().naive_local();
+        let last_day = range.end_date().date_naive();
+        let to = (last_day + Days::new(1)).and_time(NaiveTime::MIN);
+
+        let org_root = Path::new(&self.config.org_directory);
+        let mut files = self.agenda_files().into_iter().collect::<Vec<_>>();
+        files.sort();
+
+        let mut totals: HashMap<(String, Option<String>), i64> = HashMap::new();
+        let mut total_minutes = 0;
+        for file in files {
+            let content = fs::read_to_string(&file).unwrap_or_default();
+            let file_path = file
+                .strip_prefix(org_root)
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .to_string();
+            let lines: Vec<&str> = content.lines().collect();
+            let sections = self.headline_sections(&content);
+
+            for (i, section) in sections.iter().enumerate() {
+                let section_end = sections.get(i + 1).map_or(lines.len(), |next| next.line);
+                let minutes: i64 = lines[(section.line + 1).min(section_end)..section_end]
+                    .iter()
+                    .filter_map(|line| parse_clock_line(line))
+                    .filter_map(|clock| clipped_minutes(clock, from, to))
+                    .sum();
+                if minutes == 0 {
+                    continue;
+                }
+                total_minutes += minutes;
+
+                match group_by {
+                    ClockGroupBy::Heading => {
+                        *totals
+                            .entry((section.path.clone(), Some(file_path.clone())))
+                            .or_default() += minutes;
+                    }
+                    ClockGroupBy::File => {
+                        *totals.entry((file_path.clone(), None)).or_default() += minutes;
+                    }
+                    ClockGroupBy::Tag => {
+                        for tag in &section.tags {
+                            *totals.entry((tag.clone(), None)).or_default() += minutes;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut rows = totals
+            .into_iter()
+            .map(|((key, file_path), minutes)| ClockReportRow {
+                key,
+                file_path,
+                minutes,
+                duration: format_clock_duration(minutes).trim_start().to_string(),
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by(|a, b| {
+            b.minutes
+                .cmp(&a.minutes)
+                .then_with(|| a.key.cmp(&b.key))
+                .then_with(|| a.file_path.cmp(&b.file_path))
+        });
+
+        Ok(ClockReport {
+            start_date: from.format("%Y-%m-%d").to_string(),
+            end_date: last_day.format("%Y-%m-%d").to_string(),
+            group_by,
+            total_minutes,
+            total: format_clock_duration(total_minutes)
+                .trim_start()
+                .to_string(),
+            rows,
+        })
+    }
+
+    /// Lists every headline in document order with its slash-joined path,
+    /// so the lines between two entries form the first one's own section.
+    pub(crate) fn headline_sections(&self, content: &str) -> Vec<HeadlineSection> {
+        let org = self.parse_config().parse(content);
+        let mut stack: Vec<(usize, String)> = Vec::new();
+        let mut sections = Vec::new();
+        let mut handler = from_fn(|event| {
+            if let Event::Enter(Container::Headline(ref h)) = event {
+                let level = h.level();
+                while stack.last().is_some_and(|(l, _)| *l >= level) {
+                    stack.pop();
+                }
+                let title = h.title_raw().trim_end().to_string();
+                stack.push((level, title.clone()));
+                sections.push(HeadlineSection {
+                    line: line_index_at(content, h.start().into()),
+                    level,
+                    position: Position {
+                        start: h.start().into(),
+                        end: h.end().into(),
+                    },
+                    title,
+                    path: stack
+                        .iter()
+                        .map(|(_, t)| t.as_str())
+                        .collect::<Vec<_>>()
+                        .join("/"),
+                    todo_keyword: h.todo_keyword().map(|k| k.to_string()),
+                    tags: h.tags().map(|s| s.to_string()).collect(),
+                    id: h.properties().and_then(|props| {
+                        props
+                            .iter()
+                            .find(|(k, _)| k.to_string().eq_ignore_ascii_case("ID"))
+                            .map(|(_, v)| v.to_string().trim().to_string())
+                    }),
+                });
+            }
+        });
+        org.traverse(&mut handler);
+        sections
+    }
+
+    /// Returns `"<file>: <headline>"` for the first open clock found in any
+    /// org file, mirroring Emacs' single running clock.
+    pub(crate) fn running_clock(&self) -> Result<Option<String>, OrgModeError> {
+        for path in self.list_files(None, None)? {
+            let content = self.read_file(&path)?;
+            let mut heading: Option<&str> = None;
+            for line in content.lines() {
+                if headline_level(line).is_some() {
+                    heading = Some(line.trim_end());
+                } else if parse_clock_line(line).is_some_and(|c| c.end.is_none()) {
+                    return Ok(Some(format!("{path}: {}", heading.unwrap_or_default())));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn find_open_clock(lines: &[String], headline: &TargetHeadline) -> Option<(usize, ClockLine)> {
+        let to = headline.body_last_line.min(lines.len());
+        (headline.body_first_line..to).find_map(|i| {
+            parse_clock_line(&lines[i])
+                .filter(|c| c.end.is_none())
+                .map(|c| (i, c))
+        })
+    }
+
+    fn resolve_clock_time(at: Option<&str>) -> Result<NaiveDateTime, OrgModeError> {
+        let Some(value) = at else {
+            let now = Local::now().naive_local();
+            return Ok(now
+                .with_second(0)
+                .and_then(|t| t.with_nanosecond(0))
+                .unwrap_or(now));
+        };
+        let ts = Self::parse_iso_timestamp("at", value)?;
+        match ts.time {
+            Some(time) if ts.repeater.is_none() && ts.warning.is_none() => {
+                Ok(ts.date.and_time(time))
+            }
+            _ => Err(OrgModeError::InvalidTimestamp {
+                field: "at",
+                value: value.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OrgConfig;
+
+    const FIXTURE: &str = "\
+* Projects
+** TODO Write report
+:PROPERTIES:
+:ID: report-1
+:END:
+Draft the quarterly report.
+** TODO Review PR
+";
+
+    fn make_org_mode(temp_dir: &tempfile::TempDir) -> OrgMode {
+        fs::write(temp_dir.path().join("work.org"), FIXTURE).unwrap();
+        OrgMode::new(OrgConfig {
+            org_directory: temp_dir.path().to_str().unwrap().to_string(),
+            org_cache_directory: String::new(),
+            ..OrgConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn by_id(id: &str) -> HeadingTarget {
+        HeadingTarget {
+            id: Some(id.to_string()),
+            ..HeadingTarget::default()
+        }
+    }
+
+    fn by_path(path: &str) -> HeadingTarget {
+        HeadingTarget {
+            id: None,
+            file: Some("work.org".to_string()),
+            heading_path: Some(path.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_clock_line() {
+        let open = parse_clock_line("  CLOCK: [2026-05-15 Fri 09:00]").unwrap();
+        assert_eq!(open.start.to_string(), "2026-05-15 09:00:00");
+        assert!(open.end.is_none());
+
+        let closed =
+            parse_clock_line("CLOCK: [2026-05-15 Fri 09:00]--[2026-05-15 Fri 10:30] =>  1:30")
+                .unwrap();
+        assert_eq!(closed.end.unwrap().to_string(), "2026-05-15 10:30:00");
+
+        assert!(parse_clock_line("CLOCK: tomorrow").is_none());
+        assert!(parse_clock_line("Some text").is_none());
+    }
+
+    #[test]
+    fn test_clock_in_and_out_writes_logbook() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let result = org_mode
+            .clock_in(&by_id("report-1"), Some("2026-05-15 09:00"))
+            .unwrap();
+        assert_eq!(result.action, ClockAction::In);
+        assert_eq!(result.clock_line, "CLOCK: [2026-05-15 Fri 09:00]");
+        assert_eq!(result.heading_line, "** TODO Write report");
+
+        let content = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert!(content.contains(
+            ":END:\n:LOGBOOK:\nCLOCK: [2026-05-15 Fri 09:00]\n:END:\nDraft the quarterly report."
+        ));
+
+        let result = org_mode
+            .clock_out(&by_id("report-1"), Some("2026-05-15 10:30"))
+            .unwrap();
+        assert_eq!(result.duration_minutes, Some(90));
+        assert_eq!(
+            result.clock_line,
+            "CLOCK: [2026-05-15 Fri 09:00]--[2026-05-15 Fri 10:30] =>  1:30"
+        );
+
+        let content = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert!(content.contains(
+            ":LOGBOOK:\nCLOCK: [2026-05-15 Fri 09:00]--[2026-05-15 Fri 10:30] =>  1:30\n:END:"
+        ));
+    }
+
+    #[test]
+    fn test_clock_in_prepends_to_existing_logbook() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+        let target = by_path("Projects/Review PR");
+
+        org_mode
+            .clock_in(&target, Some("2026-05-15 09:00"))
+            .unwrap();
+        org_mode
+            .clock_out(&target, Some("2026-05-15 09:45"))
+            .unwrap();
+        org_mode
+            .clock_in(&target, Some("2026-05-16 14:00"))
+            .unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert!(content.ends_with(
+            "** TODO Review PR\n:LOGBOOK:\nCLOCK: [2026-05-16 Sat 14:00]\n\
+             CLOCK: [2026-05-15 Fri 09:00]--[2026-05-15 Fri 09:45] =>  0:45\n:END:\n"
+        ));
+    }
+
+    #[test]
+    fn test_clock_in_refuses_second_running_clock() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        org_mode
+            .clock_in(&by_id("report-1"), Some("2026-05-15 09:00"))
+            .unwrap();
+        let err = org_mode
+            .clock_in(&by_path("Projects/Review PR"), Some("2026-05-15 09:30"))
+            .unwrap_err();
+        match err {
+            OrgModeError::ClockAlreadyRunning(running) => {
+                assert_eq!(running, "work.org: ** TODO Write report");
+            }
+            other => panic!("expected ClockAlreadyRunning, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_clock_out_without_running_clock() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let err = org_mode.clock_out(&by_id("report-1"), None).unwrap_err();
+        assert!(matches!(err, OrgModeError::NoRunningClock(_)));
+    }
+
+    #[test]
+    fn test_clock_out_before_clock_in_is_rejected() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        org_mode
+            .clock_in(&by_id("report-1"), Some("2026-05-15 09:00"))
+            .unwrap();
+        let err = org_mode
+            .clock_out(&by_id("report-1"), Some("2026-05-15 08:00"))
+            .unwrap_err();
+        assert!(matches!(err, OrgModeError::InvalidUpdate(_)));
+    }
+
+    #[test]
+    fn test_clock_cancel_removes_empty_logbook() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        org_mode
+            .clock_in(&by_id("report-1"), Some("2026-05-15 09:00"))
+            .unwrap();
+        let result = org_mode.clock_cancel(&by_id("report-1")).unwrap();
+        assert_eq!(result.action, ClockAction::Cancel);
+        assert_eq!(result.clock_line, "CLOCK: [2026-05-15 Fri 09:00]");
+
+        let content = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert_eq!(content, FIXTURE);
+    }
+
+    #[test]
+    fn test_clock_at_rejects_date_only_and_repeater() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        for at in ["2026-05-15", "2026-05-15 09:00 +1w", "yesterday"] {
+            let err = org_mode.clock_in(&by_id("report-1"), Some(at)).unwrap_err();
+            assert!(
+                matches!(err, OrgModeError::InvalidTimestamp { field: "at", .. }),
+                "expected InvalidTimestamp for {at:?}, got {err:?}"
+            );
+        }
+    }
+
+    const REPORT_FIXTURE: &str = "\
+* Projects
+** TODO Write report :work:
+:LOGBOOK:
+CLOCK: [2026-05-15 Fri 09:00]--[2026-05-15 Fri 10:30] =>  1:30
+CLOCK: [2026-05-14 Thu 23:00]--[2026-05-15 Fri 01:00] =>  2:00
+CLOCK: [2026-05-01 Fri 09:00]--[2026-05-01 Fri 17:00] =>  8:00
+:END:
+*** Appendix :writing:
+:LOGBOOK:
+CLOCK: [2026-05-15 Fri 14:00]--[2026-05-15 Fri 14:45] =>  0:45
+CLOCK: [2026-05-15 Fri 16:00]
+:END:
+* Errands :home:
+:LOGBOOK:
+CLOCK: [2026-05-15 Fri 18:00]--[2026-05-15 Fri 18:20] =>  0:20
+:END:
+";
+
+    fn report_org_mode(temp_dir: &tempfile::TempDir) -> OrgMode {
+        fs::write(temp_dir.path().join("time.org"), REPORT_FIXTURE).unwrap();
+        OrgMode::new(OrgConfig {
+            org_directory: temp_dir.path().to_str().unwrap().to_string(),
+            org_cache_directory: String::new(),
+            ..OrgConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn may_15() -> AgendaViewType {
+        AgendaViewType::try_from("day/2026-05-15").unwrap()
+    }
+
+    #[test]
+    fn test_clock_report_by_heading_clips_to_range() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = report_org_mode(&temp_dir);
+
+        let report = org_mode
+            .clock_report(may_15(), ClockGroupBy::Heading)
+            .unwrap();
+        assert_eq!(report.start_date, "2026-05-15");
+        assert_eq!(report.end_date, "2026-05-15");
+        // 1:30 + 1:00 (clipped at midnight) + 0:45 + 0:20; running clock ignored.
+        assert_eq!(report.total_minutes, 215);
+        assert_eq!(report.total, "3:35");
+
+        let rows: Vec<(&str, i64)> = report
+            .rows
+            .iter()
+            .map(|r| (r.key.as_str(), r.minutes))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("Projects/Write report", 150),
+                ("Projects/Write report/Appendix", 45),
+                ("Errands", 20),
+            ]
+        );
+        assert_eq!(report.rows[0].file_path.as_deref(), Some("time.org"));
+        assert_eq!(report.rows[0].duration, "2:30");
+    }
+
+    #[test]
+    fn test_clock_report_by_file_and_tag() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = report_org_mode(&temp_dir);
+
+        let report = org_mode.clock_report(may_15(), ClockGroupBy::File).unwrap();
+        assert_eq!(report.rows.len(), 1);
+        assert_eq!(report.rows[0].key, "time.org");
+        assert_eq!(report.rows[0].minutes, 215);
+        assert!(report.rows[0].file_path.is_none());
+
+        let report = org_mode.clock_report(may_15(), ClockGroupBy::Tag).unwrap();
+        let rows: Vec<(&str, i64)> = report
+            .rows
+            .iter()
+            .map(|r| (r.key.as_str(), r.minutes))
+            .collect();
+        assert_eq!(rows, vec![("work", 150), ("writing", 45), ("home", 20)]);
+    }
+
+    #[test]
+    fn test_clock_report_empty_range() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = report_org_mode(&temp_dir);
+
+        let range = AgendaViewType::try_from("query/from/2026-06-01/to/2026-06-30").unwrap();
+        let report = org_mode.clock_report(range, ClockGroupBy::Heading).unwrap();
+        assert_eq!(report.total_minutes, 0);
+        assert_eq!(report.total, "0:00");
+        assert!(report.rows.is_empty());
+    }
+}
@@ -1,12 +1,12 @@
-use std::collections::HashSet;
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Component, Path};
+use std::sync::Mutex;
 use std::{fs, io, path::PathBuf};
 
-use chrono::{DateTime, Local, TimeZone};
+use chrono::{DateTime, Days, Local, NaiveDate, NaiveTime, Timelike};
 use globset::{Glob, GlobSetBuilder};
 use ignore::{Walk, WalkBuilder};
-use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
-use nucleo_matcher::{Config as NucleoConfig, Matcher};
 use orgize::ast::{Headline, PropertyDrawer, Timestamp};
 use orgize::export::{Container, Event, from_fn, from_fn_with_ctx};
 use orgize::{Org, ParseConfig};
@@ -14,11 +14,49 @@ use rowan::ast::AstNode;
 
 use crate::OrgModeError;
 use crate::config::OrgConfig;
+use crate::org_mode::agenda::TimestampSpan;
+use crate::org_mode::clock::HeadlineSection;
+use crate::org_mode::diary;
+use crate::org_mode::fulltext::{self, SectionIndex};
+use crate::org_mode::index::MetadataIndex;
+use crate::org_mode::properties::PropertyInheritance;
+use crate::org_mode::query::{Query, entry_text};
+use crate::org_mode::search::{LineMatcher, SearchLine, line_elements};
+use crate::org_mode::sort::{SortFields, sort_agenda};
+use crate::org_mode::tags::{TagInheritance, file_tags};
 use crate::org_mode::{
-    AgendaItem, AgendaView, AgendaViewType, OrgMode, Position, Priority, SearchResult, TreeNode,
+    AgendaDay, AgendaEntry, AgendaItem, AgendaOptions, AgendaReason, AgendaSortKey, AgendaView,
+    AgendaViewType, OrgMode, Position, Priority, SearchMode, SearchOptions, SearchResult, TreeNode,
 };
 use crate::utils::tags_match;
 
+/// Entry of `org_agenda_text_search_extra_files` standing for the archive
+/// files of the agenda files.
+const AGENDA_ARCHIVES: &str = "agenda-archives";
+
+/// One day an agenda item lands on, see [`AgendaReason`].
+#[derive(Debug, PartialEq, Eq)]
+struct AgendaOccurrence {
+    /// The day of the view the entry is listed under.
+    day: NaiveDate,
+    /// The date of the timestamp occurrence behind it.
+    date: NaiveDate,
+    time: Option<NaiveTime>,
+    reason: AgendaReason,
+}
+
+/// A file taking part in a ranked search.
+struct RankedFile<'a> {
+    file: String,
+    content: String,
+    sections: Cow<'a, [HeadlineSection]>,
+    full_text: Cow<'a, SectionIndex>,
+    /// Which sections the other search options let through.
+    allowed: Vec<bool>,
+    /// See [`OrgMode::entry_inherited_tags`].
+    inherited_tags: Vec<Vec<String>>,
+}
+
 macro_rules! convert_timestamp {
     ($ts:expr, $prefix:ident) => {{
         pastey::paste! {
@@ -47,10 +85,19 @@ macro_rules! convert_timestamp {
     }};
 }
 
+/// What an agenda heading takes from its file and ancestor headings.
+pub(crate) struct TaskContext {
+    pub(crate) file_path: String,
+    pub(crate) inherited_tags: Vec<String>,
+    pub(crate) category: String,
+    pub(crate) properties: BTreeMap<String, String>,
+}
+
 impl OrgMode {
     pub fn new(config: OrgConfig) -> Result<Self, OrgModeError> {
         let config = config.validate()?;
-        Ok(OrgMode { config })
+        let index = Mutex::new(MetadataIndex::load(&config));
+        Ok(OrgMode { config, index })
     }
 
     pub fn with_defaults() -> Result<Self, OrgModeError> {
@@ -66,6 +113,31 @@ impl OrgMode {
         tags: Option<&[String]>,
         limit: Option<usize>,
     ) -> Result<Vec<String>, OrgModeError> {
+        let files = self.org_files()?;
+        let Some(tags) = tags else {
+            return Ok(files
+                .into_iter()
+                .take(limit.unwrap_or(usize::MAX))
+                .collect());
+        };
+
+        let index = self.refresh_index(&files);
+        Ok(files
+            .into_iter()
+            .filter(|path| {
+                let file_tags = index
+                    .files
+                    .get(path)
+                    .map(|meta| meta.tags())
+                    .unwrap_or_default();
+                tags.iter().any(|tag| file_tags.contains(tag))
+            })
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    /// Every `.org` file under the org directory, relative to it.
+    pub(crate) fn org_files(&self) -> Result<Vec<String>, OrgModeError> {
         Walk::new(&self.config.org_directory)
             .filter_map(|entry| match entry {
                 Ok(dir_entry) => {
@@ -85,20 +157,6 @@ impl OrgMode {
                 Err(e) => Some(Err(OrgModeError::WalkError(e))),
             })
             .collect::<Result<Vec<String>, OrgModeError>>()
-            .map(|files| {
-                files
-                    .into_iter()
-                    .filter(|path| {
-                        if let Some(tags) = tags {
-                            let file_tags = self.tags_in_file(path).unwrap_or_default();
-                            tags.iter().any(|tag| file_tags.contains(tag))
-                        } else {
-                            true
-                        }
-                    })
-                    .take(limit.unwrap_or(usize::MAX))
-                    .collect::<Vec<String>>()
-            })
     }
 
     pub fn search(
@@ -106,69 +164,262 @@ impl OrgMode {
         query: &str,
         limit: Option<usize>,
         snippet_max_size: Option<usize>,
+    ) -> Result<Vec<SearchResult>, OrgModeError> {
+        self.search_with_options(query, &SearchOptions::default(), limit, snippet_max_size)
+    }
+
+    pub fn search_with_tags(
+        &self,
+        query: &str,
+        tags: Option<&[String]>,
+        limit: Option<usize>,
+        snippet_max_size: Option<usize>,
+    ) -> Result<Vec<SearchResult>, OrgModeError> {
+        let options = SearchOptions {
+            tags: tags.map(<[String]>::to_vec),
+            ..SearchOptions::default()
+        };
+        self.search_with_options(query, &options, limit, snippet_max_size)
+    }
+
+    /// Searches the org directory plus `org_agenda_text_search_extra_files`,
+    /// or only `options.files`, narrowed down by the other `options`.
+    pub fn search_with_options(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        limit: Option<usize>,
+        snippet_max_size: Option<usize>,
     ) -> Result<Vec<SearchResult>, OrgModeError> {
         if query.trim().is_empty() {
             return Ok(vec![]);
         }
 
-        let mut matcher = Matcher::new(NucleoConfig::DEFAULT);
-        let pattern = Pattern::new(
-            query,
-            CaseMatching::Ignore,
-            Normalization::Smart,
-            AtomKind::Fuzzy,
-        );
+        let matcher = options
+            .match_string
+            .as_deref()
+            .map(Query::from_match_string)
+            .transpose()?;
+        let ranked = options.mode == SearchMode::Ranked;
+        if ranked && !options.elements.is_empty() {
+            return Err(OrgModeError::InvalidQuery(
+                "ranked search cannot be restricted to elements".to_string(),
+            ));
+        }
+        let mut line_matcher = (!ranked)
+            .then(|| LineMatcher::new(query, options))
+            .transpose()?;
 
-        let files = self.list_files(None, None)?;
+        let files: Vec<String> = match options.files {
+            Some(ref files) => self.search_scope(files)?,
+            None => self.text_search_files()?,
+        };
+        let index = self.metadata()?;
         let mut all_results = Vec::new();
+        let mut ranked_files = Vec::new();
+        let mut heading_found = false;
 
         for file in files {
             let content = match self.read_file(&file) {
                 Ok(content) => content,
                 Err(_) => continue,
             };
+            let meta = index.files.get(&file);
+            let sections = match meta {
+                Some(meta) => Cow::Borrowed(meta.headlines.as_slice()),
+                None => Cow::Owned(self.headline_sections(&content)),
+            };
 
-            let matches = pattern.match_list(
-                content.lines().map(|s| s.to_owned()).collect::<Vec<_>>(),
-                &mut matcher,
-            );
+            let inherited_tags = self.entry_inherited_tags(&content, &sections);
+            let mut keep = match matcher {
+                Some(ref matcher) => self.matching_entry_mask(&file, &content, matcher),
+                None => vec![true; content.lines().count()],
+            };
+            if let Some(ref path) = options.heading_path {
+                let subtree = Self::subtree_mask(&sections, path, keep.len());
+                heading_found |= subtree.contains(&true);
+                keep.iter_mut()
+                    .zip(subtree)
+                    .for_each(|(keep, s)| *keep &= s);
+            }
+            if !options.elements.is_empty() {
+                let elements = line_elements(&content);
+                keep.iter_mut()
+                    .zip(elements)
+                    .for_each(|(keep, e)| *keep &= options.elements.contains(&e));
+            }
+
+            let Some(ref mut line_matcher) = line_matcher else {
+                let allowed = std::iter::once(0)
+                    .chain(sections.iter().map(|s| s.line))
+                    .map(|line| keep.get(line).copied().unwrap_or(false))
+                    .collect();
+                let full_text = match meta {
+                    Some(meta) => Cow::Borrowed(&meta.full_text),
+                    None => Cow::Owned(SectionIndex::build(&content, &sections)),
+                };
+                ranked_files.push(RankedFile {
+                    file,
+                    content,
+                    sections,
+                    full_text,
+                    allowed,
+                    inherited_tags,
+                });
+                continue;
+            };
+
+            let candidates = content
+                .lines()
+                .enumerate()
+                .filter(|(idx, _)| keep[*idx])
+                .map(|(index, text)| SearchLine {
+                    index,
+                    entry: sections.partition_point(|s| s.line <= index),
+                    text,
+                })
+                .collect();
 
-            for (snippet, score) in matches {
-                let snippet = Self::snippet(&snippet, snippet_max_size.unwrap_or(100));
+            for (line, score) in line_matcher.matches(candidates) {
+                let section = line.entry.checked_sub(1).map(|i| &sections[i]);
                 all_results.push(SearchResult {
                     file_path: file.clone(),
-                    snippet,
+                    heading_path: section.map(|s| s.path.clone()),
+                    level: section.map_or(0, |s| s.level),
+                    line: line.index + 1,
+                    position: section.map(|s| s.position.clone()),
+                    id: section.and_then(|s| s.id.clone()),
+                    snippet: Self::snippet(line.text, snippet_max_size.unwrap_or(100)),
                     score,
-                    tags: self.tags_in_file(&file).unwrap_or_default(),
+                    tags: section.map(|s| s.tags.clone()).unwrap_or_default(),
+                    inherited_tags: inherited_tags[line.entry].clone(),
                 });
             }
         }
 
+        if let Some(ref path) = options.heading_path
+            && !heading_found
+        {
+            return Err(OrgModeError::InvalidHeadingPath(path.clone()));
+        }
+        if ranked {
+            all_results = Self::ranked_results(query, &ranked_files, snippet_max_size);
+        }
+        if let Some(ref filter_tags) = options.tags {
+            all_results.retain(|r| {
+                filter_tags
+                    .iter()
+                    .any(|tag| r.tags.contains(tag) || r.inherited_tags.contains(tag))
+            });
+        }
         all_results.sort_by_key(|b| std::cmp::Reverse(b.score));
         all_results.truncate(limit.unwrap_or(all_results.len()));
 
         Ok(all_results)
     }
 
-    pub fn search_with_tags(
-        &self,
+    /// One result per section of `files` relevant to `query`, the section
+    /// text as snippet, cut to `snippet_max_size` characters if given.
+    fn ranked_results(
         query: &str,
-        tags: Option<&[String]>,
-        limit: Option<usize>,
+        files: &[RankedFile],
         snippet_max_size: Option<usize>,
-    ) -> Result<Vec<SearchResult>, OrgModeError> {
-        self.search(query, None, snippet_max_size)
-            .map(|results| match tags {
-                Some(filter_tags) => results
-                    .into_iter()
-                    .filter(|r| filter_tags.iter().any(|tag| r.tags.contains(tag)))
-                    .collect(),
-                None => results,
-            })
-            .map(|mut all_results| {
-                all_results.truncate(limit.unwrap_or(all_results.len()));
-                all_results
+    ) -> Vec<SearchResult> {
+        let indexes: Vec<(&SectionIndex, &[bool])> = files
+            .iter()
+            .map(|f| (f.full_text.as_ref(), f.allowed.as_slice()))
+            .collect();
+
+        fulltext::rank(query, &indexes)
+            .into_iter()
+            .map(|(at, section, score)| {
+                let RankedFile {
+                    file,
+                    content,
+                    sections,
+                    inherited_tags,
+                    ..
+                } = &files[at];
+                let heading = section.checked_sub(1).map(|i| &sections[i]);
+                let start = heading.map_or(0, |s| s.line);
+                let end = sections.get(section).map_or(usize::MAX, |s| s.line);
+                let text = content
+                    .lines()
+                    .skip(start)
+                    .take(end - start)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                SearchResult {
+                    file_path: file.clone(),
+                    heading_path: heading.map(|s| s.path.clone()),
+                    level: heading.map_or(0, |s| s.level),
+                    line: start + 1,
+                    position: heading.map(|s| s.position.clone()),
+                    id: heading.and_then(|s| s.id.clone()),
+                    snippet: match snippet_max_size {
+                        Some(max) => Self::snippet(text.trim_end(), max),
+                        None => text.trim_end().to_string(),
+                    },
+                    score: (score * 1000.0).round() as u32,
+                    tags: heading.map(|s| s.tags.clone()).unwrap_or_default(),
+                    inherited_tags: inherited_tags[section].clone(),
+                }
             })
+            .collect()
+    }
+
+    /// The tags each entry of `content` inherits from `#+FILETAGS:` and its
+    /// ancestors: first the text before the first heading, which only has
+    /// the file tags, then each of `sections`.
+    fn entry_inherited_tags(
+        &self,
+        content: &str,
+        sections: &[HeadlineSection],
+    ) -> Vec<Vec<String>> {
+        let mut inheritance =
+            TagInheritance::new(content, &self.config.org_tags_exclude_from_inheritance);
+        std::iter::once(file_tags(content))
+            .chain(sections.iter().map(|s| inheritance.enter(s.level, &s.tags)))
+            .collect()
+    }
+
+    /// Which of `line_count` lines belong to the subtrees of the headlines
+    /// at `path`.
+    fn subtree_mask(sections: &[HeadlineSection], path: &str, line_count: usize) -> Vec<bool> {
+        let mut mask = vec![false; line_count];
+        for (i, section) in sections.iter().enumerate() {
+            if section.path != path {
+                continue;
+            }
+            let end = sections[i + 1..]
+                .iter()
+                .find(|s| s.level <= section.level)
+                .map_or(line_count, |s| s.line);
+            for line in mask.iter_mut().take(end).skip(section.line) {
+                *line = true;
+            }
+        }
+        mask
+    }
+
+    /// Which lines of `content` lie inside entries that satisfy `matcher`.
+    fn matching_entry_mask(&self, file: &str, content: &str, matcher: &Query) -> Vec<bool> {
+        let today = Local::now().date_naive();
+        let mut keep = vec![false; content.lines().count()];
+
+        let path = Path::new(&self.config.org_directory).join(file);
+        for (headline, context) in self.agenda_headlines(vec![path]) {
+            if !matcher.matches(&headline, &context, today) {
+                continue;
+            }
+            let start = usize::from(headline.start()).min(content.len());
+            let first = content[..start].matches('\n').count();
+            let count = entry_text(&headline.raw()).lines().count();
+            for line in keep.iter_mut().skip(first).take(count) {
+                *line = true;
+            }
+        }
+        keep
     }
 
     pub fn list_files_by_tags(&self, tags: &[String]) -> Result<Vec<String>, OrgModeError> {
@@ -277,10 +528,8 @@ impl OrgMode {
     }
 
     pub fn get_element_by_id(&self, id: &str) -> Result<String, OrgModeError> {
-        let files = self.list_files(None, None)?;
-
-        let found = files.iter().find_map(|path| {
-            self.read_file(path)
+        let found = self.file_with_id(id)?.and_then(|path| {
+            self.read_file(&path)
                 .map(|content| self.search_id(content, id))
                 .unwrap_or_default()
         });
@@ -319,7 +568,7 @@ impl OrgMode {
         found
     }
 
-    fn snippet(s: &str, max: usize) -> String {
+    pub(crate) fn snippet(s: &str, max: usize) -> String {
         if s.chars().count() > max {
             s.chars().take(max).collect::<String>() + "..."
         } else {
@@ -327,21 +576,6 @@ impl OrgMode {
         }
     }
 
-    fn tags_in_file(&self, path: &str) -> Result<Vec<String>, OrgModeError> {
-        let content = self.read_file(path)?;
-        let mut tags = Vec::new();
-
-        let mut handler = from_fn(|event| {
-            if let Event::Enter(Container::Headline(h)) = event {
-                tags.extend(h.tags().map(|s| s.to_string()));
-            }
-        });
-
-        Org::parse(&content).traverse(&mut handler);
-
-        Ok(tags)
-    }
-
     fn files_in_path(&self, path: &str) -> Result<impl Iterator<Item = PathBuf>, OrgModeError> {
         let org_root = PathBuf::from(&self.config.org_directory);
 
@@ -374,43 +608,178 @@ impl OrgMode {
         Ok(iter)
     }
 
-    fn agenda_tasks(&self) -> impl Iterator<Item = (Headline, String)> {
+    /// The files a search restricted to `files`, a file, directory or glob
+    /// relative to the org directory, may read: org files inside the org
+    /// directory and the `org_agenda_text_search_extra_files`.
+    fn search_scope(&self, files: &str) -> Result<Vec<String>, OrgModeError> {
+        let scope = Path::new(files);
+        if files.starts_with('~')
+            || scope
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(OrgModeError::InvalidQuery(format!(
+                "files '{files}' must be relative to the org directory"
+            )));
+        }
+
+        let org_root = fs::canonicalize(&self.config.org_directory)?;
+        let extra_files: HashSet<PathBuf> = self
+            .text_search_files()?
+            .iter()
+            .filter_map(|file| fs::canonicalize(org_root.join(file)).ok())
+            .collect();
+        let mut scope = Vec::new();
+        for path in self.files_in_path(files)? {
+            let Ok(path) = fs::canonicalize(&path) else {
+                continue;
+            };
+            let is_org_file =
+                path.starts_with(&org_root) && path.extension().is_some_and(|ext| ext == "org");
+            if is_org_file || extra_files.contains(&path) {
+                let file = path.strip_prefix(&org_root).unwrap_or(&path);
+                let file = file.to_string_lossy().to_string();
+                if !scope.contains(&file) {
+                    scope.push(file);
+                }
+            }
+        }
+        Ok(scope)
+    }
+
+    /// Every file matched by `org_agenda_files`, deduplicated.
+    pub(crate) fn agenda_files(&self) -> HashSet<PathBuf> {
         self.config
             .org_agenda_files
             .iter()
             .filter_map(|loc| self.files_in_path(loc).ok())
             .flatten()
-            .collect::<HashSet<_>>()
-            .into_iter()
-            .flat_map(|file| {
-                let config = ParseConfig {
-                    todo_keywords: (
-                        self.config.unfinished_keywords(),
-                        self.config.finished_keywords(),
-                    ),
-                    ..Default::default()
-                };
-                let org = config.parse(fs::read_to_string(&file).unwrap_or_default());
+            .collect()
+    }
+
+    /// Every org file under the org directory plus the
+    /// `org_agenda_text_search_extra_files`, where `agenda-archives` stands
+    /// for the archive files of the agenda files, as in Emacs.
+    pub(crate) fn text_search_files(&self) -> Result<Vec<String>, OrgModeError> {
+        let mut files = self.org_files()?;
+        for entry in &self.config.org_agenda_text_search_extra_files {
+            let extra: Vec<String> = if entry == AGENDA_ARCHIVES {
+                self.agenda_archive_files()
+            } else {
+                self.files_in_path(entry)
+                    .map(|paths| {
+                        paths
+                            .map(|path| self.relative_to_org_directory(&path))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            };
+            for file in extra {
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+        }
+        Ok(files)
+    }
 
-                let org_root = Path::new(&self.config.org_directory);
+    /// `path` relative to the org directory, or whole if it lies outside.
+    pub(crate) fn relative_to_org_directory(&self, path: &Path) -> String {
+        path.strip_prefix(&self.config.org_directory)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    }
 
-                let mut tasks = Vec::new();
-                let mut handler = from_fn(|event| {
-                    if let Event::Enter(container) = event
-                        && let Container::Headline(headline) = container
-                        && (headline.is_todo() || headline.is_done())
-                    {
-                        let file_path = file
-                            .strip_prefix(org_root)
-                            .unwrap_or(&file)
-                            .to_string_lossy()
-                            .to_string();
-                        tasks.push((headline, file_path));
-                    }
-                });
-                org.traverse(&mut handler);
-                tasks
-            })
+    /// Every task in the agenda files with what it inherits from its file
+    /// and ancestors.
+    fn agenda_tasks(&self) -> impl Iterator<Item = (Headline, TaskContext)> {
+        self.agenda_headlines(self.files_with_tasks(self.agenda_files()))
+            .filter(|(headline, _)| headline.is_todo() || headline.is_done())
+    }
+
+    /// Every heading of the agenda files that may land in a day view: the
+    /// tasks, plus calendar entries with active or diary sexp timestamps.
+    fn agenda_entries(&self) -> impl Iterator<Item = (Headline, TaskContext)> {
+        self.agenda_headlines(self.files_with_agenda_entries(self.agenda_files()))
+    }
+
+    /// Every heading in `files` with what it inherits from its file and
+    /// ancestors.
+    pub(crate) fn agenda_headlines(
+        &self,
+        files: Vec<PathBuf>,
+    ) -> impl Iterator<Item = (Headline, TaskContext)> {
+        files.into_iter().flat_map(|file| {
+            let config = ParseConfig {
+                todo_keywords: (
+                    self.config.unfinished_keywords(),
+                    self.config.finished_keywords(),
+                ),
+                ..Default::default()
+            };
+            let content = fs::read_to_string(&file).unwrap_or_default();
+            let file_stem = file
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mut tag_inheritance =
+                TagInheritance::new(&content, &self.config.org_tags_exclude_from_inheritance);
+            let mut property_inheritance = PropertyInheritance::new(
+                &content,
+                &file_stem,
+                &self.config.org_use_property_inheritance,
+            );
+            let org = config.parse(&content);
+
+            let org_root = Path::new(&self.config.org_directory);
+
+            let mut headlines = Vec::new();
+            let mut handler = from_fn(|event| {
+                if let Event::Enter(Container::Headline(headline)) = event {
+                    let own_tags: Vec<String> = headline.tags().map(|s| s.to_string()).collect();
+                    let inherited_tags = tag_inheritance.enter(headline.level(), &own_tags);
+                    let own_properties: BTreeMap<String, String> = headline
+                        .properties()
+                        .map(|drawer| {
+                            drawer
+                                .iter()
+                                .map(|(k, v)| (k.to_string(), v.to_string()))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    let (category, properties) =
+                        property_inheritance.enter(headline.level(), own_properties);
+                    let file_path = file
+                        .strip_prefix(org_root)
+                        .unwrap_or(&file)
+                        .to_string_lossy()
+                        .to_string();
+                    let context = TaskContext {
+                        file_path,
+                        inherited_tags,
+                        category,
+                        properties,
+                    };
+                    headlines.push((headline, context));
+                }
+            });
+            org.traverse(&mut handler);
+            headlines
+        })
+    }
+
+    /// Whether a task carries every filter tag, itself or by inheritance.
+    fn task_has_tags(headline: &Headline, context: &TaskContext, tags: Option<&[String]>) -> bool {
+        tags.map(|tags| {
+            let effective: Vec<String> = headline
+                .tags()
+                .map(|s| s.to_string())
+                .chain(context.inherited_tags.iter().cloned())
+                .collect();
+            tags_match(&effective, tags)
+        })
+        .unwrap_or(true)
     }
 
     pub fn list_tasks(
@@ -420,67 +789,144 @@ impl OrgMode {
         priority: Option<Priority>,
         limit: Option<usize>,
     ) -> Result<Vec<AgendaItem>, OrgModeError> {
-        let tasks = self
+        let sort = &self.config.org_agenda_sorting_strategy;
+        self.filter_tasks(None, todo_states, tags, priority, sort, limit)
+    }
+
+    /// [`OrgMode::list_tasks`] with every filter, including an Emacs
+    /// tags/property match string such as `+work-someday|urgent+EFFORT>1/!TODO`,
+    /// and the order in one place.
+    pub fn list_tasks_with_options(
+        &self,
+        options: &AgendaOptions,
+    ) -> Result<Vec<AgendaItem>, OrgModeError> {
+        let matcher = options
+            .match_string
+            .as_deref()
+            .map(Query::from_match_string)
+            .transpose()?;
+        self.filter_tasks(
+            matcher,
+            options.todo_states.as_deref(),
+            options.tags.as_deref(),
+            options.priority.clone(),
+            self.sort_keys(options),
+            options.limit,
+        )
+    }
+
+    fn sort_keys<'a>(&'a self, options: &'a AgendaOptions) -> &'a [AgendaSortKey] {
+        options
+            .sort
+            .as_deref()
+            .unwrap_or(&self.config.org_agenda_sorting_strategy)
+    }
+
+    fn filter_tasks(
+        &self,
+        matcher: Option<Query>,
+        todo_states: Option<&[String]>,
+        tags: Option<&[String]>,
+        priority: Option<Priority>,
+        sort: &[AgendaSortKey],
+        limit: Option<usize>,
+    ) -> Result<Vec<AgendaItem>, OrgModeError> {
+        let mut filters = vec![Query::Todo(
+            todo_states.map(<[String]>::to_vec).unwrap_or_default(),
+        )];
+        filters.extend(matcher);
+        if let Some(tags) = tags {
+            filters.push(Query::TagsAll(tags.to_vec()));
+        }
+        if let Some(priority) = priority {
+            filters.push(match priority {
+                Priority::A => Query::Priority(vec!['A']),
+                Priority::B => Query::Priority(vec!['B']),
+                Priority::C => Query::Priority(vec!['C']),
+                Priority::None => Query::Not(Box::new(Query::Priority(Vec::new()))),
+            });
+        }
+        let query = Query::And(filters);
+        let today = Local::now().date_naive();
+
+        let mut tasks = self
             .agenda_tasks()
-            .filter(|(headline, _)| {
-                headline.is_todo()
-                    && tags
-                        .map(|tags| {
-                            tags_match(
-                                &headline.tags().map(|s| s.to_string()).collect::<Vec<_>>(),
-                                tags,
-                            )
-                        })
-                        .unwrap_or(true)
-                    && priority
-                        .as_ref()
-                        .map(|p| {
-                            if let Some(prio) = headline.priority() {
-                                let prio = prio.to_string();
-                                matches!(
-                                    (p, prio.as_str()),
-                                    (Priority::A, "A") | (Priority::B, "B") | (Priority::C, "C")
-                                )
-                            } else {
-                                *p == Priority::None
-                            }
-                        })
-                        .unwrap_or(true)
-                    && todo_states
-                        .map(|states| {
-                            headline
-                                .todo_keyword()
-                                .map(|kw| states.contains(&kw.to_string()))
-                                .unwrap_or(false)
-                        })
-                        .unwrap_or(true)
-            })
-            .map(|(headline, file_path)| Self::headline_to_agenda_item(&headline, file_path, None))
-            .take(limit.unwrap_or(usize::MAX))
+            .filter(|(headline, context)| query.matches(headline, context, today))
+            .map(|(headline, context)| Self::headline_to_agenda_item(&headline, context, None))
             .collect::<Vec<_>>();
 
+        tasks.sort_by(Self::file_order);
+        let keywords = self.config.todo_keywords();
+        sort_agenda(&mut tasks, sort, &keywords, SortFields::for_task);
+        if let Some(limit) = limit {
+            tasks.truncate(limit);
+        }
+
         Ok(tasks)
     }
 
+    /// Orders items by file, then by position in the file, the baseline the
+    /// sorting strategy refines.
+    fn file_order(a: &AgendaItem, b: &AgendaItem) -> std::cmp::Ordering {
+        let start = |item: &AgendaItem| item.position.as_ref().map(|p| p.start);
+        a.file_path
+            .cmp(&b.file_path)
+            .then_with(|| start(a).cmp(&start(b)))
+    }
+
     pub fn get_agenda_view(
         &self,
         agenda_view_type: AgendaViewType,
         todo_states: Option<&[String]>,
         tags: Option<&[String]>,
         limit: Option<usize>,
+    ) -> Result<AgendaView, OrgModeError> {
+        let sort = &self.config.org_agenda_sorting_strategy;
+        self.agenda_view(agenda_view_type, None, todo_states, tags, sort, limit)
+    }
+
+    /// [`OrgMode::get_agenda_view`] with every filter, including an Emacs
+    /// tags/property match string, and the order in one place. `priority`
+    /// is ignored.
+    pub fn get_agenda_view_with_options(
+        &self,
+        agenda_view_type: AgendaViewType,
+        options: &AgendaOptions,
+    ) -> Result<AgendaView, OrgModeError> {
+        let matcher = options
+            .match_string
+            .as_deref()
+            .map(Query::from_match_string)
+            .transpose()?;
+        self.agenda_view(
+            agenda_view_type,
+            matcher,
+            options.todo_states.as_deref(),
+            options.tags.as_deref(),
+            self.sort_keys(options),
+            options.limit,
+        )
+    }
+
+    fn agenda_view(
+        &self,
+        agenda_view_type: AgendaViewType,
+        matcher: Option<Query>,
+        todo_states: Option<&[String]>,
+        tags: Option<&[String]>,
+        sort: &[AgendaSortKey],
+        limit: Option<usize>,
     ) -> Result<AgendaView, OrgModeError> {
         let reference_date = matches!(agenda_view_type, AgendaViewType::Today).then(Local::now);
+        let today = Local::now().date_naive();
 
-        let mut items = self
-            .agenda_tasks()
-            .filter(|(headline, _)| {
-                tags.map(|tags| {
-                    tags_match(
-                        &headline.tags().map(|s| s.to_string()).collect::<Vec<_>>(),
-                        tags,
-                    )
-                })
-                .unwrap_or(true)
+        let mut tasks = self
+            .agenda_entries()
+            .filter(|(headline, context)| {
+                Self::task_has_tags(headline, context, tags)
+                    && matcher
+                        .as_ref()
+                        .is_none_or(|matcher| matcher.matches(headline, context, today))
                     && todo_states
                         .map(|states| {
                             headline
@@ -489,28 +935,75 @@ impl OrgMode {
                                 .unwrap_or(false)
                         })
                         .unwrap_or(true)
-                    && self.is_in_agenda_range(headline, &agenda_view_type)
             })
-            .map(|(headline, file_path)| {
-                Self::headline_to_agenda_item(&headline, file_path, reference_date)
+            .filter_map(|(headline, context)| {
+                let occurrences = self.agenda_occurrences(&headline, &agenda_view_type);
+                (!occurrences.is_empty()).then(|| {
+                    let item = Self::headline_to_agenda_item(&headline, context, reference_date);
+                    (item, occurrences)
+                })
             })
             .collect::<Vec<_>>();
 
+        tasks.sort_by(|(a, _), (b, _)| Self::file_order(a, b));
+        let keywords = self.config.todo_keywords();
+        sort_agenda(&mut tasks, sort, &keywords, |(item, occurrences)| {
+            let first = occurrences
+                .iter()
+                .min_by_key(|o| (o.day, o.time.is_none(), o.time))
+                .map(|o| (o.day, o.time));
+            SortFields::new(item, first)
+        });
         if let Some(limit) = limit {
-            items.truncate(limit);
+            tasks.truncate(limit);
         }
 
+        let start = agenda_view_type.start_date().date_naive();
+        let end = agenda_view_type.end_date().date_naive();
+        let days = start
+            .iter_days()
+            .take_while(|day| *day <= end)
+            .map(|day| {
+                let mut entries = tasks
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(item, (_, occurrences))| {
+                        occurrences
+                            .iter()
+                            .filter(move |o| o.day == day)
+                            .map(move |o| AgendaEntry {
+                                item,
+                                reason: o.reason,
+                                date: o.date.format("%Y-%m-%d").to_string(),
+                                time: o.time.map(|t| t.format("%H:%M").to_string()),
+                            })
+                    })
+                    .collect::<Vec<_>>();
+                sort_agenda(&mut entries, sort, &keywords, |entry| {
+                    let time = entry
+                        .time
+                        .as_deref()
+                        .and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok());
+                    SortFields::new(&tasks[entry.item].0, Some((day, time)))
+                });
+                AgendaDay {
+                    date: day.format("%Y-%m-%d").to_string(),
+                    entries,
+                }
+            })
+            .collect();
+
         Ok(AgendaView {
-            items,
-            start_date: Some(agenda_view_type.start_date().format("%Y-%m-%d").to_string()),
-            end_date: Some(agenda_view_type.end_date().format("%Y-%m-%d").to_string()),
+            items: tasks.into_iter().map(|(item, _)| item).collect(),
+            start_date: Some(start.format("%Y-%m-%d").to_string()),
+            end_date: Some(end.format("%Y-%m-%d").to_string()),
+            days,
         })
     }
 
-    fn is_in_agenda_range(&self, headline: &Headline, agenda_view_type: &AgendaViewType) -> bool {
-        let start_date = agenda_view_type.start_date();
-        let end_date = agenda_view_type.end_date();
-
+    /// The SCHEDULED, DEADLINE and other active timestamps of `headline`'s
+    /// own entry, in that order.
+    fn entry_timestamps(headline: &Headline) -> Vec<(Timestamp, AgendaReason)> {
         let timestamps = headline
             .syntax()
             .children()
@@ -521,51 +1014,135 @@ impl OrgMode {
                 headline.scheduled().map(|s| &s != ts).unwrap_or(true)
                     && headline.deadline().map(|s| &s != ts).unwrap_or(true)
             })
-            .collect::<Vec<_>>();
+            .map(|ts| (ts, AgendaReason::Timestamp));
+        headline
+            .scheduled()
+            .map(|ts| (ts, AgendaReason::Scheduled))
+            .into_iter()
+            .chain(headline.deadline().map(|ts| (ts, AgendaReason::Deadline)))
+            .chain(timestamps)
+            .collect()
+    }
 
-        let is_within_range = |ts_opt: Option<Timestamp>| {
-            if let Some(ts) = ts_opt
-                && let Some(date) = OrgMode::start_to_chrono(&ts)
-                && let Some(date) = match Local.from_local_datetime(&date) {
-                    chrono::LocalResult::Single(t) => Some(t),
-                    chrono::LocalResult::Ambiguous(t, _) => Some(t),
-                    chrono::LocalResult::None => {
-                        let dt_plus_1 = date + chrono::Duration::hours(1);
-                        match Local.from_local_datetime(&dt_plus_1) {
-                            chrono::LocalResult::Single(t) => Some(t),
-                            chrono::LocalResult::Ambiguous(t, _) => Some(t),
-                            chrono::LocalResult::None => None,
-                        }
-                    }
-                }
+    /// Where `headline` lands in the view and why: its SCHEDULED, DEADLINE
+    /// and other active timestamps, repeaters expanded over the range and
+    /// ranges over every day they cover, the days its diary sexps match,
+    /// plus, when the view covers today, past timestamps of an open task
+    /// carried over (`Today` only) and its deadline within the warning
+    /// period.
+    fn agenda_occurrences(
+        &self,
+        headline: &Headline,
+        agenda_view_type: &AgendaViewType,
+    ) -> Vec<AgendaOccurrence> {
+        let start_date = agenda_view_type.start_date();
+        let end_date = agenda_view_type.end_date();
+        let view_days = start_date.date_naive()..=end_date.date_naive();
+        let today = Local::now().date_naive();
+        let carry_overdue = matches!(agenda_view_type, AgendaViewType::Today) && headline.is_todo();
+
+        let mut occurrences = Vec::new();
+        let mut push = |occurrence: AgendaOccurrence| {
+            if !occurrences.contains(&occurrence) {
+                occurrences.push(occurrence);
+            }
+        };
+        for (ts, reason) in Self::entry_timestamps(headline) {
+            let Some(naive) = OrgMode::start_to_chrono(&ts) else {
+                continue;
+            };
+            let Ok(date) = Self::naive_date_to_local(naive.date(), naive.hour(), naive.minute(), 0)
+            else {
+                continue;
+            };
+            let time = ts.hour_start().is_some().then(|| naive.time());
+            // Days a date range covers after its first.
+            let extra_days = Self::timestamp_span(&ts.raw())
+                .map_or(0, |span| (span.end - span.start).num_days().max(0));
+
+            let mut starts = vec![date];
+            let repeats = ts.repeater_value().is_some();
+            if let Some(repeater_value) = ts.repeater_value()
+                && let Some(repeater_unit) = ts.repeater_unit()
             {
-                if let Some(repeater_value) = ts.repeater_value()
-                    && let Some(repeater_unit) = ts.repeater_unit()
-                {
-                    let value = repeater_value as u64;
-                    let mut current_date =
-                        OrgMode::add_repeater_duration(date, value, &repeater_unit);
-
-                    while current_date < start_date {
-                        current_date =
-                            OrgMode::add_repeater_duration(current_date, value, &repeater_unit);
+                let value = repeater_value as u64;
+                let mut current = date;
+                loop {
+                    let next = OrgMode::add_repeater_duration(current, value, &repeater_unit);
+                    if next <= current || next > end_date {
+                        break;
                     }
+                    starts.push(next);
+                    current = next;
+                }
+            }
 
-                    current_date >= start_date && current_date <= end_date
-                } else {
-                    let is_past_todo = matches!(agenda_view_type, AgendaViewType::Today)
-                        && date < start_date
-                        && headline.is_todo();
-                    is_past_todo || (date >= start_date && date <= end_date)
+            let mut listed = false;
+            for current in &starts {
+                for offset in 0..=extra_days {
+                    let Some(day) = current
+                        .date_naive()
+                        .checked_add_days(Days::new(offset as u64))
+                    else {
+                        continue;
+                    };
+                    if !view_days.contains(&day) {
+                        continue;
+                    }
+                    listed = true;
+                    push(AgendaOccurrence {
+                        day,
+                        date: current.date_naive(),
+                        time: if offset == 0 { time } else { None },
+                        reason: if *current == date {
+                            reason
+                        } else {
+                            AgendaReason::Repeat
+                        },
+                    });
                 }
-            } else {
-                false
             }
-        };
+            if !listed && !repeats && carry_overdue && date < start_date {
+                push(AgendaOccurrence {
+                    day: start_date.date_naive(),
+                    date: date.date_naive(),
+                    time: None,
+                    reason: AgendaReason::Overdue,
+                });
+            }
+        }
+
+        for entry in diary::diary_entries(entry_text(&headline.raw())) {
+            let days = view_days.start().iter_days();
+            let days = days.take_while(|day| view_days.contains(day));
+            for day in days.filter(|day| entry.sexp.matches(*day)) {
+                push(AgendaOccurrence {
+                    day,
+                    date: day,
+                    time: entry.time,
+                    reason: AgendaReason::Diary,
+                });
+            }
+        }
 
-        is_within_range(headline.scheduled())
-            || is_within_range(headline.deadline())
-            || timestamps.into_iter().any(|ts| is_within_range(Some(ts)))
+        if headline.is_todo()
+            && view_days.contains(&today)
+            && let Some(ts) = headline.deadline()
+            && let Some(due) = OrgMode::start_to_chrono(&ts)
+        {
+            let warning =
+                Self::deadline_warning_days(&ts.raw(), self.config.org_deadline_warning_days);
+            // A deadline due today already has its own entry.
+            if (1..=warning).contains(&(due.date() - today).num_days()) {
+                push(AgendaOccurrence {
+                    day: today,
+                    date: due.date(),
+                    time: None,
+                    reason: AgendaReason::Upcoming,
+                });
+            }
+        }
+        occurrences
     }
 
     pub fn start_to_chrono(ts: &Timestamp) -> Option<chrono::NaiveDateTime> {
@@ -576,9 +1153,9 @@ impl OrgMode {
         convert_timestamp!(ts, end)
     }
 
-    fn headline_to_agenda_item(
+    pub(crate) fn headline_to_agenda_item(
         headline: &Headline,
-        file_path: String,
+        context: TaskContext,
         reference_date: Option<DateTime<Local>>,
     ) -> AgendaItem {
         let days_overdue = reference_date.and_then(|today| {
@@ -593,20 +1170,42 @@ impl OrgMode {
             }
         });
 
+        let today = reference_date.unwrap_or_else(Local::now).date_naive();
+        let days_until_deadline = headline
+            .deadline()
+            .and_then(|ts| OrgMode::start_to_chrono(&ts))
+            .map(|due| (due.date() - today).num_days());
+
+        let span = Self::entry_timestamps(headline)
+            .iter()
+            .filter_map(|(ts, _)| Self::timestamp_span(&ts.raw()))
+            .find(TimestampSpan::is_range);
+        let bound = |date: NaiveDate, time: Option<NaiveTime>| match time {
+            Some(time) => format!("{} {}", date.format("%Y-%m-%d"), time.format("%H:%M")),
+            None => date.format("%Y-%m-%d").to_string(),
+        };
+
         AgendaItem {
-            file_path,
-            heading: headline.title_raw(),
+            file_path: context.file_path,
+            heading: headline.title_raw().trim_end().to_string(),
             level: headline.level(),
             todo_state: headline.todo_keyword().map(|t| t.to_string()),
             priority: headline.priority().map(|p| p.to_string()),
             deadline: headline.deadline().map(|d| d.raw()),
             scheduled: headline.scheduled().map(|d| d.raw()),
             tags: headline.tags().map(|s| s.to_string()).collect(),
+            inherited_tags: context.inherited_tags,
+            category: context.category,
+            properties: context.properties,
             position: Some(Position {
                 start: headline.start().into(),
                 end: headline.end().into(),
             }),
             days_overdue,
+            days_until_deadline,
+            start: span.map(|span| bound(span.start, span.start_time)),
+            end: span.map(|span| bound(span.end, span.end_time)),
+            duration_minutes: span.map(|span| span.duration_minutes()),
         }
     }
 }
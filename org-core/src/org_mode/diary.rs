@@ -0,0 +1,293 @@
+//! Diary sexp timestamps such as `<%%(diary-anniversary 5 14 1990)>`.
+//!
+//! Only the common forms are evaluated: `diary-anniversary`, `diary-date`,
+//! `diary-float`, `diary-block`, `diary-cyclic` and `org-class`. Dates are
+//! read month first, Emacs' default `calendar-date-style`, except for
+//! `org-class`, which always takes year, month, day.
+
+use chrono::{Datelike, Days, NaiveDate, NaiveTime};
+
+/// A month, day or year argument: a number, or `t` for any.
+type Field = Option<i64>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum DiarySexp {
+    /// `(diary-anniversary MONTH DAY [YEAR])`: every year on that day,
+    /// after YEAR. February 29 falls on March 1 in common years.
+    Anniversary { month: u32, day: u32, year: Field },
+    /// `(diary-date MONTH DAY YEAR)`.
+    Date {
+        month: Field,
+        day: Field,
+        year: Field,
+    },
+    /// `(diary-float MONTH DAYNAME N [DAY])`: the Nth DAYNAME (0 is Sunday)
+    /// on or after DAY of the month, or counting back from DAY, by default
+    /// the month's last day, when N is negative.
+    Float {
+        month: Field,
+        weekday: u32,
+        n: i64,
+        day: Option<u32>,
+    },
+    /// `(diary-block M1 D1 Y1 M2 D2 Y2)`: every day between both dates.
+    Block { start: NaiveDate, end: NaiveDate },
+    /// `(diary-cyclic N MONTH DAY YEAR)`: every N days from that date.
+    Cyclic { every: u64, start: NaiveDate },
+    /// `(org-class Y1 M1 D1 Y2 M2 D2 DAYNAME SKIP-WEEKS...)`: DAYNAME of
+    /// each week between both dates, except the listed ISO weeks.
+    Class {
+        start: NaiveDate,
+        end: NaiveDate,
+        weekday: u32,
+        skip_weeks: Vec<u32>,
+    },
+}
+
+/// A diary sexp timestamp with the time of day that may follow the sexp,
+/// as in `<%%(diary-float t 4 2) 18:00>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiaryEntry {
+    pub(crate) sexp: DiarySexp,
+    pub(crate) time: Option<NaiveTime>,
+}
+
+/// The diary sexp timestamps of `text` this module can evaluate.
+pub(crate) fn diary_entries(text: &str) -> Vec<DiaryEntry> {
+    text.match_indices("<%%(")
+        .filter_map(|(at, _)| {
+            let inner = &text[at + 3..];
+            let inner = &inner[..inner.find('>')?];
+            let close = inner.rfind(')')?;
+            Some(DiaryEntry {
+                sexp: DiarySexp::parse(&inner[..=close])?,
+                time: inner[close + 1..]
+                    .split_whitespace()
+                    .next()
+                    .and_then(|t| NaiveTime::parse_from_str(t.split('-').next()?, "%H:%M").ok()),
+            })
+        })
+        .collect()
+}
+
+impl DiarySexp {
+    /// Parses a `(function args...)` form with plain number or `t`
+    /// arguments.
+    pub(crate) fn parse(sexp: &str) -> Option<Self> {
+        let body = sexp.trim().strip_prefix('(')?.strip_suffix(')')?;
+        let mut tokens = body.split_whitespace();
+        let function = tokens.next()?;
+        let args = tokens
+            .map(|tok| match tok {
+                "t" => Some(None),
+                _ => tok.parse::<i64>().ok().map(Some),
+            })
+            .collect::<Option<Vec<Field>>>()?;
+
+        let number = |i: usize| args.get(i).copied().flatten();
+        let date = |month: usize, day: usize, year: usize| {
+            NaiveDate::from_ymd_opt(
+                i32::try_from(number(year)?).ok()?,
+                u32::try_from(number(month)?).ok()?,
+                u32::try_from(number(day)?).ok()?,
+            )
+        };
+        let weekday = |i: usize| u32::try_from(number(i)?).ok().filter(|d| *d < 7);
+
+        Some(match (function, args.len()) {
+            ("diary-anniversary", 2 | 3) => DiarySexp::Anniversary {
+                month: u32::try_from(number(0)?).ok()?,
+                day: u32::try_from(number(1)?).ok()?,
+                year: number(2),
+            },
+            ("diary-date", 3) => DiarySexp::Date {
+                month: args[0],
+                day: args[1],
+                year: args[2],
+            },
+            ("diary-float", 3 | 4) => DiarySexp::Float {
+                month: args[0],
+                weekday: weekday(1)?,
+                n: number(2).filter(|n| *n != 0)?,
+                day: match args.get(3) {
+                    Some(day) => Some(u32::try_from((*day)?).ok()?),
+                    None => None,
+                },
+            },
+            ("diary-block", 6) => DiarySexp::Block {
+                start: date(0, 1, 2)?,
+                end: date(3, 4, 5)?,
+            },
+            ("diary-cyclic", 4) => DiarySexp::Cyclic {
+                every: u64::try_from(number(0)?).ok().filter(|n| *n > 0)?,
+                start: date(1, 2, 3)?,
+            },
+            ("org-class", n) if n >= 7 => DiarySexp::Class {
+                start: date(1, 2, 0)?,
+                end: date(4, 5, 3)?,
+                weekday: weekday(6)?,
+                skip_weeks: (7..n)
+                    .map(|i| u32::try_from(number(i)?).ok())
+                    .collect::<Option<_>>()?,
+            },
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn matches(&self, date: NaiveDate) -> bool {
+        let field = |field: Field, value: i64| field.is_none_or(|f| f == value);
+        let weekday = date.weekday().num_days_from_sunday();
+        match self {
+            DiarySexp::Anniversary { month, day, year } => {
+                let leap_day = (*month, *day) == (2, 29) && !date.leap_year();
+                let (month, day) = if leap_day { (3, 1) } else { (*month, *day) };
+                date.month() == month
+                    && date.day() == day
+                    && year.is_none_or(|year| i64::from(date.year()) > year)
+            }
+            DiarySexp::Date { month, day, year } => {
+                field(*month, i64::from(date.month()))
+                    && field(*day, i64::from(date.day()))
+                    && field(*year, i64::from(date.year()))
+            }
+            DiarySexp::Float {
+                month,
+                weekday: dayname,
+                n,
+                day,
+            } => {
+                field(*month, i64::from(date.month()))
+                    && Self::nth_weekday(date, *dayname, *n, *day) == Some(date)
+            }
+            DiarySexp::Block { start, end } => (*start..=*end).contains(&date),
+            DiarySexp::Cyclic { every, start } => {
+                date >= *start && ((date - *start).num_days() as u64).is_multiple_of(*every)
+            }
+            DiarySexp::Class {
+                start,
+                end,
+                weekday: dayname,
+                skip_weeks,
+            } => {
+                (*start..=*end).contains(&date)
+                    && weekday == *dayname
+                    && !skip_weeks.contains(&date.iso_week().week())
+            }
+        }
+    }
+
+    /// The `n`th `dayname` of `date`'s month, counted from `day`.
+    fn nth_weekday(date: NaiveDate, dayname: u32, n: i64, day: Option<u32>) -> Option<NaiveDate> {
+        let first = date.with_day(1)?;
+        if n > 0 {
+            let base = first.with_day(day.unwrap_or(1))?;
+            let offset = (7 + dayname - base.weekday().num_days_from_sunday()) % 7;
+            base.checked_add_days(Days::new(u64::from(offset) + 7 * (n as u64 - 1)))
+        } else {
+            let last = first
+                .checked_add_months(chrono::Months::new(1))?
+                .pred_opt()?;
+            let base = match day {
+                Some(day) => first.with_day(day)?,
+                None => last,
+            };
+            let offset = (7 + base.weekday().num_days_from_sunday() - dayname) % 7;
+            base.checked_sub_days(Days::new(u64::from(offset) + 7 * (n.unsigned_abs() - 1)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn days(sexp: &str, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let sexp = DiarySexp::parse(sexp).expect("sexp parses");
+        from.iter_days()
+            .take_while(|d| *d <= to)
+            .filter(|d| sexp.matches(*d))
+            .collect()
+    }
+
+    #[test]
+    fn test_anniversary_and_date() {
+        let range = (date(1989, 1, 1), date(1992, 12, 31));
+        assert_eq!(
+            days("(diary-anniversary 5 14 1990)", range.0, range.1),
+            [date(1991, 5, 14), date(1992, 5, 14)]
+        );
+        assert_eq!(
+            days("(diary-date t 1 1992)", range.0, range.1),
+            (1..=12).map(|m| date(1992, m, 1)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_leap_day_anniversary_moves_to_march_first() {
+        assert_eq!(
+            days(
+                "(diary-anniversary 2 29 2000)",
+                date(2000, 1, 1),
+                date(2005, 12, 31)
+            ),
+            [
+                date(2001, 3, 1),
+                date(2002, 3, 1),
+                date(2003, 3, 1),
+                date(2004, 2, 29),
+                date(2005, 3, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_float() {
+        let (from, to) = (date(2026, 1, 1), date(2026, 12, 31));
+        // Second Thursday of each month.
+        let second_thursdays = days("(diary-float t 4 2)", from, to);
+        assert_eq!(second_thursdays.len(), 12);
+        assert_eq!(second_thursdays[0], date(2026, 1, 8));
+        // Last Monday of May.
+        assert_eq!(days("(diary-float 5 1 -1)", from, to), [date(2026, 5, 25)]);
+        // First Tuesday on or after November 2nd.
+        assert_eq!(
+            days("(diary-float 11 2 1 2)", from, to),
+            [date(2026, 11, 3)]
+        );
+    }
+
+    #[test]
+    fn test_block_cyclic_and_class() {
+        let (from, to) = (date(2026, 3, 1), date(2026, 3, 31));
+        assert_eq!(
+            days("(diary-block 3 30 2026 4 2 2026)", from, to),
+            [date(2026, 3, 30), date(2026, 3, 31)]
+        );
+        assert_eq!(
+            days("(diary-cyclic 10 3 5 2026)", from, to),
+            [date(2026, 3, 5), date(2026, 3, 15), date(2026, 3, 25)]
+        );
+        // Wednesdays of March, skipping ISO week 11.
+        assert_eq!(
+            days("(org-class 2026 3 1 2026 3 31 3 11)", from, to),
+            [date(2026, 3, 4), date(2026, 3, 18), date(2026, 3, 25)]
+        );
+    }
+
+    #[test]
+    fn test_diary_entries_in_text() {
+        let entries = diary_entries(
+            "* Mom's birthday <%%(diary-anniversary 5 14 1990)>\n\
+             <%%(diary-float t 4 2) 18:00-19:00>\n<%%(unknown-form 1)>\n",
+        );
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].time, None);
+        assert_eq!(entries[1].time, NaiveTime::from_hms_opt(18, 0, 0));
+        assert!(DiarySexp::parse("(diary-float t 7 1)").is_none());
+        assert!(DiarySexp::parse("(diary-date '(1 2) 1 t)").is_none());
+    }
+}
@@ -0,0 +1,281 @@
+//! Inverted index over heading sections, ranked with BM25.
+//!
+//! Each file keeps its own postings in the metadata index, so a changed file
+//! only re-indexes itself; collection statistics are summed over the files
+//! searched at query time.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::org_mode::clock::HeadlineSection;
+
+/// BM25 term frequency saturation.
+const K1: f64 = 1.2;
+/// BM25 length normalisation.
+const B: f64 = 0.75;
+/// How many body occurrences one title occurrence is worth.
+const TITLE_BOOST: f64 = 3.0;
+
+const STOPWORDS: &[&str] = &[
+    "a", "about", "an", "and", "are", "as", "at", "be", "but", "by", "did", "do", "does", "for",
+    "from", "had", "has", "have", "how", "i", "if", "in", "into", "is", "it", "its", "of", "on",
+    "or", "our", "so", "than", "that", "the", "their", "then", "there", "these", "this", "to",
+    "was", "we", "were", "what", "when", "where", "which", "who", "why", "will", "with", "you",
+];
+
+/// Lowercased, stemmed words of `text`, without stopwords.
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .map(|word| stem(&word))
+        .collect()
+}
+
+/// A light suffix-stripping stemmer for English, enough for `migrations`,
+/// `migrating` and `migrated` to meet at `migrat`.
+fn stem(word: &str) -> String {
+    const PLURALS: &[(&str, &str)] = &[("sses", "ss"), ("ies", "y")];
+    const SUFFIXES: &[(&str, &str)] = &[
+        ("ational", "ate"),
+        ("ization", "ize"),
+        ("ation", "ate"),
+        ("ness", ""),
+        ("ing", ""),
+        ("ed", ""),
+        ("ly", ""),
+    ];
+
+    let strip = |word: &str, rules: &[(&str, &str)]| -> Option<String> {
+        rules.iter().find_map(|(suffix, replacement)| {
+            let stem = word.strip_suffix(suffix)?;
+            (stem.chars().count() >= 3).then(|| format!("{stem}{replacement}"))
+        })
+    };
+
+    let mut word = match strip(word, PLURALS) {
+        Some(word) => word,
+        None if ["ss", "us", "is"].iter().any(|end| word.ends_with(end)) => word.to_string(),
+        None => match word.strip_suffix('s') {
+            Some(stem) if stem.chars().count() >= 3 => stem.to_string(),
+            _ => word.to_string(),
+        },
+    };
+    if let Some(stripped) = strip(&word, SUFFIXES) {
+        // `planning` -> `plann` -> `plan`, but `falling` stays `fall`.
+        let mut chars = stripped.chars().rev();
+        word = match (chars.next(), chars.next()) {
+            (Some(a), Some(b)) if a == b && !"aeioulsz".contains(a) => {
+                stripped[..stripped.len() - a.len_utf8()].to_string()
+            }
+            _ => stripped,
+        };
+    }
+    if word.chars().count() > 3
+        && let Some(stem) = word.strip_suffix('e')
+    {
+        word = stem.to_string();
+    }
+    word
+}
+
+/// One term's occurrences in one section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Posting {
+    pub section: u32,
+    pub title: u32,
+    pub body: u32,
+}
+
+/// The postings of one file. Section 0 is the text before the first
+/// headline and section `i` the own section of the `i`-th headline.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SectionIndex {
+    /// Token count of each section.
+    pub lengths: Vec<u32>,
+    pub postings: BTreeMap<String, Vec<Posting>>,
+}
+
+impl SectionIndex {
+    pub(crate) fn build(content: &str, headlines: &[HeadlineSection]) -> Self {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut index = SectionIndex::default();
+
+        for section in 0..=headlines.len() {
+            let (start, title) = match section.checked_sub(1) {
+                Some(i) => (headlines[i].line + 1, tokenize(&headlines[i].title)),
+                None => (0, Vec::new()),
+            };
+            let end = headlines.get(section).map_or(lines.len(), |h| h.line);
+            let body: Vec<String> = lines[start.min(end)..end]
+                .iter()
+                .flat_map(|line| tokenize(line))
+                .collect();
+
+            let mut counts: BTreeMap<&str, (u32, u32)> = BTreeMap::new();
+            for term in &title {
+                counts.entry(term).or_default().0 += 1;
+            }
+            for term in &body {
+                counts.entry(term).or_default().1 += 1;
+            }
+            for (term, (title, body)) in counts {
+                index
+                    .postings
+                    .entry(term.to_string())
+                    .or_default()
+                    .push(Posting {
+                        section: section as u32,
+                        title,
+                        body,
+                    });
+            }
+            index.lengths.push((title.len() + body.len()) as u32);
+        }
+        index
+    }
+}
+
+/// Ranks the sections of several files against `query` with BM25, title
+/// occurrences counting [`TITLE_BOOST`] times. Statistics cover every
+/// section of `files`, but only the sections `allowed` marks are returned,
+/// as `(file, section, score)`.
+pub(crate) fn rank(query: &str, files: &[(&SectionIndex, &[bool])]) -> Vec<(usize, usize, f64)> {
+    let mut terms = tokenize(query);
+    terms.sort();
+    terms.dedup();
+
+    let sections: usize = files.iter().map(|(index, _)| index.lengths.len()).sum();
+    if sections == 0 || terms.is_empty() {
+        return Vec::new();
+    }
+    let total_length: u64 = files
+        .iter()
+        .flat_map(|(index, _)| &index.lengths)
+        .map(|&len| u64::from(len))
+        .sum();
+    let average_length = (total_length as f64 / sections as f64).max(1.0);
+
+    let mut scores: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+    for term in &terms {
+        let frequency: usize = files
+            .iter()
+            .filter_map(|(index, _)| index.postings.get(term))
+            .map(Vec::len)
+            .sum();
+        if frequency == 0 {
+            continue;
+        }
+        let idf =
+            (1.0 + (sections as f64 - frequency as f64 + 0.5) / (frequency as f64 + 0.5)).ln();
+
+        for (file, (index, allowed)) in files.iter().enumerate() {
+            for posting in index.postings.get(term).into_iter().flatten() {
+                let section = posting.section as usize;
+                if !allowed.get(section).copied().unwrap_or(false) {
+                    continue;
+                }
+                let tf = f64::from(posting.body) + TITLE_BOOST * f64::from(posting.title);
+                let length = f64::from(index.lengths[section]);
+                let norm = K1 * (1.0 - B + B * length / average_length);
+                *scores.entry((file, section)).or_default() += idf * tf * (K1 + 1.0) / (tf + norm);
+            }
+        }
+    }
+
+    scores
+        .into_iter()
+        .map(|((file, section), score)| (file, section, score))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::org_mode::Position;
+
+    fn headline(line: usize, title: &str) -> HeadlineSection {
+        HeadlineSection {
+            line,
+            level: 1,
+            position: Position { start: 0, end: 0 },
+            title: title.to_string(),
+            path: title.to_string(),
+            todo_keyword: None,
+            tags: Vec::new(),
+            id: None,
+        }
+    }
+
+    #[test]
+    fn test_tokenize_stems_and_drops_stopwords() {
+        assert_eq!(
+            tokenize("What did we decide about the DB migrations?"),
+            ["decid", "db", "migrat"]
+        );
+        for word in ["migration", "migrating", "migrated", "migrate"] {
+            assert_eq!(stem(word), "migrat", "{word}");
+        }
+        assert_eq!(stem("planning"), "plan");
+        assert_eq!(stem("class"), "class");
+        assert_eq!(stem("queries"), "query");
+        assert_eq!(stem("thing"), "thing");
+    }
+
+    #[test]
+    fn test_build_counts_title_and_body_per_section() {
+        let content = "Migration notes\n* DB migration\nWe migrated the DB.\n* Lunch\nPizza\n";
+        let index = SectionIndex::build(
+            content,
+            &[headline(1, "DB migration"), headline(3, "Lunch")],
+        );
+
+        assert_eq!(index.lengths, [2, 4, 2]);
+        assert_eq!(
+            index.postings["migrat"],
+            [
+                Posting {
+                    section: 0,
+                    title: 0,
+                    body: 1
+                },
+                Posting {
+                    section: 1,
+                    title: 1,
+                    body: 1
+                },
+            ]
+        );
+        assert_eq!(
+            index.postings["pizza"],
+            [Posting {
+                section: 2,
+                title: 0,
+                body: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rank_prefers_titles_and_honours_allowed() {
+        let a = SectionIndex::build(
+            "* DB migration\nMoved to Postgres.\n* Notes\nThe migration is mentioned here once among many other words entirely\n",
+            &[headline(0, "DB migration"), headline(2, "Notes")],
+        );
+        let b = SectionIndex::build("* Lunch\nPizza\n", &[headline(0, "Lunch")]);
+
+        let all = [true; 3];
+        let mut ranked = rank("db migration", &[(&a, &all), (&b, &all[..2])]);
+        ranked.sort_by(|x, y| y.2.total_cmp(&x.2));
+        let order: Vec<(usize, usize)> = ranked.iter().map(|&(f, s, _)| (f, s)).collect();
+        assert_eq!(order, [(0, 1), (0, 2)]);
+
+        let ranked = rank("migration", &[(&a, &[true, false, true]), (&b, &all[..2])]);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!((ranked[0].0, ranked[0].1), (0, 2));
+
+        assert!(rank("the", &[(&a, &all)]).is_empty());
+    }
+}
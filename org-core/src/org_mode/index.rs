@@ -0,0 +1,593 @@
+//! Per-file metadata (headlines, IDs, tags, links and full-text postings)
+//! kept in memory and persisted under `org_cache_directory`, so queries only
+//! re-parse the files whose size or modification time changed since they
+//! were last indexed.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use shellexpand::tilde;
+
+use crate::OrgModeError;
+use crate::config::OrgConfig;
+use crate::org_mode::clock::HeadlineSection;
+use crate::org_mode::fulltext::SectionIndex;
+use crate::org_mode::links::{RawLink, parse_links};
+use crate::org_mode::tags::file_tags;
+use crate::org_mode::{FileChanges, OrgMode};
+
+/// Bumped whenever the cached layout changes, so older caches are dropped.
+const INDEX_VERSION: u32 = 7;
+
+/// Files modified this recently when indexed may change again within the
+/// same mtime tick without changing size, so they are re-parsed next time.
+const RACY_WINDOW: Duration = Duration::from_secs(2);
+
+/// Longest context snippet stored with a link, in characters.
+const CONTEXT_MAX_CHARS: usize = 160;
+
+/// Size and modification time of a file when it was indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    size: u64,
+    modified_secs: u64,
+    modified_nanos: u32,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<Self> {
+        let meta = fs::metadata(path).ok()?;
+        let modified = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            size: meta.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+        })
+    }
+
+    fn is_racy(&self) -> bool {
+        let modified = Duration::new(self.modified_secs, self.modified_nanos);
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .is_ok_and(|now| now.saturating_sub(modified) < RACY_WINDOW)
+    }
+}
+
+/// A link found while indexing a file, with its zero-based line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct FileLink {
+    pub line: usize,
+    pub link: RawLink,
+    pub context: String,
+}
+
+/// What the index knows about one org file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct FileMetadata {
+    stamp: FileStamp,
+    racy: bool,
+    /// Tells real edits apart from re-parses of untouched files.
+    content_hash: u64,
+    /// `:ID:` of the document itself, set before the first headline.
+    pub doc_id: Option<String>,
+    /// Tags from `#+FILETAGS:`, which every headline inherits.
+    pub file_tags: Vec<String>,
+    pub headlines: Vec<HeadlineSection>,
+    pub links: Vec<FileLink>,
+    /// Postings of each headline's own section, for ranked search.
+    pub full_text: SectionIndex,
+    /// Whether the file holds an active timestamp or diary sexp, which
+    /// date views list even on headings that are not tasks.
+    pub has_timestamps: bool,
+}
+
+impl FileMetadata {
+    /// File tags, then every headline tag in document order, repeats
+    /// included.
+    pub(crate) fn tags(&self) -> Vec<String> {
+        self.file_tags
+            .iter()
+            .chain(self.headlines.iter().flat_map(|h| &h.tags))
+            .cloned()
+            .collect()
+    }
+
+    fn has_id(&self, id: &str) -> bool {
+        self.doc_id.as_deref() == Some(id)
+            || self.headlines.iter().any(|h| h.id.as_deref() == Some(id))
+    }
+
+    fn has_tasks(&self) -> bool {
+        self.headlines.iter().any(|h| h.todo_keyword.is_some())
+    }
+}
+
+/// Files changed since the last [`OrgMode::refresh`], collapsed so a file
+/// added and removed in between is not reported at all.
+#[derive(Debug, Default)]
+struct PendingChanges {
+    added: BTreeSet<String>,
+    modified: BTreeSet<String>,
+    removed: BTreeSet<String>,
+}
+
+impl PendingChanges {
+    fn record_added(&mut self, file: String) {
+        if self.removed.remove(&file) {
+            self.modified.insert(file);
+        } else {
+            self.added.insert(file);
+        }
+    }
+
+    fn record_modified(&mut self, file: String) {
+        if !self.added.contains(&file) {
+            self.modified.insert(file);
+        }
+    }
+
+    fn record_removed(&mut self, file: String) {
+        self.modified.remove(&file);
+        if !self.added.remove(&file) {
+            self.removed.insert(file);
+        }
+    }
+}
+
+/// The metadata of every org file under the org directory, keyed by path
+/// relative to it.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct MetadataIndex {
+    version: u32,
+    org_directory: String,
+    /// See [`indexing_config_hash`].
+    config_hash: u64,
+    pub files: BTreeMap<String, FileMetadata>,
+    #[serde(skip)]
+    pending: PendingChanges,
+    /// Agenda files outside the org directory, as of the last refresh.
+    #[serde(skip)]
+    external: BTreeMap<PathBuf, FileStamp>,
+    /// Every agenda file, as of the last refresh.
+    #[serde(skip)]
+    agenda: HashSet<PathBuf>,
+}
+
+impl MetadataIndex {
+    /// Loads the index persisted for `config`, or an empty one when there is
+    /// none or it was written by another version, for another directory or
+    /// with other TODO keywords.
+    pub(crate) fn load(config: &OrgConfig) -> Self {
+        let empty = Self {
+            version: INDEX_VERSION,
+            org_directory: config.org_directory.clone(),
+            config_hash: indexing_config_hash(config),
+            files: BTreeMap::new(),
+            pending: PendingChanges::default(),
+            external: BTreeMap::new(),
+            agenda: HashSet::new(),
+        };
+        cache_path(config)
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str::<Self>(&json).ok())
+            .filter(|index| {
+                index.version == empty.version
+                    && index.org_directory == empty.org_directory
+                    && index.config_hash == empty.config_hash
+            })
+            .unwrap_or(empty)
+    }
+
+    /// Persists the index. The cache is an optimisation only, so failures
+    /// are ignored and the next start simply re-parses everything.
+    fn save(&self, config: &OrgConfig) {
+        let Some(path) = cache_path(config) else {
+            return;
+        };
+        let Ok(json) = serde_json::to_string(self) else {
+            return;
+        };
+        if let Some(parent) = path.parent()
+            && fs::create_dir_all(parent).is_ok()
+        {
+            let _ = OrgMode::atomic_write(&path, json.as_bytes());
+        }
+    }
+}
+
+/// One cache file per org directory, or `None` when persistence is off.
+fn cache_path(config: &OrgConfig) -> Option<PathBuf> {
+    let dir = config.org_cache_directory.trim();
+    if dir.is_empty() {
+        return None;
+    }
+    let mut hasher = DefaultHasher::new();
+    config.org_directory.hash(&mut hasher);
+    Some(PathBuf::from(tilde(dir).as_ref()).join(format!("index-{:016x}.json", hasher.finish())))
+}
+
+/// Hash of the settings indexed data depends on: the TODO keywords, which
+/// decide what headlines count as tasks and where their titles start.
+fn indexing_config_hash(config: &OrgConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    config.unfinished_keywords().hash(&mut hasher);
+    config.finished_keywords().hash(&mut hasher);
+    hasher.finish()
+}
+
+impl OrgMode {
+    /// Brings the index up to date with the org directory and returns it.
+    pub(crate) fn metadata(&self) -> Result<MutexGuard<'_, MetadataIndex>, OrgModeError> {
+        let files = self.org_files()?;
+        Ok(self.refresh_index(&files))
+    }
+
+    /// Re-indexes the new or changed files among `files` and forgets the
+    /// ones no longer listed, persisting the index when anything changed.
+    pub(crate) fn refresh_index(&self, files: &[String]) -> MutexGuard<'_, MetadataIndex> {
+        let mut index = self.index.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut changed = false;
+
+        for file in files {
+            let Some(stamp) = FileStamp::of(&self.org_path(file)) else {
+                continue;
+            };
+            if index
+                .files
+                .get(file)
+                .is_some_and(|meta| meta.stamp == stamp && !meta.racy)
+            {
+                continue;
+            }
+            let Ok(content) = self.read_file(file) else {
+                continue;
+            };
+            let meta = self.index_file(&content, stamp);
+            let hash = meta.content_hash;
+            match index.files.insert(file.clone(), meta) {
+                None => index.pending.record_added(file.clone()),
+                Some(old) if old.content_hash != hash => {
+                    index.pending.record_modified(file.clone())
+                }
+                Some(_) => {}
+            }
+            changed = true;
+        }
+
+        let listed: HashSet<&String> = files.iter().collect();
+        let gone: Vec<String> = index
+            .files
+            .keys()
+            .filter(|file| !listed.contains(file))
+            .cloned()
+            .collect();
+        for file in gone {
+            index.files.remove(&file);
+            index.pending.record_removed(file);
+            changed = true;
+        }
+
+        if changed {
+            index.save(&self.config);
+        }
+        index
+    }
+
+    fn index_file(&self, content: &str, stamp: FileStamp) -> FileMetadata {
+        let headlines = self.headline_sections(content);
+        let first_headline = headlines.first().map_or(usize::MAX, |h| h.line);
+        let mut doc_id = None;
+        let mut links = Vec::new();
+
+        for (idx, line) in content.lines().enumerate() {
+            let trimmed = line.trim();
+            if idx < first_headline
+                && doc_id.is_none()
+                && trimmed
+                    .get(..4)
+                    .is_some_and(|key| key.eq_ignore_ascii_case(":ID:"))
+            {
+                doc_id = Some(trimmed[4..].trim().to_string());
+            }
+            let found = parse_links(line);
+            if found.is_empty() {
+                continue;
+            }
+            let context = OrgMode::snippet(line.trim(), CONTEXT_MAX_CHARS);
+            links.extend(found.into_iter().map(|link| FileLink {
+                line: idx,
+                link,
+                context: context.clone(),
+            }));
+        }
+
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+
+        FileMetadata {
+            stamp,
+            racy: stamp.is_racy(),
+            content_hash: hasher.finish(),
+            doc_id,
+            file_tags: file_tags(content),
+            full_text: SectionIndex::build(content, &headlines),
+            has_timestamps: content.match_indices('<').any(|(at, _)| {
+                let rest = &content[at + 1..];
+                rest.starts_with(|c: char| c.is_ascii_digit()) || rest.starts_with("%%(")
+            }),
+            headlines,
+            links,
+        }
+    }
+
+    /// Re-scans the org directory and the agenda files and returns what
+    /// changed since the previous call, including changes already picked
+    /// up by queries in between.
+    pub fn refresh(&self) -> Result<FileChanges, OrgModeError> {
+        let files = self.org_files()?;
+        let agenda = self.agenda_files();
+        let org_root = PathBuf::from(&self.config.org_directory);
+        let mut guard = self.refresh_index(&files);
+        let index = &mut *guard;
+
+        let external: BTreeMap<PathBuf, FileStamp> = agenda
+            .iter()
+            .filter(|path| !path.starts_with(&org_root))
+            .filter_map(|path| FileStamp::of(path).map(|stamp| (path.clone(), stamp)))
+            .collect();
+        for (path, stamp) in &external {
+            let file = path.to_string_lossy().into_owned();
+            match index.external.get(path) {
+                None => index.pending.record_added(file),
+                Some(old) if old != stamp => index.pending.record_modified(file),
+                Some(_) => {}
+            }
+        }
+        for path in index.external.keys() {
+            if !external.contains_key(path) {
+                index
+                    .pending
+                    .record_removed(path.to_string_lossy().into_owned());
+            }
+        }
+        index.external = external;
+
+        let pending = std::mem::take(&mut index.pending);
+        let agenda_changed = pending
+            .added
+            .iter()
+            .chain(&pending.modified)
+            .chain(&pending.removed)
+            .map(|file| org_root.join(file))
+            .any(|path| agenda.contains(&path) || index.agenda.contains(&path));
+        index.agenda = agenda;
+
+        Ok(FileChanges {
+            added: pending.added.into_iter().collect(),
+            modified: pending.modified.into_iter().collect(),
+            removed: pending.removed.into_iter().collect(),
+            agenda_changed,
+        })
+    }
+
+    /// The indexed file holding `id`, as a document or headline `:ID:`.
+    pub(crate) fn file_with_id(&self, id: &str) -> Result<Option<String>, OrgModeError> {
+        Ok(self
+            .metadata()?
+            .files
+            .iter()
+            .find(|(_, meta)| meta.has_id(id))
+            .map(|(file, _)| file.clone()))
+    }
+
+    /// Drops the agenda files the index knows to hold no TODO or DONE
+    /// headline. Files outside the org directory are always kept.
+    pub(crate) fn files_with_tasks(&self, files: HashSet<PathBuf>) -> Vec<PathBuf> {
+        self.indexed_files_where(files, FileMetadata::has_tasks)
+    }
+
+    /// Like [`OrgMode::files_with_tasks`], also keeping files with active
+    /// timestamps or diary sexps.
+    pub(crate) fn files_with_agenda_entries(&self, files: HashSet<PathBuf>) -> Vec<PathBuf> {
+        self.indexed_files_where(files, |meta| meta.has_tasks() || meta.has_timestamps)
+    }
+
+    fn indexed_files_where(
+        &self,
+        files: HashSet<PathBuf>,
+        keep: impl Fn(&FileMetadata) -> bool,
+    ) -> Vec<PathBuf> {
+        let Ok(index) = self.metadata() else {
+            return files.into_iter().collect();
+        };
+        let org_root = Path::new(&self.config.org_directory);
+        files
+            .into_iter()
+            .filter(|file| {
+                file.strip_prefix(org_root)
+                    .ok()
+                    .and_then(Path::to_str)
+                    .and_then(|rel| index.files.get(rel))
+                    .is_none_or(&keep)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_org_mode(org_dir: &tempfile::TempDir, cache_dir: &tempfile::TempDir) -> OrgMode {
+        OrgMode::new(OrgConfig {
+            org_directory: org_dir.path().to_str().unwrap().to_string(),
+            org_cache_directory: cache_dir.path().to_str().unwrap().to_string(),
+            ..OrgConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_index_tracks_changed_and_removed_files() {
+        let org_dir = tempfile::TempDir::new().unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        fs::write(org_dir.path().join("a.org"), "* TODO Task :work:\n").unwrap();
+        fs::write(org_dir.path().join("b.org"), "* Notes\n").unwrap();
+        let org_mode = make_org_mode(&org_dir, &cache_dir);
+
+        let work = vec!["work".to_string()];
+        assert_eq!(org_mode.list_files(Some(&work), None).unwrap(), ["a.org"]);
+
+        fs::write(
+            org_dir.path().join("b.org"),
+            "* Notes :work:\n:PROPERTIES:\n:ID: notes-1\n:END:\nSee [[id:other]].\n",
+        )
+        .unwrap();
+        fs::remove_file(org_dir.path().join("a.org")).unwrap();
+
+        assert_eq!(org_mode.list_files(Some(&work), None).unwrap(), ["b.org"]);
+        assert_eq!(
+            org_mode.file_with_id("notes-1").unwrap().as_deref(),
+            Some("b.org")
+        );
+
+        let index = org_mode.metadata().unwrap();
+        assert_eq!(index.files.keys().collect::<Vec<_>>(), ["b.org"]);
+        let links = &index.files["b.org"].links;
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].line, 4);
+        assert_eq!(links[0].link.target, "id:other");
+    }
+
+    #[test]
+    fn test_index_is_persisted_and_reused() {
+        let org_dir = tempfile::TempDir::new().unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            org_dir.path().join("notes.org"),
+            ":PROPERTIES:\n:ID: doc-1\n:END:\n* DONE Shipped\n",
+        )
+        .unwrap();
+
+        let org_mode = make_org_mode(&org_dir, &cache_dir);
+        assert_eq!(org_mode.metadata().unwrap().files.len(), 1);
+        let cached: Vec<_> = fs::read_dir(cache_dir.path()).unwrap().collect();
+        assert_eq!(cached.len(), 1);
+
+        let reloaded = make_org_mode(&org_dir, &cache_dir);
+        {
+            let index = reloaded.index.lock().unwrap();
+            let meta = &index.files["notes.org"];
+            assert_eq!(meta.doc_id.as_deref(), Some("doc-1"));
+            assert!(meta.has_tasks());
+        }
+        assert_eq!(
+            reloaded.get_element_by_id("doc-1").unwrap(),
+            ":PROPERTIES:\n:ID: doc-1\n:END:\n* DONE Shipped\n"
+        );
+    }
+
+    #[test]
+    fn test_index_is_dropped_when_todo_keywords_change() {
+        let org_dir = tempfile::TempDir::new().unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let file = org_dir.path().join("next.org");
+        fs::write(&file, "* NEXT Call Bob\n").unwrap();
+        // Old enough not to be re-parsed as racily modified.
+        fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+        let with_keywords = |keywords: &[&str]| {
+            OrgMode::new(OrgConfig {
+                org_directory: org_dir.path().to_str().unwrap().to_string(),
+                org_cache_directory: cache_dir.path().to_str().unwrap().to_string(),
+                org_todo_keywords: keywords.iter().map(|k| k.to_string()).collect(),
+                ..OrgConfig::default()
+            })
+            .unwrap()
+        };
+
+        let before = with_keywords(&["TODO", "|", "DONE"]);
+        assert!(
+            before
+                .list_tasks(None, None, None, None)
+                .unwrap()
+                .is_empty()
+        );
+
+        let after = with_keywords(&["TODO", "NEXT", "|", "DONE"]);
+        let tasks = after.list_tasks(None, None, None, None).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].heading, "Call Bob");
+    }
+
+    #[test]
+    fn test_empty_cache_directory_keeps_index_in_memory() {
+        let org_dir = tempfile::TempDir::new().unwrap();
+        fs::write(org_dir.path().join("notes.org"), "* Notes\n").unwrap();
+        let org_mode = OrgMode::new(OrgConfig {
+            org_directory: org_dir.path().to_str().unwrap().to_string(),
+            org_cache_directory: String::new(),
+            ..OrgConfig::default()
+        })
+        .unwrap();
+
+        assert!(cache_path(&org_mode.config).is_none());
+        assert_eq!(org_mode.metadata().unwrap().files.len(), 1);
+    }
+
+    #[test]
+    fn test_refresh_reports_changes_since_last_call() {
+        let org_dir = tempfile::TempDir::new().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+        let external = outside.path().join("work.org");
+        fs::write(org_dir.path().join("agenda.org"), "* TODO Call\n").unwrap();
+        fs::write(org_dir.path().join("notes.org"), "* Notes\n").unwrap();
+        fs::write(&external, "* TODO Report\n").unwrap();
+        let org_mode = OrgMode::new(OrgConfig {
+            org_directory: org_dir.path().to_str().unwrap().to_string(),
+            org_agenda_files: vec![
+                "agenda.org".to_string(),
+                external.to_str().unwrap().to_string(),
+            ],
+            org_cache_directory: String::new(),
+            ..OrgConfig::default()
+        })
+        .unwrap();
+
+        let first = org_mode.refresh().unwrap();
+        // Absolute paths sort first.
+        assert_eq!(
+            first.added,
+            [external.to_str().unwrap(), "agenda.org", "notes.org"]
+        );
+        assert!(org_mode.refresh().unwrap().is_empty());
+
+        // A query in between must not swallow the change.
+        fs::write(org_dir.path().join("notes.org"), "* Notes :idea:\n").unwrap();
+        fs::write(org_dir.path().join("new.org"), "* New\n").unwrap();
+        org_mode.list_files_by_tags(&["idea".to_string()]).unwrap();
+        let changes = org_mode.refresh().unwrap();
+        assert_eq!(changes.added, ["new.org"]);
+        assert_eq!(changes.modified, ["notes.org"]);
+        assert!(!changes.agenda_changed);
+
+        fs::remove_file(org_dir.path().join("agenda.org")).unwrap();
+        let changes = org_mode.refresh().unwrap();
+        assert_eq!(changes.removed, ["agenda.org"]);
+        assert!(changes.agenda_changed);
+
+        fs::write(&external, "* DONE Report sent\n").unwrap();
+        let changes = org_mode.refresh().unwrap();
+        assert_eq!(changes.modified, [external.to_str().unwrap()]);
+        assert!(changes.agenda_changed);
+    }
+}
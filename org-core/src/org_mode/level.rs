@@ -0,0 +1,196 @@
+use std::fs;
+
+use crate::OrgModeError;
+use crate::org_mode::capture::MAX_HEADING_LEVEL;
+use crate::org_mode::update::{headline_level, join_lines, shift_headline_levels};
+use crate::org_mode::{HeadingTarget, OrgMode, UpdateResult};
+
+impl OrgMode {
+    /// Changes the level of the target heading by `delta` (negative to
+    /// promote, positive to demote). With `subtree` every descendant moves
+    /// by the same amount; otherwise only the headline itself changes, as
+    /// with Emacs' `org-promote`/`org-demote`.
+    pub fn shift_level(
+        &self,
+        target: &HeadingTarget,
+        delta: isize,
+        subtree: bool,
+    ) -> Result<UpdateResult, OrgModeError> {
+        Self::validate_heading_target(target)?;
+        if delta == 0 {
+            return Err(OrgModeError::InvalidUpdate(
+                "level change must be non-zero".to_string(),
+            ));
+        }
+        let (file_rel, full_path) = self.resolve_target_file(target)?;
+
+        Self::with_capture_lock(&full_path, || {
+            let content = fs::read_to_string(&full_path).map_err(OrgModeError::IoError)?;
+            let headline = self.locate_target(&content, target)?;
+
+            let new_level = headline.level as isize + delta;
+            if new_level < 1 || new_level as usize > MAX_HEADING_LEVEL {
+                return Err(OrgModeError::InvalidLevel(new_level.max(0) as usize));
+            }
+
+            let mut lines: Vec<String> = content.lines().map(String::from).collect();
+            let old_heading_line = lines[headline.line_idx].clone();
+            let end = if subtree {
+                headline.subtree_end_line.min(lines.len())
+            } else {
+                headline.line_idx + 1
+            };
+            shift_headline_levels(&mut lines[headline.line_idx..end], delta)?;
+            let heading_line = lines[headline.line_idx].clone();
+
+            Self::atomic_write(&full_path, join_lines(&lines, &content).as_bytes())?;
+
+            let mut changes = Vec::new();
+            Self::push_change(
+                &mut changes,
+                "level",
+                Some(headline.level.to_string().as_str()),
+                Some(new_level.to_string().as_str()),
+            );
+            Self::push_change(
+                &mut changes,
+                "heading_line",
+                Some(old_heading_line.as_str()),
+                Some(heading_line.as_str()),
+            );
+            if subtree {
+                let descendants = lines[headline.line_idx + 1..end]
+                    .iter()
+                    .filter(|line| headline_level(line).is_some())
+                    .count();
+                if descendants > 0 {
+                    changes.push(format!(
+                        "subtree: {descendants} descendant heading(s) shifted"
+                    ));
+                }
+            }
+
+            Ok(UpdateResult {
+                file_path: file_rel.clone(),
+                heading_line,
+                changes,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OrgConfig;
+
+    const OUTLINE: &str = "\
+* Projects
+** Website :web:
+:PROPERTIES:
+:ID: website-1
+:END:
+*** TODO Pick colours
+**** Ask designer
+** Report
+";
+
+    fn make_org_mode(temp_dir: &tempfile::TempDir) -> OrgMode {
+        fs::write(temp_dir.path().join("outline.org"), OUTLINE).unwrap();
+        OrgMode::new(OrgConfig {
+            org_directory: temp_dir.path().to_str().unwrap().to_string(),
+            org_cache_directory: String::new(),
+            ..OrgConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn by_id(id: &str) -> HeadingTarget {
+        HeadingTarget {
+            id: Some(id.to_string()),
+            ..HeadingTarget::default()
+        }
+    }
+
+    #[test]
+    fn test_promote_subtree() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let result = org_mode.shift_level(&by_id("website-1"), -1, true).unwrap();
+        assert_eq!(result.heading_line, "* Website :web:");
+        assert_eq!(
+            result.changes,
+            vec![
+                "level: 2 -> 1",
+                "heading_line: ** Website :web: -> * Website :web:",
+                "subtree: 2 descendant heading(s) shifted",
+            ]
+        );
+
+        let content = fs::read_to_string(temp_dir.path().join("outline.org")).unwrap();
+        assert_eq!(
+            content,
+            "* Projects\n* Website :web:\n:PROPERTIES:\n:ID: website-1\n:END:\n\
+             ** TODO Pick colours\n*** Ask designer\n** Report\n"
+        );
+    }
+
+    #[test]
+    fn test_demote_heading_only() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let target = HeadingTarget {
+            id: None,
+            file: Some("outline.org".to_string()),
+            heading_path: Some("Projects/Report".to_string()),
+        };
+        let result = org_mode.shift_level(&target, 2, false).unwrap();
+        assert_eq!(result.heading_line, "**** Report");
+
+        let content = fs::read_to_string(temp_dir.path().join("outline.org")).unwrap();
+        assert!(content.ends_with("**** Ask designer\n**** Report\n"));
+        assert!(content.contains("*** TODO Pick colours\n"));
+    }
+
+    #[test]
+    fn test_promote_past_level_one_is_rejected() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let err = org_mode
+            .shift_level(&by_id("website-1"), -2, true)
+            .unwrap_err();
+        assert!(matches!(err, OrgModeError::InvalidLevel(0)));
+
+        let content = fs::read_to_string(temp_dir.path().join("outline.org")).unwrap();
+        assert_eq!(content, OUTLINE);
+    }
+
+    #[test]
+    fn test_demote_subtree_past_max_level_is_rejected() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let delta = (MAX_HEADING_LEVEL - 3) as isize;
+        let err = org_mode
+            .shift_level(&by_id("website-1"), delta, true)
+            .unwrap_err();
+        assert!(matches!(err, OrgModeError::InvalidLevel(_)));
+        org_mode
+            .shift_level(&by_id("website-1"), delta, false)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_zero_delta_is_rejected() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let err = org_mode
+            .shift_level(&by_id("website-1"), 0, false)
+            .unwrap_err();
+        assert!(matches!(err, OrgModeError::InvalidUpdate(_)));
+    }
+}
@@ -0,0 +1,671 @@
+//! Org link parsing and the link index built from the metadata index, used
+//! for backlinks and for resolving outgoing links.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use shellexpand::tilde;
+
+use crate::OrgModeError;
+use crate::org_mode::{Backlink, LinkKind, OrgLink, OrgMode};
+
+/// A `[[target][description]]` or `[[target]]` link found on one line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RawLink {
+    pub target: String,
+    pub description: Option<String>,
+}
+
+/// Where a link points: an org-directory relative file and, for heading
+/// links, the heading title.
+type Location = (String, Option<String>);
+
+/// Extracts every bracket link on `line`. Links spanning several lines are
+/// not recognised.
+pub(crate) fn parse_links(line: &str) -> Vec<RawLink> {
+    let mut links = Vec::new();
+    let mut rest = line;
+    while let Some(open) = rest.find("[[") {
+        rest = &rest[open + 2..];
+        let Some(close) = rest.find(']') else {
+            break;
+        };
+        let target = &rest[..close];
+        let after = &rest[close + 1..];
+        if let Some(after) = after.strip_prefix(']') {
+            push_link(&mut links, target, None);
+            rest = after;
+        } else if let Some(desc) = after.strip_prefix('[')
+            && let Some(end) = desc.find("]]")
+        {
+            push_link(&mut links, target, Some(&desc[..end]));
+            rest = &desc[end + 2..];
+        }
+    }
+    links
+}
+
+fn push_link(links: &mut Vec<RawLink>, target: &str, description: Option<&str>) {
+    let target = target.trim();
+    if target.is_empty() || target.contains('[') {
+        return;
+    }
+    links.push(RawLink {
+        target: target.to_string(),
+        description: description
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(String::from),
+    });
+}
+
+/// The id of an `id:` link.
+pub(crate) fn link_id(target: &str) -> Option<&str> {
+    target.strip_prefix("id:").map(str::trim)
+}
+
+/// The path and search option of a `file:` link. A bare `*Heading` link
+/// is a file link into the file containing it, so its path is empty.
+pub(crate) fn link_file(target: &str) -> Option<(&str, Option<&str>)> {
+    if target.starts_with('*') {
+        return Some(("", Some(target)));
+    }
+    let rest = target.strip_prefix("file:")?;
+    Some(match rest.split_once("::") {
+        Some((path, search)) => (path, Some(search)),
+        None => (rest, None),
+    })
+}
+
+/// Classifies a link target by its type prefix.
+pub(crate) fn link_kind(target: &str) -> LinkKind {
+    if link_id(target).is_some() {
+        LinkKind::Id
+    } else if link_file(target).is_some() {
+        LinkKind::File
+    } else if target.starts_with("https:") || target.starts_with("http:") {
+        LinkKind::Https
+    } else if target.starts_with("attachment:") {
+        LinkKind::Attachment
+    } else {
+        LinkKind::Custom
+    }
+}
+
+/// Heading title named by a `*Heading` search option.
+fn search_heading(search: Option<&str>) -> Option<String> {
+    search
+        .and_then(|s| s.trim().strip_prefix('*'))
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+}
+
+/// Lexically normalises `path`, refusing to climb above its root.
+fn normalize(path: &Path) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// One link occurrence recorded by [`LinkIndex::build`].
+#[derive(Debug)]
+struct IndexedLink {
+    file_path: String,
+    heading_path: Option<String>,
+    /// `:ID:` of the enclosing heading, which locates its attachments.
+    heading_id: Option<String>,
+    line: usize,
+    link: RawLink,
+    context: String,
+}
+
+/// Every link across the org directory plus the location of every `:ID:`
+/// and the heading titles of every file.
+#[derive(Debug, Default)]
+pub(crate) struct LinkIndex {
+    links: Vec<IndexedLink>,
+    ids: HashMap<String, Location>,
+    titles: HashMap<String, HashSet<String>>,
+    /// The only files outside the org directory links are checked against.
+    agenda: HashSet<PathBuf>,
+}
+
+impl LinkIndex {
+    fn build(org_mode: &OrgMode) -> Result<Self, OrgModeError> {
+        let mut index = LinkIndex {
+            agenda: org_mode.agenda_files(),
+            ..LinkIndex::default()
+        };
+        let metadata = org_mode.metadata()?;
+
+        for (file, meta) in &metadata.files {
+            let sections = &meta.headlines;
+            index.titles.insert(
+                file.clone(),
+                sections.iter().map(|s| s.title.clone()).collect(),
+            );
+            for section in sections {
+                if let Some(ref id) = section.id {
+                    index
+                        .ids
+                        .entry(id.clone())
+                        .or_insert_with(|| (file.clone(), Some(section.title.clone())));
+                }
+            }
+            if let Some(ref id) = meta.doc_id {
+                index
+                    .ids
+                    .entry(id.clone())
+                    .or_insert_with(|| (file.clone(), None));
+            }
+
+            for entry in &meta.links {
+                let enclosing = sections.partition_point(|s| s.line <= entry.line);
+                let section = enclosing.checked_sub(1).map(|i| &sections[i]);
+                index.links.push(IndexedLink {
+                    file_path: file.clone(),
+                    heading_path: section.map(|s| s.path.clone()),
+                    heading_id: section.and_then(|s| s.id.clone()),
+                    line: entry.line + 1,
+                    link: entry.link.clone(),
+                    context: entry.context.clone(),
+                });
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Resolves a link found in `from_file` to the location it points at.
+    fn locate(&self, org_mode: &OrgMode, from_file: &str, target: &str) -> Option<Location> {
+        if let Some(id) = link_id(target) {
+            return self.ids.get(id).cloned();
+        }
+        let (path, search) = link_file(target)?;
+        let file = if path.is_empty() {
+            from_file.to_string()
+        } else {
+            org_mode.resolve_link_path(from_file, path)?
+        };
+        Some((file, search_heading(search)))
+    }
+
+    /// Whether the target of `entry` exists, or `None` for link types that
+    /// are not checked and for paths outside the org directory other than
+    /// the agenda files, which are never looked up so that links cannot
+    /// probe the rest of the host.
+    fn resolves(&self, org_mode: &OrgMode, entry: &IndexedLink) -> Option<bool> {
+        let target = &entry.link.target;
+        match link_kind(target) {
+            LinkKind::Id => Some(self.locate(org_mode, &entry.file_path, target).is_some()),
+            LinkKind::File => {
+                let Some((file, heading)) = self.locate(org_mode, &entry.file_path, target) else {
+                    // Outside the org directory only the agenda files are known.
+                    let (path, _) = link_file(target)?;
+                    let expanded = tilde(path.trim());
+                    let path = Path::new(expanded.as_ref());
+                    let full = if path.is_absolute() {
+                        path.to_path_buf()
+                    } else {
+                        let linking = org_mode.org_path(&entry.file_path);
+                        linking.parent().unwrap_or(Path::new("")).join(path)
+                    };
+                    let full = full
+                        .components()
+                        .fold(PathBuf::new(), |mut full, component| {
+                            match component {
+                                Component::ParentDir => {
+                                    full.pop();
+                                }
+                                Component::CurDir => {}
+                                _ => full.push(component),
+                            }
+                            full
+                        });
+                    return self.agenda.contains(&full).then_some(true);
+                };
+                if !org_mode.org_path(&file).exists() {
+                    return Some(false);
+                }
+                // Heading searches are only checked in indexed org files.
+                Some(match (heading, self.titles.get(&file)) {
+                    (Some(title), Some(titles)) => titles.contains(&title),
+                    _ => true,
+                })
+            }
+            LinkKind::Attachment => {
+                let name = target.strip_prefix("attachment:")?.trim();
+                let id = entry.heading_id.as_deref()?;
+                let dir = Path::new(&entry.file_path)
+                    .parent()
+                    .unwrap_or(Path::new(""))
+                    .join("data");
+                let split = id.char_indices().nth(2).map_or(id.len(), |(i, _)| i);
+                let (head, tail) = id.split_at(split);
+                let path = normalize(&dir.join(head).join(tail).join(name))?;
+                Some(org_mode.org_path(&path).exists())
+            }
+            LinkKind::Https | LinkKind::Custom => None,
+        }
+    }
+
+    fn to_org_link(&self, org_mode: &OrgMode, entry: &IndexedLink) -> OrgLink {
+        OrgLink {
+            kind: link_kind(&entry.link.target),
+            target: entry.link.target.clone(),
+            description: entry.link.description.clone(),
+            file_path: entry.file_path.clone(),
+            heading_path: entry.heading_path.clone(),
+            line: entry.line,
+            resolves: self.resolves(org_mode, entry),
+        }
+    }
+}
+
+impl OrgMode {
+    /// Returns every link pointing at `target`, which is either an `:ID:`
+    /// value, an org file path (`notes.org`), a heading in one
+    /// (`notes.org::*Heading`) or a heading title (`*Heading` or `Heading`)
+    /// in whichever files have it. `id:` links and `file:` links resolving
+    /// to the same place both count, so a heading with an ID is found
+    /// through either form.
+    pub fn backlinks(&self, target: &str) -> Result<Vec<Backlink>, OrgModeError> {
+        let target = target.trim();
+        if target.is_empty() {
+            return Err(OrgModeError::InvalidElementId(
+                "backlink target must not be empty".to_string(),
+            ));
+        }
+
+        let index = LinkIndex::build(self)?;
+        let wanted: Vec<Location> = match target.split_once("::") {
+            Some((file, search)) => normalize(Path::new(file))
+                .map(|file| (file, search_heading(Some(search))))
+                .into_iter()
+                .collect(),
+            None if target.ends_with(".org") => normalize(Path::new(target))
+                .map(|file| (file, None))
+                .into_iter()
+                .collect(),
+            None => match index.ids.get(target) {
+                Some(location) if !target.starts_with('*') => vec![location.clone()],
+                _ => {
+                    let title = target.strip_prefix('*').unwrap_or(target).trim();
+                    index
+                        .titles
+                        .iter()
+                        .filter(|(_, titles)| titles.contains(title))
+                        .map(|(file, _)| (file.clone(), Some(title.to_string())))
+                        .collect()
+                }
+            },
+        };
+
+        let backlinks = index
+            .links
+            .iter()
+            .filter(|entry| {
+                if link_id(&entry.link.target) == Some(target) {
+                    return true;
+                }
+                let Some(location) = index.locate(self, &entry.file_path, &entry.link.target)
+                else {
+                    return false;
+                };
+                wanted.iter().any(|wanted| {
+                    wanted.0 == location.0 && (wanted.1.is_none() || wanted.1 == location.1)
+                })
+            })
+            .map(|entry| Backlink {
+                file_path: entry.file_path.clone(),
+                heading_path: entry.heading_path.clone(),
+                line: entry.line,
+                link: entry.link.target.clone(),
+                description: entry.link.description.clone(),
+                context: entry.context.clone(),
+            })
+            .collect();
+
+        Ok(backlinks)
+    }
+
+    /// Lists the links in `file`, or only those inside the subtree at
+    /// `heading_path`, with their type and whether their target exists.
+    pub fn links_in(
+        &self,
+        file: &str,
+        heading_path: Option<&str>,
+    ) -> Result<Vec<OrgLink>, OrgModeError> {
+        let content = self.read_file(file)?;
+        let file = normalize(Path::new(file))
+            .ok_or_else(|| OrgModeError::InvalidDirectory(file.to_string()))?;
+
+        if let Some(path) = heading_path
+            && !self
+                .headline_sections(&content)
+                .iter()
+                .any(|section| section.path == path)
+        {
+            return Err(OrgModeError::HeadingNotFound(format!("{file}#{path}")));
+        }
+        let in_subtree = |entry_path: &Option<String>| match (heading_path, entry_path) {
+            (None, _) => true,
+            (Some(path), Some(entry_path)) => {
+                entry_path == path
+                    || entry_path
+                        .strip_prefix(path)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            }
+            (Some(_), None) => false,
+        };
+
+        let index = LinkIndex::build(self)?;
+        Ok(index
+            .links
+            .iter()
+            .filter(|entry| entry.file_path == file && in_subtree(&entry.heading_path))
+            .map(|entry| index.to_org_link(self, entry))
+            .collect())
+    }
+
+    /// Lists every link across the org directory whose target does not exist.
+    pub fn broken_links(&self) -> Result<Vec<OrgLink>, OrgModeError> {
+        let index = LinkIndex::build(self)?;
+        Ok(index
+            .links
+            .iter()
+            .map(|entry| index.to_org_link(self, entry))
+            .filter(|link| link.resolves == Some(false))
+            .collect())
+    }
+
+    pub(crate) fn org_path(&self, file_rel: &str) -> PathBuf {
+        PathBuf::from(&self.config.org_directory).join(file_rel)
+    }
+
+    /// Resolves the path of a `file:` link written in `from_file` to a path
+    /// relative to the org directory. Relative paths are taken from the
+    /// linking file's directory; absolute ones must lie inside the org
+    /// directory.
+    pub(crate) fn resolve_link_path(&self, from_file: &str, path: &str) -> Option<String> {
+        let expanded = tilde(path.trim());
+        let path = Path::new(expanded.as_ref());
+        if path.is_absolute() {
+            let org_dir = PathBuf::from(&self.config.org_directory);
+            let relative = path.strip_prefix(&org_dir).ok().or_else(|| {
+                org_dir
+                    .canonicalize()
+                    .ok()
+                    .and_then(|dir| path.strip_prefix(dir).ok())
+            })?;
+            return normalize(relative);
+        }
+        let base = Path::new(from_file).parent().unwrap_or(Path::new(""));
+        normalize(&base.join(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OrgConfig;
+    use std::fs;
+
+    fn make_org_mode(temp_dir: &tempfile::TempDir) -> OrgMode {
+        let dir = temp_dir.path();
+        fs::create_dir_all(dir.join("projects")).unwrap();
+        fs::write(
+            dir.join("notes.org"),
+            "* Reading list\n\
+             :PROPERTIES:\n\
+             :ID: reading-1\n\
+             :END:\n\
+             ** Rust book\n\
+             See [[*Reading list][the list]].\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("projects/site.org"),
+            "#+TITLE: Site\n\
+             * Plan\n\
+             Inspired by [[id:reading-1][my reading list]] and [[https://example.com]].\n\
+             * Refs\n\
+             - [[file:../notes.org::*Reading list]]\n\
+             - [[file:../notes.org]]\n",
+        )
+        .unwrap();
+        OrgMode::new(OrgConfig {
+            org_directory: dir.to_str().unwrap().to_string(),
+            org_cache_directory: String::new(),
+            ..OrgConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_links() {
+        let links = parse_links("a [[id:x][Desc]] b [[file:y.org]] [not] [[broken");
+        assert_eq!(
+            links,
+            vec![
+                RawLink {
+                    target: "id:x".to_string(),
+                    description: Some("Desc".to_string()),
+                },
+                RawLink {
+                    target: "file:y.org".to_string(),
+                    description: None,
+                },
+            ]
+        );
+        assert!(parse_links("no links here").is_empty());
+    }
+
+    #[test]
+    fn test_link_file_and_search_heading() {
+        assert_eq!(
+            link_file("file:a/b.org::*Heading"),
+            Some(("a/b.org", Some("*Heading")))
+        );
+        assert_eq!(link_file("*Local"), Some(("", Some("*Local"))));
+        assert_eq!(link_file("https://example.com"), None);
+        assert_eq!(
+            search_heading(Some("*Heading")),
+            Some("Heading".to_string())
+        );
+        assert_eq!(search_heading(Some("42")), None);
+    }
+
+    #[test]
+    fn test_backlinks_by_id_include_file_heading_links() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let backlinks = org_mode.backlinks("reading-1").unwrap();
+        let found: Vec<(&str, usize)> = backlinks
+            .iter()
+            .map(|b| (b.file_path.as_str(), b.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("notes.org", 6),
+                ("projects/site.org", 3),
+                ("projects/site.org", 5)
+            ]
+        );
+
+        let from_plan = &backlinks[1];
+        assert_eq!(from_plan.heading_path.as_deref(), Some("Plan"));
+        assert_eq!(from_plan.description.as_deref(), Some("my reading list"));
+        assert!(from_plan.context.starts_with("Inspired by [[id:reading-1]"));
+    }
+
+    #[test]
+    fn test_backlinks_by_file_and_heading() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        assert_eq!(org_mode.backlinks("notes.org").unwrap().len(), 4);
+        assert_eq!(
+            org_mode
+                .backlinks("notes.org::*Reading list")
+                .unwrap()
+                .len(),
+            3
+        );
+        assert!(
+            org_mode
+                .backlinks("notes.org::*Rust book")
+                .unwrap()
+                .is_empty()
+        );
+        assert!(org_mode.backlinks("unknown-id").unwrap().is_empty());
+        assert!(matches!(
+            org_mode.backlinks("  "),
+            Err(OrgModeError::InvalidElementId(_))
+        ));
+    }
+
+    #[test]
+    fn test_backlinks_by_heading_title() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        for target in ["*Reading list", "Reading list"] {
+            let found: Vec<(String, usize)> = org_mode
+                .backlinks(target)
+                .unwrap()
+                .into_iter()
+                .map(|b| (b.file_path, b.line))
+                .collect();
+            assert_eq!(
+                found,
+                [
+                    ("notes.org".to_string(), 6),
+                    ("projects/site.org".to_string(), 3),
+                    ("projects/site.org".to_string(), 5)
+                ],
+                "{target}"
+            );
+        }
+        assert!(org_mode.backlinks("*Rust book").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_links_in_classifies_and_resolves() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let links = org_mode.links_in("projects/site.org", None).unwrap();
+        let found: Vec<(LinkKind, &str, Option<bool>)> = links
+            .iter()
+            .map(|l| (l.kind, l.target.as_str(), l.resolves))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (LinkKind::Id, "id:reading-1", Some(true)),
+                (LinkKind::Https, "https://example.com", None),
+                (
+                    LinkKind::File,
+                    "file:../notes.org::*Reading list",
+                    Some(true)
+                ),
+                (LinkKind::File, "file:../notes.org", Some(true)),
+            ]
+        );
+
+        let refs = org_mode
+            .links_in("projects/site.org", Some("Refs"))
+            .unwrap();
+        assert_eq!(refs.len(), 2);
+        assert!(
+            refs.iter()
+                .all(|l| l.heading_path.as_deref() == Some("Refs"))
+        );
+
+        assert!(matches!(
+            org_mode.links_in("projects/site.org", Some("Nope")),
+            Err(OrgModeError::HeadingNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_broken_links_across_directory() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+        let dir = temp_dir.path();
+        fs::write(
+            dir.join("dangling.org"),
+            "* Todo\n:PROPERTIES:\n:ID: dang-1\n:END:\n\
+             [[id:missing]] [[file:gone.org]] [[file:notes.org::*Nope]]\n\
+             [[attachment:plan.pdf]] [[attachment:present.txt]] [[mailto:me@example.com]]\n\
+             [[file:/etc/hostname]] [[file:../../../../nope.txt]] [[attachment:../../../../../etc/hosts]]\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("data/da/ng-1")).unwrap();
+        fs::write(dir.join("data/da/ng-1/present.txt"), "").unwrap();
+
+        let broken: Vec<String> = org_mode
+            .broken_links()
+            .unwrap()
+            .into_iter()
+            .map(|l| l.target)
+            .collect();
+        assert_eq!(
+            broken,
+            vec![
+                "id:missing",
+                "file:gone.org",
+                "file:notes.org::*Nope",
+                "attachment:plan.pdf",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paths_outside_org_directory_are_not_checked() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+        let agenda = outside.path().join("work.org");
+        fs::write(&agenda, "* TODO Report\n").unwrap();
+        fs::write(outside.path().join("secret.txt"), "").unwrap();
+        let dir = temp_dir.path();
+        fs::write(
+            dir.join("notes.org"),
+            format!(
+                "[[file:{}]] [[file:{}]] [[file:{}]]\n",
+                agenda.display(),
+                outside.path().join("secret.txt").display(),
+                outside.path().join("missing.txt").display(),
+            ),
+        )
+        .unwrap();
+        let org_mode = OrgMode::new(OrgConfig {
+            org_directory: dir.to_str().unwrap().to_string(),
+            org_agenda_files: vec![agenda.to_str().unwrap().to_string()],
+            org_cache_directory: String::new(),
+            ..OrgConfig::default()
+        })
+        .unwrap();
+
+        let resolves: Vec<Option<bool>> = org_mode
+            .links_in("notes.org", None)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.resolves)
+            .collect();
+        assert_eq!(resolves, [Some(true), None, None]);
+    }
+}
@@ -0,0 +1,166 @@
+//! Line-level helpers for a heading's `:LOGBOOK:` drawer.
+
+/// Line span of a `:LOGBOOK:` drawer: `start` is the `:LOGBOOK:` line and
+/// `end` the matching `:END:` line (both inclusive).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LogbookSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl LogbookSpan {
+    pub(crate) fn entries(&self) -> std::ops::Range<usize> {
+        self.start + 1..self.end
+    }
+}
+
+/// Finds the first `:LOGBOOK:` drawer within `lines[from..to]`.
+pub(crate) fn find_logbook(lines: &[String], from: usize, to: usize) -> Option<LogbookSpan> {
+    let to = to.min(lines.len());
+    let start = (from..to).find(|&i| lines[i].trim().eq_ignore_ascii_case(":LOGBOOK:"))?;
+    let end = (start + 1..to).find(|&i| lines[i].trim().eq_ignore_ascii_case(":END:"))?;
+    Some(LogbookSpan { start, end })
+}
+
+/// Inserts `entry` at the top of the logbook drawer found in
+/// `lines[body_first..body_last]`, creating the drawer at `body_first` when
+/// the heading has none (indented by `indent`, usually matching the property
+/// drawer). A multi-line entry is inserted line by line. Returns the line
+/// index of the entry's first line.
+pub(crate) fn push_logbook_entry(
+    lines: &mut Vec<String>,
+    body_first: usize,
+    body_last: usize,
+    indent: &str,
+    entry: &str,
+) -> usize {
+    match find_logbook(lines, body_first, body_last) {
+        Some(span) => {
+            let indent = leading_whitespace(&lines[span.start]).to_string();
+            let at = span.start + 1;
+            lines.splice(at..at, entry.lines().map(|l| format!("{indent}{l}")));
+            at
+        }
+        None => {
+            let at = body_first.min(lines.len());
+            let drawer = std::iter::once(":LOGBOOK:")
+                .chain(entry.lines())
+                .chain(std::iter::once(":END:"))
+                .map(|l| format!("{indent}{l}"));
+            lines.splice(at..at, drawer);
+            at + 1
+        }
+    }
+}
+
+/// Removes line `idx` from the drawer `span`, dropping the whole drawer when
+/// it is left empty.
+pub(crate) fn remove_logbook_entry(lines: &mut Vec<String>, span: LogbookSpan, idx: usize) {
+    lines.remove(idx);
+    let end = span.end - 1;
+    let empty = (span.start + 1..end).all(|i| lines[i].trim().is_empty());
+    if empty {
+        lines.drain(span.start..=end);
+    }
+}
+
+/// Formats a state change note the way Emacs does:
+/// `- State "DONE"       from "TODO"       [2026-05-15 Fri 10:00]`, with
+/// an optional `note` on the following, indented lines.
+pub(crate) fn state_change_entry(
+    to: Option<&str>,
+    from: Option<&str>,
+    stamp: &str,
+    note: Option<&str>,
+) -> String {
+    let to = format!("\"{}\"", to.unwrap_or_default());
+    let from = format!("\"{}\"", from.unwrap_or_default());
+    let mut entry = format!("- State {to:<12} from {from:<12} {stamp}");
+    if let Some(note) = note.map(str::trim).filter(|n| !n.is_empty()) {
+        entry.push_str(" \\\\");
+        for line in note.lines() {
+            entry.push_str("\n  ");
+            entry.push_str(line.trim_end());
+        }
+    }
+    entry
+}
+
+pub(crate) fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_lines(s: &str) -> Vec<String> {
+        s.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_push_creates_drawer_when_absent() {
+        let mut lines = to_lines("* Task\nBody text");
+        let idx = push_logbook_entry(&mut lines, 1, 2, "", "CLOCK: [2026-05-15 Fri 09:00]");
+        assert_eq!(idx, 2);
+        assert_eq!(
+            lines,
+            to_lines("* Task\n:LOGBOOK:\nCLOCK: [2026-05-15 Fri 09:00]\n:END:\nBody text")
+        );
+    }
+
+    #[test]
+    fn test_push_prepends_to_existing_drawer_with_indent() {
+        let mut lines = to_lines("* Task\n  :LOGBOOK:\n  CLOCK: old\n  :END:");
+        let idx = push_logbook_entry(&mut lines, 1, 4, "", "CLOCK: new");
+        assert_eq!(idx, 2);
+        assert_eq!(
+            lines,
+            to_lines("* Task\n  :LOGBOOK:\n  CLOCK: new\n  CLOCK: old\n  :END:")
+        );
+    }
+
+    #[test]
+    fn test_state_change_entry_pads_like_emacs() {
+        assert_eq!(
+            state_change_entry(Some("DONE"), Some("TODO"), "[2026-05-15 Fri 10:00]", None),
+            "- State \"DONE\"       from \"TODO\"       [2026-05-15 Fri 10:00]"
+        );
+        assert_eq!(
+            state_change_entry(
+                Some("WAIT"),
+                Some("TODO"),
+                "[2026-05-15 Fri 10:00]",
+                Some("Blocked on review\nby the API team")
+            ),
+            "- State \"WAIT\"       from \"TODO\"       [2026-05-15 Fri 10:00] \\\\\n  Blocked on review\n  by the API team"
+        );
+    }
+
+    #[test]
+    fn test_push_multi_line_entry() {
+        let mut lines = to_lines("* Task\n:LOGBOOK:\nCLOCK: old\n:END:");
+        let idx = push_logbook_entry(&mut lines, 1, 4, "", "- State \\\\\n  note");
+        assert_eq!(idx, 2);
+        assert_eq!(
+            lines,
+            to_lines("* Task\n:LOGBOOK:\n- State \\\\\n  note\nCLOCK: old\n:END:")
+        );
+    }
+
+    #[test]
+    fn test_remove_last_entry_drops_drawer() {
+        let mut lines = to_lines("* Task\n:LOGBOOK:\nCLOCK: open\n:END:\nBody");
+        let span = find_logbook(&lines, 1, 5).unwrap();
+        remove_logbook_entry(&mut lines, span, 2);
+        assert_eq!(lines, to_lines("* Task\nBody"));
+    }
+
+    #[test]
+    fn test_remove_entry_keeps_non_empty_drawer() {
+        let mut lines = to_lines("* Task\n:LOGBOOK:\nCLOCK: open\nCLOCK: closed\n:END:");
+        let span = find_logbook(&lines, 1, 5).unwrap();
+        remove_logbook_entry(&mut lines, span, 2);
+        assert_eq!(lines, to_lines("* Task\n:LOGBOOK:\nCLOCK: closed\n:END:"));
+    }
+}
@@ -0,0 +1,281 @@
+//! Emacs tags/property match strings, e.g.
+//! `+work-someday|urgent+PRIORITY="A"+EFFORT>1/!TODO|WAIT`, compiled into
+//! query expressions.
+
+use regex::Regex;
+
+use crate::OrgModeError;
+use crate::org_mode::query::{Comparison, DateSpec, MatchValue, Query};
+
+fn invalid(reason: impl Into<String>) -> OrgModeError {
+    OrgModeError::InvalidQuery(reason.into())
+}
+
+/// Splits `input` at every `sep` outside of `"..."` and `{...}`.
+fn split_top_level(input: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut in_quotes, mut in_braces, mut start) = (false, false, 0);
+    for (i, c) in input.char_indices() {
+        match c {
+            '"' if !in_braces => in_quotes = !in_quotes,
+            '{' if !in_quotes => in_braces = true,
+            '}' if !in_quotes => in_braces = false,
+            c if c == sep && !in_quotes && !in_braces => {
+                parts.push(&input[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&input[start..]);
+    parts
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '@' | '#' | '%')
+}
+
+impl Query {
+    /// Compiles an Emacs match string: `|`-separated groups of `+tag`,
+    /// `-tag`, `{regexp}` and `PROP<op>VALUE` terms, optionally followed by
+    /// `/` and the same over TODO keywords (`/!` keeps unfinished ones only).
+    pub(crate) fn from_match_string(input: &str) -> Result<Self, OrgModeError> {
+        let (tags, todo) = match split_top_level(input, '/').as_slice() {
+            [tags, _, ..] => (*tags, Some(&input[tags.len() + 1..])),
+            _ => (input, None),
+        };
+
+        let mut parts = Vec::new();
+        if !tags.trim().is_empty() {
+            let groups = split_top_level(tags, '|')
+                .into_iter()
+                .map(|group| Self::match_group(group, Self::match_term))
+                .collect::<Result<Vec<_>, _>>()?;
+            parts.push(Query::Or(groups));
+        }
+        if let Some(todo) = todo {
+            let (unfinished, todo) = match todo.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, todo),
+            };
+            if unfinished {
+                parts.push(Query::Todo(Vec::new()));
+            }
+            if !todo.trim().is_empty() {
+                let groups = split_top_level(todo, '|')
+                    .into_iter()
+                    .map(|group| Self::match_group(group, Self::match_keyword))
+                    .collect::<Result<Vec<_>, _>>()?;
+                parts.push(Query::Or(groups));
+            }
+        }
+        Ok(Query::And(parts))
+    }
+
+    /// The terms of one `|` group, each optionally prefixed by `+`, `&` or
+    /// `-` (negation).
+    fn match_group(
+        group: &str,
+        term: fn(&str) -> Result<(Query, &str), OrgModeError>,
+    ) -> Result<Self, OrgModeError> {
+        let mut terms = Vec::new();
+        let mut rest = group.trim();
+        if rest.is_empty() {
+            return Err(invalid("empty match group"));
+        }
+        while !rest.is_empty() {
+            let (negate, body) = match rest.chars().next() {
+                Some('-') => (true, &rest[1..]),
+                Some('+' | '&') => (false, &rest[1..]),
+                _ => (false, rest),
+            };
+            let (query, remaining) = term(body)?;
+            terms.push(if negate {
+                Query::Not(Box::new(query))
+            } else {
+                query
+            });
+            rest = remaining.trim_start();
+        }
+        Ok(Query::And(terms))
+    }
+
+    fn match_term(input: &str) -> Result<(Query, &str), OrgModeError> {
+        if let Some(body) = input.strip_prefix('{') {
+            let (pattern, rest) = body
+                .split_once('}')
+                .ok_or_else(|| invalid("unterminated {regexp}"))?;
+            return Ok((Query::TagRegexp(Self::match_regex(pattern)?), rest));
+        }
+
+        let name_len = input
+            .find(|c: char| !is_name_char(c))
+            .unwrap_or(input.len());
+        if name_len == 0 {
+            return Err(invalid(format!(
+                "unexpected '{}' in match string",
+                input.chars().next().unwrap_or_default()
+            )));
+        }
+        let (name, rest) = input.split_at(name_len);
+
+        let op_len = rest
+            .find(|c: char| !matches!(c, '<' | '>' | '=' | '!'))
+            .unwrap_or(rest.len());
+        if op_len == 0 {
+            return Ok((Query::Tags(vec![name.to_string()]), rest));
+        }
+        let (op, rest) = rest.split_at(op_len);
+        let op =
+            Comparison::parse(op).ok_or_else(|| invalid(format!("unknown comparison '{op}'")))?;
+        let (value, rest) = Self::match_value(name, op, rest)?;
+        Ok((
+            Query::PropertyMatch {
+                key: name.to_string(),
+                op,
+                value,
+            },
+            rest,
+        ))
+    }
+
+    /// A `"string"`, `"<date>"`, `{regexp}` or number.
+    fn match_value<'a>(
+        key: &str,
+        op: Comparison,
+        input: &'a str,
+    ) -> Result<(MatchValue, &'a str), OrgModeError> {
+        if let Some(body) = input.strip_prefix('"') {
+            let (text, rest) = body
+                .split_once('"')
+                .ok_or_else(|| invalid(format!("unterminated string for {key}")))?;
+            let value = match text.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+                Some(date) => MatchValue::Date(
+                    date.split_whitespace()
+                        .next()
+                        .and_then(DateSpec::parse)
+                        .ok_or_else(|| invalid(format!("bad date '{text}' for {key}")))?,
+                ),
+                None => MatchValue::Text(text.to_string()),
+            };
+            return Ok((value, rest));
+        }
+
+        if let Some(body) = input.strip_prefix('{') {
+            let (pattern, rest) = body
+                .split_once('}')
+                .ok_or_else(|| invalid(format!("unterminated {{regexp}} for {key}")))?;
+            if !matches!(op, Comparison::Eq | Comparison::Ne) {
+                return Err(invalid(format!("{key} regexp only supports = and <>")));
+            }
+            return Ok((MatchValue::Regexp(Self::match_regex(pattern)?), rest));
+        }
+
+        let len = input
+            .char_indices()
+            .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && c == '-')))
+            .map_or(input.len(), |(i, _)| i);
+        let (number, rest) = input.split_at(len);
+        let number = number
+            .parse()
+            .map_err(|_| invalid(format!("{key} needs a number, \"string\" or {{regexp}}")))?;
+        Ok((MatchValue::Number(number), rest))
+    }
+
+    fn match_keyword(input: &str) -> Result<(Query, &str), OrgModeError> {
+        let len = input
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(input.len());
+        if len == 0 {
+            return Err(invalid(format!("bad TODO match '{input}'")));
+        }
+        let (keyword, rest) = input.split_at(len);
+        Ok((
+            Query::PropertyMatch {
+                key: "TODO".to_string(),
+                op: Comparison::Eq,
+                value: MatchValue::Text(keyword.to_string()),
+            },
+            rest,
+        ))
+    }
+
+    fn match_regex(pattern: &str) -> Result<Regex, OrgModeError> {
+        Regex::new(pattern).map_err(|e| invalid(format!("bad regexp '{pattern}': {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::org_mode::query::tests::{context, headline};
+
+    fn matches(match_string: &str, content: &str) -> bool {
+        let today = NaiveDate::from_ymd_opt(2026, 6, 10).unwrap();
+        let ctx = context(&["work"], &[("EFFORT", "2"), ("OWNER", "ana")]);
+        Query::from_match_string(match_string)
+            .expect("match string compiles")
+            .matches(&headline(content), &ctx, today)
+    }
+
+    #[test]
+    fn test_tag_terms_and_groups() {
+        let content = "* TODO [#A] Fix outage :urgent:\n";
+        assert!(matches("", content));
+        assert!(matches("work", content));
+        assert!(matches("+work+urgent", content));
+        assert!(matches("work&urgent", content));
+        assert!(!matches("+work-urgent", content));
+        assert!(matches("+work-urgent|urgent", content));
+        assert!(!matches("home|someday", content));
+        assert!(matches("{^urg}", content));
+        assert!(!matches("-{^w}", content));
+    }
+
+    #[test]
+    fn test_property_terms() {
+        let content = "* TODO [#A] Fix outage :urgent:\nSCHEDULED: <2026-06-09 Tue>\n";
+        assert!(matches("urgent+PRIORITY=\"A\"+EFFORT>1", content));
+        assert!(!matches("EFFORT>=3", content));
+        assert!(matches("OWNER<>\"bo\"+OWNER={^a}", content));
+        assert!(matches("MISSING<>\"x\"", content));
+        assert!(matches("LEVEL=1+CATEGORY=\"work\"", content));
+        assert!(matches("SCHEDULED<\"<today>\"", content));
+        assert!(!matches("SCHEDULED>=\"<2026-06-10>\"", content));
+        assert!(!matches("DEADLINE<\"<+1w>\"", content));
+        // Headings without a priority cookie count as B.
+        assert!(matches("PRIORITY=\"B\"", "* TODO Plain\n"));
+    }
+
+    #[test]
+    fn test_todo_part() {
+        assert!(matches("work/TODO|WAIT", "* TODO Draft\n"));
+        assert!(!matches("work/WAIT", "* TODO Draft\n"));
+        assert!(matches("/-WAIT", "* TODO Draft\n"));
+        assert!(matches("/DONE", "* DONE Shipped\n"));
+        assert!(!matches("/!", "* DONE Shipped\n"));
+        assert!(!matches("/!DONE", "* DONE Shipped\n"));
+        assert!(matches("/!", "* WAIT Vendor\n"));
+    }
+
+    #[test]
+    fn test_match_string_errors() {
+        for (input, reason) in [
+            ("+work|", "empty match group"),
+            ("+*", "unexpected '*'"),
+            ("EFFORT>x", "EFFORT needs a number"),
+            ("EFFORT~1", "unexpected '~'"),
+            ("OWNER=\"ana", "unterminated string"),
+            ("OWNER>{a}", "regexp only supports = and <>"),
+            ("SCHEDULED<\"<someday>\"", "bad date"),
+            ("{(}", "bad regexp"),
+        ] {
+            let err = Query::from_match_string(input)
+                .expect_err(input)
+                .to_string();
+            assert!(err.contains(reason), "{input}: {err}");
+        }
+    }
+}
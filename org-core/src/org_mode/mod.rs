@@ -1,6 +1,22 @@
 mod agenda;
+mod archive;
 mod capture;
+mod clock;
 mod core;
+mod diary;
+mod fulltext;
+mod index;
+mod level;
+mod links;
+mod logbook;
+mod match_string;
+mod properties;
+mod query;
+mod refile;
+mod repeat;
+mod search;
+mod sort;
+mod tags;
 mod types;
 mod update;
 
@@ -0,0 +1,119 @@
+//! Property inheritance and agenda categories.
+
+use std::collections::BTreeMap;
+
+const CATEGORY: &str = "CATEGORY";
+
+/// The value of the first `#+CATEGORY:` line of a file, if any.
+pub(crate) fn file_category(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let line = line.trim_start();
+        line.get(..11)
+            .filter(|key| key.eq_ignore_ascii_case("#+CATEGORY:"))
+            .map(|_| line[11..].trim().to_string())
+            .filter(|value| !value.is_empty())
+    })
+}
+
+/// Follows a document's headings in order to work out each one's category
+/// and the properties it inherits from its ancestors.
+pub(crate) struct PropertyInheritance<'a> {
+    inherit: &'a [String],
+    category: String,
+    ancestors: Vec<(usize, BTreeMap<String, String>)>,
+}
+
+impl<'a> PropertyInheritance<'a> {
+    /// `inherit` lists the property keys passed down to children; the
+    /// category always is. Headings outside any `:CATEGORY:` fall back to
+    /// `#+CATEGORY:` and then to `file_stem`, as in Emacs.
+    pub(crate) fn new(content: &str, file_stem: &str, inherit: &'a [String]) -> Self {
+        Self {
+            inherit,
+            category: file_category(content).unwrap_or_else(|| file_stem.to_string()),
+            ancestors: Vec::new(),
+        }
+    }
+
+    /// Moves to the next heading, at `level` and with its own drawer
+    /// `properties`, and returns its category and effective properties.
+    pub(crate) fn enter(
+        &mut self,
+        level: usize,
+        properties: BTreeMap<String, String>,
+    ) -> (String, BTreeMap<String, String>) {
+        while self.ancestors.last().is_some_and(|(l, _)| *l >= level) {
+            self.ancestors.pop();
+        }
+
+        let mut effective = BTreeMap::new();
+        for (_, ancestor) in &self.ancestors {
+            for (key, value) in ancestor {
+                if self.inherit.iter().any(|k| k.eq_ignore_ascii_case(key)) {
+                    effective.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        effective.extend(properties.clone());
+
+        let category = std::iter::once(&properties)
+            .chain(self.ancestors.iter().rev().map(|(_, props)| props))
+            .find_map(|props| {
+                props
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(CATEGORY))
+                    .map(|(_, value)| value.clone())
+            })
+            .unwrap_or_else(|| self.category.clone());
+
+        self.ancestors.push((level, properties));
+        (category, effective)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn props(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_file_category() {
+        assert_eq!(
+            file_category("#+TITLE: Notes\n#+category: Work \n* A").as_deref(),
+            Some("Work")
+        );
+        assert_eq!(file_category("#+CATEGORY:\n* A"), None);
+        assert_eq!(file_category("* A\n:CATEGORY: x"), None);
+    }
+
+    #[test]
+    fn test_inheritance_follows_ancestors() {
+        let inherit = vec!["OWNER".to_string()];
+        let mut inheritance = PropertyInheritance::new("* A", "notes", &inherit);
+
+        let (category, effective) =
+            inheritance.enter(1, props(&[("CATEGORY", "infra"), ("OWNER", "ana")]));
+        assert_eq!(category, "infra");
+        assert_eq!(effective, props(&[("CATEGORY", "infra"), ("OWNER", "ana")]));
+
+        // Only listed keys pass down; the category always does.
+        let (category, effective) = inheritance.enter(2, props(&[("EFFORT", "1:00")]));
+        assert_eq!(category, "infra");
+        assert_eq!(effective, props(&[("EFFORT", "1:00"), ("OWNER", "ana")]));
+
+        // Own values win over inherited ones.
+        let (_, effective) = inheritance.enter(3, props(&[("OWNER", "bo")]));
+        assert_eq!(effective, props(&[("OWNER", "bo")]));
+
+        // A new top-level heading starts over with the file stem.
+        let (category, effective) = inheritance.enter(1, BTreeMap::new());
+        assert_eq!(category, "notes");
+        assert!(effective.is_empty());
+    }
+}
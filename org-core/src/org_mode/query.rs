@@ -0,0 +1,698 @@
+//! An org-ql style query language over the headings of the agenda files,
+//! e.g. `(and (todo "TODO" "WAIT") (tags "work") (not (priority "C")))`.
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use chrono::{Days, Local, NaiveDate};
+use orgize::ast::Headline;
+use regex::{Regex, RegexBuilder};
+
+use crate::OrgModeError;
+use crate::org_mode::core::TaskContext;
+use crate::org_mode::{AgendaItem, OrgMode};
+use crate::utils::tags_match;
+
+/// A parsed query expression.
+#[derive(Debug, Clone)]
+pub(crate) enum Query {
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+    /// Unfinished keyword among the given ones, or any unfinished one.
+    Todo(Vec<String>),
+    /// Finished keyword among the given ones, or any finished one.
+    Done(Vec<String>),
+    /// Any of the tags, set on the heading or inherited.
+    Tags(Vec<String>),
+    /// Every one of the tags, set on the heading or inherited.
+    TagsAll(Vec<String>),
+    /// Priority among the given ones, or any priority at all.
+    Priority(Vec<char>),
+    /// Priority compared by importance: `A` is greater than `B`.
+    PriorityCmp(Comparison, char),
+    Scheduled(DateRange),
+    Deadline(DateRange),
+    /// The property is set, or compares as given to a value.
+    Property {
+        key: String,
+        test: Option<(Comparison, String)>,
+    },
+    /// Every regex matches the heading line or its body text.
+    Regexp(Vec<Regex>),
+    /// Every string occurs in the title, ignoring case.
+    Heading(Vec<String>),
+    Category(Vec<String>),
+    /// Any tag, set on the heading or inherited, matches the regex.
+    TagRegexp(Regex),
+    /// A property, or one of the special `TODO`, `LEVEL`, `PRIORITY`,
+    /// `CATEGORY`, `ITEM`, `FILE`, `SCHEDULED` and `DEADLINE` keys,
+    /// compared the way Emacs match strings do.
+    PropertyMatch {
+        key: String,
+        op: Comparison,
+        value: MatchValue,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    pub(crate) fn parse(op: &str) -> Option<Self> {
+        match op {
+            "=" | "==" => Some(Self::Eq),
+            "!=" | "/=" | "<>" => Some(Self::Ne),
+            "<" => Some(Self::Lt),
+            "<=" => Some(Self::Le),
+            ">" => Some(Self::Gt),
+            ">=" => Some(Self::Ge),
+            _ => None,
+        }
+    }
+
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering.is_eq(),
+            Self::Ne => ordering.is_ne(),
+            Self::Lt => ordering.is_lt(),
+            Self::Le => ordering.is_le(),
+            Self::Gt => ordering.is_gt(),
+            Self::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+/// The right-hand side of a match string property comparison.
+#[derive(Debug, Clone)]
+pub(crate) enum MatchValue {
+    /// Compared against the number the value starts with, `0` if none.
+    Number(f64),
+    Text(String),
+    /// Only `=` and `<>` apply.
+    Regexp(Regex),
+    /// Compared against the date of a timestamp value; headings without
+    /// one only match `<>`.
+    Date(DateSpec),
+}
+
+impl MatchValue {
+    fn compare(&self, op: Comparison, actual: Option<&str>, today: NaiveDate) -> bool {
+        let actual_text = actual.unwrap_or_default();
+        match self {
+            MatchValue::Number(expected) => {
+                op.holds(leading_number(actual_text).total_cmp(expected))
+            }
+            MatchValue::Text(expected) => op.holds(actual_text.cmp(expected.as_str())),
+            MatchValue::Regexp(re) => re.is_match(actual_text) == (op == Comparison::Eq),
+            MatchValue::Date(spec) => {
+                match (actual.and_then(timestamp_date), spec.resolve(today)) {
+                    (Some(actual), Some(expected)) => op.holds(actual.cmp(&expected)),
+                    _ => op == Comparison::Ne,
+                }
+            }
+        }
+    }
+}
+
+/// The number at the start of `value`, like Emacs' `string-to-number`.
+fn leading_number(value: &str) -> f64 {
+    let value = value.trim_start();
+    let end = value
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && matches!(c, '-' | '+'))))
+        .map_or(value.len(), |(i, _)| i);
+    value[..end].parse().unwrap_or(0.0)
+}
+
+/// The date an org timestamp such as `<2026-06-10 Wed 10:00>` starts on.
+pub(crate) fn timestamp_date(raw: &str) -> Option<NaiveDate> {
+    let inner = raw.trim().trim_start_matches(['<', '[']);
+    NaiveDate::parse_from_str(inner.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// A day given as `today`, `tomorrow`, `yesterday`, `+N`/`-N` days (or
+/// `+Nw` weeks) from today, or `YYYY-MM-DD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DateSpec {
+    Relative(i64),
+    Absolute(NaiveDate),
+}
+
+impl DateSpec {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "today" => Some(Self::Relative(0)),
+            "tomorrow" => Some(Self::Relative(1)),
+            "yesterday" => Some(Self::Relative(-1)),
+            _ if value.starts_with(['+', '-']) => {
+                let (days, factor) = match value.strip_suffix('w') {
+                    Some(weeks) => (weeks, 7),
+                    None => (value.strip_suffix('d').unwrap_or(value), 1),
+                };
+                days.parse::<i64>().ok().map(|n| Self::Relative(n * factor))
+            }
+            _ => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .map(Self::Absolute),
+        }
+    }
+
+    fn resolve(self, today: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Absolute(date) => Some(date),
+            Self::Relative(days) if days < 0 => {
+                today.checked_sub_days(Days::new(days.unsigned_abs()))
+            }
+            Self::Relative(days) => today.checked_add_days(Days::new(days.unsigned_abs())),
+        }
+    }
+}
+
+/// Inclusive bounds on a planning date; no bounds means the date is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct DateRange {
+    from: Option<DateSpec>,
+    to: Option<DateSpec>,
+}
+
+impl DateRange {
+    fn contains(&self, date: NaiveDate, today: NaiveDate) -> bool {
+        let after_from = self
+            .from
+            .and_then(|from| from.resolve(today))
+            .is_none_or(|from| date >= from);
+        let before_to = self
+            .to
+            .and_then(|to| to.resolve(today))
+            .is_none_or(|to| date <= to);
+        after_from && before_to
+    }
+}
+
+/// An expression before it is checked against the known predicates.
+#[derive(Debug, Clone, PartialEq)]
+enum Sexp {
+    List(Vec<Sexp>),
+    Str(String),
+    Atom(String),
+}
+
+impl Sexp {
+    /// The text of a string or atom argument.
+    fn text(&self) -> Result<&str, OrgModeError> {
+        match self {
+            Sexp::Str(s) | Sexp::Atom(s) => Ok(s),
+            Sexp::List(_) => Err(invalid("expected a value, found a list")),
+        }
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> OrgModeError {
+    OrgModeError::InvalidQuery(reason.into())
+}
+
+fn tokenize(input: &str) -> Result<Vec<Sexp>, OrgModeError> {
+    let mut stack: Vec<Vec<Sexp>> = vec![Vec::new()];
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => stack.push(Vec::new()),
+            ')' => {
+                let list = stack.pop().filter(|_| !stack.is_empty());
+                let (Some(list), Some(parent)) = (list, stack.last_mut()) else {
+                    return Err(invalid("unbalanced ')'"));
+                };
+                parent.push(Sexp::List(list));
+            }
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => s.push(escaped),
+                            None => return Err(invalid("unterminated string")),
+                        },
+                        Some(c) => s.push(c),
+                        None => return Err(invalid("unterminated string")),
+                    }
+                }
+                push(&mut stack, Sexp::Str(s));
+            }
+            c => {
+                let mut atom = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '(' | ')' | '"') {
+                        break;
+                    }
+                    atom.push(next);
+                    chars.next();
+                }
+                push(&mut stack, Sexp::Atom(atom));
+            }
+        }
+    }
+
+    match stack.pop() {
+        Some(top) if stack.is_empty() => Ok(top),
+        _ => Err(invalid("missing ')'")),
+    }
+}
+
+fn push(stack: &mut [Vec<Sexp>], sexp: Sexp) {
+    if let Some(list) = stack.last_mut() {
+        list.push(sexp);
+    }
+}
+
+impl FromStr for Query {
+    type Err = OrgModeError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut exprs = tokenize(input)?;
+        match (exprs.pop(), exprs.is_empty()) {
+            (Some(expr), true) => Query::from_sexp(&expr),
+            (None, _) => Err(invalid("empty query")),
+            (Some(_), false) => Err(invalid(
+                "expected a single expression; combine them with (and ...)",
+            )),
+        }
+    }
+}
+
+impl Query {
+    fn from_sexp(sexp: &Sexp) -> Result<Self, OrgModeError> {
+        let Sexp::List(items) = sexp else {
+            return Err(invalid(format!(
+                "expected a (predicate ...) expression, found '{}'",
+                sexp.text()?
+            )));
+        };
+        let Some((Sexp::Atom(name), args)) = items.split_first() else {
+            return Err(invalid("expression must start with a predicate name"));
+        };
+
+        let texts = || {
+            args.iter()
+                .map(|arg| arg.text().map(str::to_string))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        match name.as_str() {
+            "and" | "or" => {
+                let queries = args
+                    .iter()
+                    .map(Query::from_sexp)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(if name == "and" {
+                    Query::And(queries)
+                } else {
+                    Query::Or(queries)
+                })
+            }
+            "not" => match args {
+                [arg] => Ok(Query::Not(Box::new(Query::from_sexp(arg)?))),
+                _ => Err(invalid("(not ...) takes exactly one expression")),
+            },
+            "todo" => Ok(Query::Todo(texts()?)),
+            "done" => Ok(Query::Done(texts()?)),
+            "tags" => Ok(Query::Tags(texts()?)),
+            "tags-all" => Ok(Query::TagsAll(texts()?)),
+            "priority" => Self::priority(&texts()?),
+            "scheduled" => Ok(Query::Scheduled(Self::date_range(&texts()?)?)),
+            "deadline" => Ok(Query::Deadline(Self::date_range(&texts()?)?)),
+            "property" => Self::property(&texts()?),
+            "regexp" => texts()?
+                .iter()
+                .map(|pattern| {
+                    // Lowercase patterns ignore case, as in Emacs searches.
+                    RegexBuilder::new(pattern)
+                        .case_insensitive(!pattern.chars().any(char::is_uppercase))
+                        .build()
+                        .map_err(|e| invalid(format!("bad regexp '{pattern}': {e}")))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Query::Regexp),
+            "heading" => Ok(Query::Heading(texts()?)),
+            "category" => Ok(Query::Category(texts()?)),
+            other => Err(invalid(format!("unknown predicate '{other}'"))),
+        }
+    }
+
+    fn priority(args: &[String]) -> Result<Self, OrgModeError> {
+        let letter = |value: &str| {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphabetic() => Ok(c.to_ascii_uppercase()),
+                _ => Err(invalid(format!("bad priority '{value}'"))),
+            }
+        };
+        if let [op, value] = args
+            && let Some(op) = Comparison::parse(op)
+        {
+            return Ok(Query::PriorityCmp(op, letter(value)?));
+        }
+        args.iter()
+            .map(|value| letter(value))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Query::Priority)
+    }
+
+    fn date_range(args: &[String]) -> Result<DateRange, OrgModeError> {
+        let mut range = DateRange::default();
+        let mut args = args.iter();
+        while let Some(key) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| invalid(format!("{key} needs a date")))?;
+            let date =
+                DateSpec::parse(value).ok_or_else(|| invalid(format!("bad date '{value}'")))?;
+            match key.as_str() {
+                ":from" => range.from = Some(date),
+                ":to" => range.to = Some(date),
+                ":on" => {
+                    range.from = Some(date);
+                    range.to = Some(date);
+                }
+                other => return Err(invalid(format!("unknown date option '{other}'"))),
+            }
+        }
+        Ok(range)
+    }
+
+    fn property(args: &[String]) -> Result<Self, OrgModeError> {
+        match args {
+            [key] => Ok(Query::Property {
+                key: key.clone(),
+                test: None,
+            }),
+            [key, value] => Ok(Query::Property {
+                key: key.clone(),
+                test: Some((Comparison::Eq, value.clone())),
+            }),
+            [key, op, value] => {
+                let op = Comparison::parse(op)
+                    .ok_or_else(|| invalid(format!("unknown comparison '{op}'")))?;
+                Ok(Query::Property {
+                    key: key.clone(),
+                    test: Some((op, value.clone())),
+                })
+            }
+            _ => Err(invalid("(property KEY [[OP] VALUE]) expected")),
+        }
+    }
+
+    /// Whether `headline`, with what it inherits in `context`, matches
+    /// the query on `today`.
+    pub(crate) fn matches(
+        &self,
+        headline: &Headline,
+        context: &TaskContext,
+        today: NaiveDate,
+    ) -> bool {
+        let keyword = || headline.todo_keyword().map(|kw| kw.to_string());
+        let effective_tags = || -> Vec<String> {
+            headline
+                .tags()
+                .map(|s| s.to_string())
+                .chain(context.inherited_tags.iter().cloned())
+                .collect()
+        };
+        let priority = || {
+            headline
+                .priority()
+                .and_then(|p| p.to_string().chars().next())
+        };
+
+        match self {
+            Query::And(queries) => queries.iter().all(|q| q.matches(headline, context, today)),
+            Query::Or(queries) => queries.iter().any(|q| q.matches(headline, context, today)),
+            Query::Not(query) => !query.matches(headline, context, today),
+            Query::Todo(states) => {
+                headline.is_todo()
+                    && (states.is_empty() || keyword().is_some_and(|kw| states.contains(&kw)))
+            }
+            Query::Done(states) => {
+                headline.is_done()
+                    && (states.is_empty() || keyword().is_some_and(|kw| states.contains(&kw)))
+            }
+            Query::Tags(tags) => {
+                let effective = effective_tags();
+                tags.iter().any(|tag| effective.contains(tag))
+            }
+            Query::TagsAll(tags) => tags_match(&effective_tags(), tags),
+            Query::Priority(priorities) => {
+                priority().is_some_and(|p| priorities.is_empty() || priorities.contains(&p))
+            }
+            // Lower letters are more important, so compare them reversed.
+            Query::PriorityCmp(op, value) => priority().is_some_and(|p| op.holds(value.cmp(&p))),
+            Query::Scheduled(range) => headline
+                .scheduled()
+                .and_then(|ts| OrgMode::start_to_chrono(&ts))
+                .is_some_and(|dt| range.contains(dt.date(), today)),
+            Query::Deadline(range) => headline
+                .deadline()
+                .and_then(|ts| OrgMode::start_to_chrono(&ts))
+                .is_some_and(|dt| range.contains(dt.date(), today)),
+            Query::Property { key, test } => context
+                .properties
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .is_some_and(|(_, actual)| {
+                    test.as_ref()
+                        .is_none_or(|(op, expected)| op.holds(compare_values(actual, expected)))
+                }),
+            Query::Regexp(regexes) => {
+                let raw = headline.raw();
+                let text = entry_text(&raw);
+                regexes.iter().all(|re| re.is_match(text))
+            }
+            Query::Heading(needles) => {
+                let title = headline.title_raw().trim_end().to_lowercase();
+                needles
+                    .iter()
+                    .all(|needle| title.contains(&needle.to_lowercase()))
+            }
+            Query::Category(categories) => categories.contains(&context.category),
+            Query::TagRegexp(re) => effective_tags().iter().any(|tag| re.is_match(tag)),
+            Query::PropertyMatch { key, op, value } => {
+                let actual = Self::match_property(key, headline, context);
+                value.compare(*op, actual.as_deref(), today)
+            }
+        }
+    }
+
+    /// The value a match string sees for `key` on a heading.
+    fn match_property(key: &str, headline: &Headline, context: &TaskContext) -> Option<String> {
+        match key.to_ascii_uppercase().as_str() {
+            "TODO" => headline.todo_keyword().map(|kw| kw.to_string()),
+            "LEVEL" => Some(headline.level().to_string()),
+            // Emacs reports headings without a cookie at the default `B`.
+            "PRIORITY" => Some(
+                headline
+                    .priority()
+                    .map_or_else(|| "B".to_string(), |p| p.to_string()),
+            ),
+            "CATEGORY" => Some(context.category.clone()),
+            "ITEM" => Some(headline.title_raw().trim_end().to_string()),
+            "FILE" => Some(context.file_path.clone()),
+            "SCHEDULED" => headline.scheduled().map(|ts| ts.raw()),
+            "DEADLINE" => headline.deadline().map(|ts| ts.raw()),
+            _ => context
+                .properties
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, value)| value.clone()),
+        }
+    }
+}
+
+/// The heading line and body of an entry, without its child headings.
+pub(crate) fn entry_text(raw: &str) -> &str {
+    let mut offset = 0;
+    for (i, line) in raw.split_inclusive('\n').enumerate() {
+        if i > 0 && line.starts_with('*') && line.trim_start_matches('*').starts_with(' ') {
+            return &raw[..offset];
+        }
+        offset += line.len();
+    }
+    raw
+}
+
+/// Orders property values as durations (`1:30`, `90min`, `2h`, `1d`),
+/// then as numbers, and as plain text otherwise.
+fn compare_values(actual: &str, expected: &str) -> Ordering {
+    if let (Some(a), Some(b)) = (duration_minutes(actual), duration_minutes(expected)) {
+        return a.total_cmp(&b);
+    }
+    if let (Ok(a), Ok(b)) = (actual.trim().parse::<f64>(), expected.trim().parse::<f64>()) {
+        return a.total_cmp(&b);
+    }
+    actual.trim().cmp(expected.trim())
+}
+
+pub(crate) fn duration_minutes(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if let Some((hours, minutes)) = value.split_once(':') {
+        let hours: f64 = hours.parse().ok()?;
+        let minutes: f64 = minutes.parse().ok()?;
+        return Some(hours * 60.0 + minutes);
+    }
+    let split = value.find(|c: char| c.is_ascii_alphabetic())?;
+    let amount: f64 = value[..split].trim().parse().ok()?;
+    let per_unit = match value[split..].trim() {
+        "min" => 1.0,
+        "h" => 60.0,
+        "d" => 60.0 * 24.0,
+        "w" => 60.0 * 24.0 * 7.0,
+        _ => return None,
+    };
+    Some(amount * per_unit)
+}
+
+impl OrgMode {
+    /// Runs a query expression against every heading in the agenda files.
+    pub fn query(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<AgendaItem>, OrgModeError> {
+        let query: Query = query.parse()?;
+        let today = Local::now().date_naive();
+
+        Ok(self
+            .agenda_headlines(self.agenda_files().into_iter().collect())
+            .filter(|(headline, context)| query.matches(headline, context, today))
+            .map(|(headline, context)| Self::headline_to_agenda_item(&headline, context, None))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use orgize::ParseConfig;
+    use orgize::export::{Container, Event, from_fn};
+
+    pub(crate) fn headline(content: &str) -> Headline {
+        let config = ParseConfig {
+            todo_keywords: (
+                vec!["TODO".to_string(), "WAIT".to_string()],
+                vec!["DONE".to_string()],
+            ),
+            ..Default::default()
+        };
+        let mut found = None;
+        let mut handler = from_fn(|event| {
+            if let Event::Enter(Container::Headline(h)) = event
+                && found.is_none()
+            {
+                found = Some(h);
+            }
+        });
+        config.parse(content).traverse(&mut handler);
+        found.expect("content has a heading")
+    }
+
+    pub(crate) fn context(inherited_tags: &[&str], properties: &[(&str, &str)]) -> TaskContext {
+        TaskContext {
+            file_path: "work.org".to_string(),
+            inherited_tags: inherited_tags.iter().map(|t| t.to_string()).collect(),
+            category: "work".to_string(),
+            properties: properties
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    fn matches(query: &str, content: &str, context: &TaskContext) -> bool {
+        let today = NaiveDate::from_ymd_opt(2026, 6, 10).unwrap();
+        let query: Query = query.parse().expect("query parses");
+        query.matches(&headline(content), context, today)
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (query, reason) in [
+            ("", "empty query"),
+            ("(todo", "missing ')'"),
+            ("(todo))", "unbalanced ')'"),
+            ("(todo \"TODO)", "unterminated string"),
+            ("(todo) (done)", "expected a single expression"),
+            ("(frobnicate)", "unknown predicate 'frobnicate'"),
+            (
+                "(not (todo) (done))",
+                "(not ...) takes exactly one expression",
+            ),
+            ("(scheduled :from)", ":from needs a date"),
+            ("(scheduled :from someday)", "bad date 'someday'"),
+            ("(priority \"AB\")", "bad priority 'AB'"),
+            (
+                "(property \"EFFORT\" \"~\" \"1h\")",
+                "unknown comparison '~'",
+            ),
+            ("(regexp \"(\")", "bad regexp"),
+        ] {
+            let err = query.parse::<Query>().expect_err(query).to_string();
+            assert!(err.contains(reason), "{query}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_keyword_tag_and_priority_predicates() {
+        let content = "* TODO [#B] Send invoice :billing:\n";
+        let ctx = context(&["work"], &[]);
+
+        assert!(matches("(todo)", content, &ctx));
+        assert!(matches("(todo \"WAIT\" \"TODO\")", content, &ctx));
+        assert!(!matches("(done)", content, &ctx));
+        assert!(matches("(tags \"work\" \"home\")", content, &ctx));
+        assert!(!matches("(tags-all \"work\" \"home\")", content, &ctx));
+        assert!(matches("(tags-all \"work\" \"billing\")", content, &ctx));
+        assert!(matches("(not (priority \"C\"))", content, &ctx));
+        assert!(matches("(priority \">=\" \"B\")", content, &ctx));
+        assert!(!matches("(priority \">\" \"B\")", content, &ctx));
+        assert!(matches("(or (heading \"INVOICE\") (done))", content, &ctx));
+        assert!(!matches("(priority)", "* TODO Unprioritized\n", &ctx));
+    }
+
+    #[test]
+    fn test_date_property_and_text_predicates() {
+        let content = "* TODO Pay rent\nSCHEDULED: <2026-06-12 Fri>\nSee the invoice.\n** TODO Child\nreceipt\n";
+        let ctx = context(&[], &[("EFFORT", "1:30"), ("OWNER", "ana")]);
+
+        assert!(matches("(scheduled)", content, &ctx));
+        assert!(matches("(scheduled :from today :to +7)", content, &ctx));
+        assert!(matches("(scheduled :on 2026-06-12)", content, &ctx));
+        assert!(!matches("(scheduled :to tomorrow)", content, &ctx));
+        assert!(!matches("(deadline)", content, &ctx));
+
+        assert!(matches("(property \"effort\" \">\" \"1h\")", content, &ctx));
+        assert!(!matches(
+            "(property \"EFFORT\" \">\" \"2h\")",
+            content,
+            &ctx
+        ));
+        assert!(matches("(property \"OWNER\" \"ana\")", content, &ctx));
+        assert!(!matches("(property \"COST\")", content, &ctx));
+
+        assert!(matches("(regexp \"invoice\")", content, &ctx));
+        assert!(!matches("(regexp \"receipt\")", content, &ctx));
+        assert!(!matches("(regexp \"INVOICE\")", content, &ctx));
+    }
+}
@@ -0,0 +1,323 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::OrgModeError;
+use crate::org_mode::update::{
+    TargetHeadline, detect_newline, headline_level, join_lines, line_end_at, shift_headline_levels,
+};
+use crate::org_mode::{HeadingTarget, OrgMode, RefileResult, RefileTarget};
+
+/// A subtree cut out of its file, with the lines left behind.
+pub(crate) struct DetachedSubtree {
+    pub headline: TargetHeadline,
+    pub lines: Vec<String>,
+    pub remaining: Vec<String>,
+}
+
+/// The first line and level of a subtree after [`OrgMode::insert_subtree`].
+pub(crate) struct MovedSubtree {
+    pub heading_line: String,
+    pub level: usize,
+}
+
+impl OrgMode {
+    /// Moves the source heading and its subtree under `target`, re-levelling
+    /// it to sit one level below the new parent (or at level 1 when refiling
+    /// to the top of a file). Both files stay locked for the whole move.
+    pub fn refile(
+        &self,
+        source: &HeadingTarget,
+        target: &RefileTarget,
+    ) -> Result<RefileResult, OrgModeError> {
+        Self::validate_heading_target(source)?;
+        Self::validate_relative_file_path(&target.file)?;
+        if let Some(ref path) = target.heading_path
+            && path.split('/').any(|segment| segment.trim().is_empty())
+        {
+            return Err(OrgModeError::InvalidHeadingPath(format!(
+                "heading_path contains an empty or whitespace-only segment: '{path}'"
+            )));
+        }
+
+        let (source_rel, source_path) = self.resolve_target_file(source)?;
+        let target_path = self.existing_file_path(&target.file)?;
+
+        Self::with_capture_locks(&source_path, &target_path, || {
+            let source_content = fs::read_to_string(&source_path).map_err(OrgModeError::IoError)?;
+            let same_file = source_path == target_path;
+
+            let detached = self.detach_subtree(&source_content, source)?;
+            if same_file {
+                self.check_not_into_itself(&source_content, &detached.headline, target)?;
+            }
+            let remaining = join_lines(&detached.remaining, &source_content);
+            let target_content = if same_file {
+                remaining.clone()
+            } else {
+                fs::read_to_string(&target_path).map_err(OrgModeError::IoError)?
+            };
+
+            let (new_target, moved) = self.insert_subtree(
+                &target_content,
+                target.heading_path.as_deref(),
+                detached.lines,
+            )?;
+
+            // Write the destination first: a failure in between leaves a
+            // duplicate rather than losing the subtree.
+            Self::atomic_write(&target_path, new_target.as_bytes())?;
+            if !same_file {
+                Self::atomic_write(&source_path, remaining.as_bytes())?;
+            }
+
+            Ok(RefileResult {
+                source_file: source_rel.clone(),
+                target_file: target.file.clone(),
+                heading_line: moved.heading_line,
+                level: moved.level,
+                under_target: target.heading_path.clone(),
+            })
+        })
+    }
+
+    /// Resolves `file_rel` inside the org directory, requiring it to exist.
+    pub(crate) fn existing_file_path(&self, file_rel: &str) -> Result<PathBuf, OrgModeError> {
+        let full_path = PathBuf::from(&self.config.org_directory).join(file_rel);
+        if !full_path.is_file() {
+            return Err(OrgModeError::HeadingNotFound(format!(
+                "{file_rel} (file does not exist)"
+            )));
+        }
+        self.prepare_target_path(file_rel)
+    }
+
+    /// Runs `f` holding the capture locks of both files, taken in a stable
+    /// order so two concurrent moves between the same files cannot deadlock.
+    pub(crate) fn with_capture_locks<T>(
+        a: &Path,
+        b: &Path,
+        f: impl FnOnce() -> Result<T, OrgModeError>,
+    ) -> Result<T, OrgModeError> {
+        if a == b {
+            return Self::with_capture_lock(a, f);
+        }
+        let (first, second) = if a < b { (a, b) } else { (b, a) };
+        Self::with_capture_lock(first, || Self::with_capture_lock(second, f))
+    }
+
+    /// Locates `source` in `content` and splits its subtree out.
+    pub(crate) fn detach_subtree(
+        &self,
+        content: &str,
+        source: &HeadingTarget,
+    ) -> Result<DetachedSubtree, OrgModeError> {
+        let headline = self.locate_target(content, source)?;
+        let mut remaining: Vec<String> = content.lines().map(String::from).collect();
+        let end = headline.subtree_end_line.min(remaining.len());
+        let lines = remaining.drain(headline.line_idx..end).collect();
+        Ok(DetachedSubtree {
+            headline,
+            lines,
+            remaining,
+        })
+    }
+
+    /// Appends `lines` (a detached subtree) at the end of `heading_path` in
+    /// `content`, or at the end of the file when no path is given.
+    pub(crate) fn insert_subtree(
+        &self,
+        content: &str,
+        heading_path: Option<&str>,
+        mut lines: Vec<String>,
+    ) -> Result<(String, MovedSubtree), OrgModeError> {
+        let (insert_pos, parent_level) = match heading_path {
+            Some(path) => {
+                let org = self.parse_config().parse(content);
+                let search = self.find_heading_path(&org, path, content.len() as u32);
+                if !search.remaining_parts.is_empty() {
+                    return Err(OrgModeError::HeadingNotFound(path.to_string()));
+                }
+                (usize::from(search.insert_pos), search.last_matched_level)
+            }
+            None => (content.len(), 0),
+        };
+
+        let level = parent_level + 1;
+        let current = lines
+            .first()
+            .and_then(|l| headline_level(l))
+            .unwrap_or(level);
+        shift_headline_levels(&mut lines, level as isize - current as isize)?;
+
+        let newline = detect_newline(content);
+        let mut out = String::with_capacity(content.len() + lines.len() * 40);
+        out.push_str(&content[..insert_pos]);
+        if insert_pos > 0 && !content[..insert_pos].ends_with('\n') {
+            out.push_str(newline);
+        }
+        out.push_str(&lines.join(newline));
+        out.push_str(newline);
+        out.push_str(&content[insert_pos..]);
+
+        Ok((
+            out,
+            MovedSubtree {
+                heading_line: lines.first().cloned().unwrap_or_default(),
+                level,
+            },
+        ))
+    }
+
+    /// Rejects a same-file move whose target heading lies inside the moved
+    /// subtree.
+    pub(crate) fn check_not_into_itself(
+        &self,
+        content: &str,
+        headline: &TargetHeadline,
+        target: &RefileTarget,
+    ) -> Result<(), OrgModeError> {
+        let Some(ref path) = target.heading_path else {
+            return Ok(());
+        };
+        let org = self.parse_config().parse(content);
+        let search = self.find_heading_path(&org, path, content.len() as u32);
+        if !search.remaining_parts.is_empty() {
+            return Ok(());
+        }
+        let target_end = line_end_at(content, search.insert_pos.into());
+        if target_end > headline.line_idx && target_end <= headline.subtree_end_line {
+            return Err(OrgModeError::InvalidUpdate(format!(
+                "cannot refile a heading into itself or its own subtree: {path}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OrgConfig;
+
+    const WORK: &str = "\
+* Inbox
+** TODO Call plumber :home:
+:PROPERTIES:
+:ID: plumber-1
+:END:
+Leaking sink.
+*** Find number
+* Projects
+** House
+";
+
+    const SOMEDAY: &str = "\
+* Someday
+** Learn Rust
+";
+
+    fn make_org_mode(temp_dir: &tempfile::TempDir) -> OrgMode {
+        fs::write(temp_dir.path().join("work.org"), WORK).unwrap();
+        fs::write(temp_dir.path().join("someday.org"), SOMEDAY).unwrap();
+        OrgMode::new(OrgConfig {
+            org_directory: temp_dir.path().to_str().unwrap().to_string(),
+            org_cache_directory: String::new(),
+            ..OrgConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn by_id(id: &str) -> HeadingTarget {
+        HeadingTarget {
+            id: Some(id.to_string()),
+            ..HeadingTarget::default()
+        }
+    }
+
+    fn to(file: &str, heading_path: Option<&str>) -> RefileTarget {
+        RefileTarget {
+            file: file.to_string(),
+            heading_path: heading_path.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_refile_within_file_relevels_subtree() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let result = org_mode
+            .refile(&by_id("plumber-1"), &to("work.org", Some("Projects/House")))
+            .unwrap();
+        assert_eq!(result.level, 3);
+        assert_eq!(result.heading_line, "*** TODO Call plumber :home:");
+
+        let content = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert_eq!(
+            content,
+            "* Inbox\n* Projects\n** House\n*** TODO Call plumber :home:\n:PROPERTIES:\n\
+             :ID: plumber-1\n:END:\nLeaking sink.\n**** Find number\n"
+        );
+    }
+
+    #[test]
+    fn test_refile_to_other_file_top_level() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let source = HeadingTarget {
+            id: None,
+            file: Some("work.org".to_string()),
+            heading_path: Some("Inbox/Call plumber".to_string()),
+        };
+        let result = org_mode.refile(&source, &to("someday.org", None)).unwrap();
+        assert_eq!(result.source_file, "work.org");
+        assert_eq!(result.target_file, "someday.org");
+        assert_eq!(result.level, 1);
+
+        let work = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert_eq!(work, "* Inbox\n* Projects\n** House\n");
+
+        let someday = fs::read_to_string(temp_dir.path().join("someday.org")).unwrap();
+        assert!(someday.ends_with(
+            "** Learn Rust\n* TODO Call plumber :home:\n:PROPERTIES:\n:ID: plumber-1\n\
+             :END:\nLeaking sink.\n** Find number\n"
+        ));
+    }
+
+    #[test]
+    fn test_refile_into_own_subtree_is_rejected() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let err = org_mode
+            .refile(
+                &by_id("plumber-1"),
+                &to("work.org", Some("Inbox/Call plumber/Find number")),
+            )
+            .unwrap_err();
+        assert!(matches!(err, OrgModeError::InvalidUpdate(_)));
+
+        let content = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert_eq!(content, WORK);
+    }
+
+    #[test]
+    fn test_refile_missing_target_heading() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let org_mode = make_org_mode(&temp_dir);
+
+        let err = org_mode
+            .refile(&by_id("plumber-1"), &to("someday.org", Some("Nowhere")))
+            .unwrap_err();
+        assert!(matches!(err, OrgModeError::HeadingNotFound(_)));
+
+        let err = org_mode
+            .refile(&by_id("plumber-1"), &to("missing.org", None))
+            .unwrap_err();
+        assert!(matches!(err, OrgModeError::HeadingNotFound(_)));
+
+        let content = fs::read_to_string(temp_dir.path().join("work.org")).unwrap();
+        assert_eq!(content, WORK);
+    }
+}
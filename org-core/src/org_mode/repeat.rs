@@ -0,0 +1,196 @@
+//! Shifting of repeating timestamps when a task is marked done.
+
+use chrono::{Days, Duration, Months, NaiveDateTime, NaiveTime};
+
+/// How a repeater computes the next occurrence, as in Emacs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RepeatKind {
+    /// `+1w`: shift once by the interval.
+    Cumulate,
+    /// `++1w`: shift by the interval until the date is in the future.
+    CatchUp,
+    /// `.+1w`: shift to the interval counted from now.
+    Restart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Repeater {
+    kind: RepeatKind,
+    value: u32,
+    unit: char,
+}
+
+impl Repeater {
+    fn parse(tok: &str) -> Option<Self> {
+        let (kind, body) = if let Some(rest) = tok.strip_prefix("++") {
+            (RepeatKind::CatchUp, rest)
+        } else if let Some(rest) = tok.strip_prefix(".+") {
+            (RepeatKind::Restart, rest)
+        } else if let Some(rest) = tok.strip_prefix('+') {
+            (RepeatKind::Cumulate, rest)
+        } else {
+            return None;
+        };
+        let unit = body.chars().last()?;
+        if !matches!(unit, 'h' | 'd' | 'w' | 'm' | 'y') {
+            return None;
+        }
+        let value = body[..body.len() - 1].parse().ok().filter(|&n| n > 0)?;
+        Some(Self { kind, value, unit })
+    }
+
+    fn add(&self, dt: NaiveDateTime) -> Option<NaiveDateTime> {
+        let value = self.value;
+        match self.unit {
+            'h' => dt.checked_add_signed(Duration::hours(value.into())),
+            'd' => dt.checked_add_days(Days::new(value.into())),
+            'w' => dt.checked_add_days(Days::new(u64::from(value) * 7)),
+            'm' => dt.checked_add_months(Months::new(value)),
+            _ => dt.checked_add_months(Months::new(value * 12)),
+        }
+    }
+
+    /// The next occurrence of `start` once the task is done at `now`.
+    fn next(&self, start: NaiveDateTime, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        match self.kind {
+            RepeatKind::Cumulate => self.add(start),
+            RepeatKind::CatchUp => {
+                let mut next = self.add(start)?;
+                while !self.is_future(next, now) {
+                    next = self.add(next)?;
+                }
+                Some(next)
+            }
+            RepeatKind::Restart if self.unit == 'h' => self.add(now),
+            RepeatKind::Restart => self.add(now.date().and_time(start.time())),
+        }
+    }
+
+    fn is_future(&self, dt: NaiveDateTime, now: NaiveDateTime) -> bool {
+        if self.unit == 'h' {
+            dt > now
+        } else {
+            dt.date() > now.date()
+        }
+    }
+}
+
+/// Moves the raw org timestamp `raw` (e.g. `<2026-05-15 Fri 10:00 ++1w>`)
+/// to its next occurrence, or returns `None` when it has no repeater.
+/// Everything but the date, weekday and time is kept verbatim.
+pub(crate) fn shift_repeating_timestamp(raw: &str, now: NaiveDateTime) -> Option<String> {
+    let open = raw.chars().next().filter(|c| matches!(c, '<' | '['))?;
+    let close = raw.chars().last().filter(|c| matches!(c, '>' | ']'))?;
+    let inner = raw.strip_prefix(open)?.strip_suffix(close)?;
+
+    let mut tokens: Vec<&str> = inner.split_whitespace().collect();
+    let date = chrono::NaiveDate::parse_from_str(tokens.first()?, "%Y-%m-%d").ok()?;
+    if tokens
+        .get(1)
+        .is_some_and(|t| t.chars().all(char::is_alphabetic))
+    {
+        tokens.remove(1);
+    }
+    let repeater = tokens.iter().find_map(|t| Repeater::parse(t))?;
+
+    // A time token is either `10:00` or a `10:00-11:30` range.
+    let time_idx = tokens
+        .iter()
+        .position(|t| t.split('-').next().is_some_and(|s| parse_time(s).is_some()));
+    let (start_time, end_time) = match time_idx {
+        Some(i) => {
+            let mut parts = tokens[i].splitn(2, '-');
+            let start = parts.next().and_then(parse_time)?;
+            let end = match parts.next() {
+                Some(end) => Some(parse_time(end)?),
+                None => None,
+            };
+            (Some(start), end)
+        }
+        None => (None, None),
+    };
+
+    let start = date.and_time(start_time.unwrap_or(NaiveTime::MIN));
+    let next = repeater.next(start, now)?;
+
+    let mut out = format!("{open}{} {}", next.format("%Y-%m-%d"), next.format("%a"));
+    for (i, tok) in tokens.iter().enumerate().skip(1) {
+        out.push(' ');
+        if Some(i) == time_idx {
+            out.push_str(&next.format("%H:%M").to_string());
+            if let Some(end) = end_time {
+                let end = end + (next.time() - start.time());
+                out.push('-');
+                out.push_str(&end.format("%H:%M").to_string());
+            }
+        } else {
+            out.push_str(tok);
+        }
+    }
+    out.push(close);
+    Some(out)
+}
+
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_plain_repeater_shifts_once() {
+        let now = at("2026-06-10 09:00");
+        assert_eq!(
+            shift_repeating_timestamp("<2026-05-15 Fri +1w>", now).as_deref(),
+            Some("<2026-05-22 Fri +1w>")
+        );
+        assert_eq!(
+            shift_repeating_timestamp("<2026-01-31 Sat 10:00 +1m -2d>", now).as_deref(),
+            Some("<2026-02-28 Sat 10:00 +1m -2d>")
+        );
+    }
+
+    #[test]
+    fn test_catch_up_repeater_lands_in_the_future() {
+        let now = at("2026-06-10 09:00");
+        assert_eq!(
+            shift_repeating_timestamp("<2026-05-15 Fri ++1w>", now).as_deref(),
+            Some("<2026-06-12 Fri ++1w>")
+        );
+        // Already in the future: still moves by one interval.
+        assert_eq!(
+            shift_repeating_timestamp("<2026-06-20 Sat ++1m>", now).as_deref(),
+            Some("<2026-07-20 Mon ++1m>")
+        );
+    }
+
+    #[test]
+    fn test_restart_repeater_counts_from_now() {
+        let now = at("2026-06-10 09:00");
+        assert_eq!(
+            shift_repeating_timestamp("<2026-05-15 Fri 18:00-19:30 .+3d>", now).as_deref(),
+            Some("<2026-06-13 Sat 18:00-19:30 .+3d>")
+        );
+        assert_eq!(
+            shift_repeating_timestamp("<2026-05-15 Fri 18:00 .+2h>", now).as_deref(),
+            Some("<2026-06-10 Wed 11:00 .+2h>")
+        );
+    }
+
+    #[test]
+    fn test_timestamp_without_repeater_is_left_alone() {
+        let now = at("2026-06-10 09:00");
+        assert_eq!(
+            shift_repeating_timestamp("<2026-05-15 Fri 10:00>", now),
+            None
+        );
+        assert_eq!(shift_repeating_timestamp("<2026-05-15 Fri -3d>", now), None);
+        assert_eq!(shift_repeating_timestamp("garbage", now), None);
+    }
+}
@@ -0,0 +1,355 @@
+//! Line matchers behind `OrgMode::search`, one per [`SearchMode`].
+
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config as NucleoConfig, Matcher, Utf32Str};
+use regex::{Regex, RegexBuilder};
+
+use crate::OrgModeError;
+use crate::org_mode::{SearchElement, SearchMode, SearchOptions};
+
+/// A line of a file, numbered from 0, with the number of headlines up to
+/// and including it, so lines sharing `entry` belong to the same entry.
+pub(crate) struct SearchLine<'a> {
+    pub(crate) index: usize,
+    pub(crate) entry: usize,
+    pub(crate) text: &'a str,
+}
+
+/// One `+word`, `-word` or `"phrase"` of a `words` query.
+#[derive(Debug)]
+pub(crate) struct Term {
+    text: String,
+    exclude: bool,
+}
+
+pub(crate) enum LineMatcher {
+    Fuzzy {
+        pattern: Pattern,
+        matcher: Box<Matcher>,
+        buf: Vec<char>,
+    },
+    Exact {
+        needle: String,
+        case_sensitive: bool,
+    },
+    Regex(Regex),
+    Words {
+        terms: Vec<Term>,
+        case_sensitive: bool,
+    },
+}
+
+impl LineMatcher {
+    pub(crate) fn new(query: &str, options: &SearchOptions) -> Result<Self, OrgModeError> {
+        let case_sensitive = options.case_sensitive;
+        Ok(match options.mode {
+            SearchMode::Fuzzy => LineMatcher::Fuzzy {
+                pattern: Pattern::new(
+                    query,
+                    if case_sensitive {
+                        CaseMatching::Respect
+                    } else {
+                        CaseMatching::Ignore
+                    },
+                    Normalization::Smart,
+                    AtomKind::Fuzzy,
+                ),
+                matcher: Box::new(Matcher::new(NucleoConfig::DEFAULT)),
+                buf: Vec::new(),
+            },
+            SearchMode::Exact => LineMatcher::Exact {
+                needle: fold_case(query, case_sensitive),
+                case_sensitive,
+            },
+            SearchMode::Regex => LineMatcher::Regex(
+                RegexBuilder::new(query)
+                    .case_insensitive(!case_sensitive)
+                    .build()
+                    .map_err(|e| {
+                        OrgModeError::InvalidQuery(format!("bad regexp '{query}': {e}"))
+                    })?,
+            ),
+            SearchMode::Words => LineMatcher::Words {
+                terms: parse_words(&fold_case(query, case_sensitive))?,
+                case_sensitive,
+            },
+            SearchMode::Ranked => {
+                return Err(OrgModeError::InvalidQuery(
+                    "ranked search matches sections, not lines".to_string(),
+                ));
+            }
+        })
+    }
+
+    /// The matching lines with their scores. `words` queries apply to whole
+    /// entries: each matching entry yields its first line holding a
+    /// required term, or its first line if every term is an exclusion.
+    pub(crate) fn matches<'a>(&mut self, lines: Vec<SearchLine<'a>>) -> Vec<(SearchLine<'a>, u32)> {
+        match self {
+            LineMatcher::Words {
+                terms,
+                case_sensitive,
+            } => {
+                let mut hits = Vec::new();
+                let mut lines = lines.into_iter().peekable();
+                while let Some(first) = lines.next() {
+                    let mut entry = vec![first];
+                    while let Some(line) = lines.next_if(|l| l.entry == entry[0].entry) {
+                        entry.push(line);
+                    }
+                    if let Some(hit) = Self::match_entry(terms, *case_sensitive, entry) {
+                        hits.push(hit);
+                    }
+                }
+                hits
+            }
+            _ => lines
+                .into_iter()
+                .filter_map(|line| self.score_line(line.text).map(|score| (line, score)))
+                .collect(),
+        }
+    }
+
+    fn score_line(&mut self, text: &str) -> Option<u32> {
+        match self {
+            LineMatcher::Fuzzy {
+                pattern,
+                matcher,
+                buf,
+            } => pattern.score(Utf32Str::new(text, buf), matcher),
+            LineMatcher::Exact {
+                needle,
+                case_sensitive,
+            } => count(&fold_case(text, *case_sensitive), needle),
+            LineMatcher::Regex(regex) => {
+                let found = regex.find_iter(text).count();
+                (found > 0).then_some(found as u32)
+            }
+            LineMatcher::Words { .. } => None,
+        }
+    }
+
+    /// Scores an entry by how often its required terms occur.
+    fn match_entry<'a>(
+        terms: &[Term],
+        case_sensitive: bool,
+        entry: Vec<SearchLine<'a>>,
+    ) -> Option<(SearchLine<'a>, u32)> {
+        let folded: Vec<String> = entry
+            .iter()
+            .map(|line| fold_case(line.text, case_sensitive))
+            .collect();
+        let text = folded.join("\n");
+        if terms
+            .iter()
+            .any(|term| text.contains(&term.text) == term.exclude)
+        {
+            return None;
+        }
+
+        let required: Vec<&str> = terms
+            .iter()
+            .filter(|term| !term.exclude)
+            .map(|term| term.text.as_str())
+            .collect();
+        let score = required
+            .iter()
+            .filter_map(|term| count(&text, term))
+            .sum::<u32>();
+        let hit = folded
+            .iter()
+            .position(|line| required.iter().any(|term| line.contains(term)))
+            .unwrap_or(0);
+        entry.into_iter().nth(hit).map(|line| (line, score))
+    }
+}
+
+/// The kind of each line of `content`. Drawer and block delimiters count
+/// as part of the property drawer or source block they open or close.
+pub(crate) fn line_elements(content: &str) -> Vec<SearchElement> {
+    let mut open: Option<(SearchElement, &str)> = None;
+    content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim();
+            let upper = trimmed.to_ascii_uppercase();
+            if line.starts_with('*') && line.trim_start_matches('*').starts_with([' ', '\t']) {
+                open = None;
+                return SearchElement::Headline;
+            }
+            if let Some((element, end)) = open {
+                if upper.starts_with(end) {
+                    open = None;
+                }
+                return element;
+            }
+            if upper == ":PROPERTIES:" {
+                open = Some((SearchElement::Property, ":END:"));
+                SearchElement::Property
+            } else if upper.starts_with("#+BEGIN_SRC") {
+                open = Some((SearchElement::SrcBlock, "#+END_SRC"));
+                SearchElement::SrcBlock
+            } else if trimmed.starts_with('|') {
+                SearchElement::Table
+            } else {
+                SearchElement::Body
+            }
+        })
+        .collect()
+}
+
+fn fold_case(text: &str, case_sensitive: bool) -> String {
+    if case_sensitive {
+        text.to_string()
+    } else {
+        text.to_lowercase()
+    }
+}
+
+fn count(haystack: &str, needle: &str) -> Option<u32> {
+    let found = haystack.matches(needle).count();
+    (found > 0).then_some(found as u32)
+}
+
+/// Splits a `words` query into `+word`, `-word` and `"phrase"` terms. Terms
+/// without a prefix are required, as in Emacs' boolean search view.
+fn parse_words(query: &str) -> Result<Vec<Term>, OrgModeError> {
+    let mut terms = Vec::new();
+    let mut rest = query.trim_start();
+    while !rest.is_empty() {
+        let (exclude, body) = match rest.chars().next() {
+            Some('-') => (true, &rest[1..]),
+            Some('+') => (false, &rest[1..]),
+            _ => (false, rest),
+        };
+        let (text, remaining) = match body.strip_prefix('"') {
+            Some(phrase) => phrase.split_once('"').ok_or_else(|| {
+                OrgModeError::InvalidQuery(format!("unterminated phrase in '{query}'"))
+            })?,
+            None => body.split_at(body.find(char::is_whitespace).unwrap_or(body.len())),
+        };
+        if text.is_empty() {
+            return Err(OrgModeError::InvalidQuery(format!(
+                "empty search term in '{query}'"
+            )));
+        }
+        terms.push(Term {
+            text: text.to_string(),
+            exclude,
+        });
+        rest = remaining.trim_start();
+    }
+    Ok(terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(content: &str) -> Vec<SearchLine<'_>> {
+        let mut entry = 0;
+        content
+            .lines()
+            .enumerate()
+            .map(|(index, text)| {
+                if text.starts_with('*') {
+                    entry += 1;
+                }
+                SearchLine { index, entry, text }
+            })
+            .collect()
+    }
+
+    fn hits(query: &str, mode: SearchMode, case_sensitive: bool, content: &str) -> Vec<usize> {
+        let options = SearchOptions {
+            mode,
+            case_sensitive,
+            ..SearchOptions::default()
+        };
+        LineMatcher::new(query, &options)
+            .expect("query compiles")
+            .matches(lines(content))
+            .into_iter()
+            .map(|(line, _)| line.index)
+            .collect()
+    }
+
+    const CONTENT: &str =
+        "* Invoices\nPaid invoice for ACME\n* Bills\nInvoice: plumber\nnot paid yet\n";
+
+    #[test]
+    fn test_exact_and_regex_modes() {
+        assert_eq!(
+            hits("invoice", SearchMode::Exact, false, CONTENT),
+            [0, 1, 3]
+        );
+        assert_eq!(hits("Invoice", SearchMode::Exact, true, CONTENT), [0, 3]);
+        assert_eq!(
+            hits("ivc", SearchMode::Exact, false, CONTENT),
+            [] as [usize; 0]
+        );
+        assert_eq!(hits("ivc", SearchMode::Fuzzy, false, CONTENT), [0, 1, 3]);
+        assert_eq!(
+            hits(r"^\w+ invoice", SearchMode::Regex, false, CONTENT),
+            [1]
+        );
+        assert_eq!(
+            hits("^invoice", SearchMode::Regex, true, CONTENT),
+            [] as [usize; 0]
+        );
+    }
+
+    #[test]
+    fn test_words_mode_matches_entries() {
+        // `paid` sits on a different line than `plumber` but in the same entry.
+        assert_eq!(
+            hits("+plumber +paid", SearchMode::Words, false, CONTENT),
+            [3]
+        );
+        assert_eq!(
+            hits("invoice -acme", SearchMode::Words, false, CONTENT),
+            [3]
+        );
+        assert_eq!(hits("\"not paid\"", SearchMode::Words, false, CONTENT), [4]);
+        assert_eq!(hits("-plumber", SearchMode::Words, false, CONTENT), [0]);
+        assert_eq!(hits("+ACME", SearchMode::Words, true, CONTENT), [1]);
+        assert_eq!(
+            hits("+acme", SearchMode::Words, true, CONTENT),
+            [] as [usize; 0]
+        );
+    }
+
+    #[test]
+    fn test_invalid_queries() {
+        for (query, mode) in [
+            ("(", SearchMode::Regex),
+            ("\"open phrase", SearchMode::Words),
+            ("+ word", SearchMode::Words),
+        ] {
+            let options = SearchOptions {
+                mode,
+                ..SearchOptions::default()
+            };
+            assert!(matches!(
+                LineMatcher::new(query, &options),
+                Err(OrgModeError::InvalidQuery(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_line_elements() {
+        use SearchElement::*;
+
+        let content = "Intro\n* Setup :dev:\n:PROPERTIES:\n:OWNER: ana\n:END:\n\
+                       Install it:\n#+begin_src sh\n  make install\n#+end_src\n\
+                       | step | done |\n** Notes\n#+BEGIN_SRC rust\n";
+        assert_eq!(
+            line_elements(content),
+            [
+                Body, Headline, Property, Property, Property, Body, SrcBlock, SrcBlock, SrcBlock,
+                Table, Headline, SrcBlock,
+            ]
+        );
+    }
+}
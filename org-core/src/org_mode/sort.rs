@@ -0,0 +1,220 @@
+//! Ordering of agenda items by an `org_agenda_sorting_strategy`.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::org_mode::query::{duration_minutes, timestamp_date};
+use crate::org_mode::{AgendaItem, AgendaSortKey};
+
+/// The values the sort keys compare, taken from one item as listed on a
+/// given date and time.
+pub(crate) struct SortFields {
+    listed: Option<(NaiveDate, Option<NaiveTime>)>,
+    priority: char,
+    deadline: Option<NaiveDate>,
+    scheduled: Option<NaiveDate>,
+    todo_state: Option<String>,
+    category: String,
+    heading: String,
+    effort: Option<f64>,
+}
+
+impl SortFields {
+    pub(crate) fn new(item: &AgendaItem, listed: Option<(NaiveDate, Option<NaiveTime>)>) -> Self {
+        SortFields {
+            listed,
+            priority: item
+                .priority
+                .as_deref()
+                .and_then(|p| p.chars().next())
+                .unwrap_or('B'),
+            deadline: item.deadline.as_deref().and_then(timestamp_date),
+            scheduled: item.scheduled.as_deref().and_then(timestamp_date),
+            todo_state: item.todo_state.clone(),
+            category: item.category.clone(),
+            heading: item.heading.to_lowercase(),
+            effort: item
+                .properties
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case("EFFORT"))
+                .and_then(|(_, value)| duration_minutes(value)),
+        }
+    }
+
+    /// Where a task list places the item: its scheduled day, else its
+    /// deadline.
+    pub(crate) fn for_task(item: &AgendaItem) -> Self {
+        let listed = item
+            .scheduled
+            .as_deref()
+            .or(item.deadline.as_deref())
+            .and_then(timestamp_date)
+            .map(|date| (date, None));
+        Self::new(item, listed)
+    }
+}
+
+/// Stably sorts `values` by `keys`, each compared only when the previous
+/// ones tie. `todo_keywords` gives the order of `todo-state` keys and the
+/// values' current order that of `category-keep`.
+pub(crate) fn sort_agenda<T>(
+    values: &mut Vec<T>,
+    keys: &[AgendaSortKey],
+    todo_keywords: &[String],
+    fields: impl Fn(&T) -> SortFields,
+) {
+    let mut keyed: Vec<(SortFields, T)> = values.drain(..).map(|v| (fields(&v), v)).collect();
+
+    let mut categories: HashMap<String, usize> = HashMap::new();
+    for (fields, _) in &keyed {
+        let next = categories.len();
+        categories.entry(fields.category.clone()).or_insert(next);
+    }
+    let todo_rank = |state: &Option<String>| {
+        state
+            .as_ref()
+            .and_then(|state| todo_keywords.iter().position(|k| k == state))
+    };
+
+    keyed.sort_by(|(a, _), (b, _)| {
+        keys.iter()
+            .map(|key| match key {
+                AgendaSortKey::TimeUp => present_first(time_key(a), time_key(b), false),
+                AgendaSortKey::TimeDown => present_first(time_key(a), time_key(b), true),
+                AgendaSortKey::PriorityUp => b.priority.cmp(&a.priority),
+                AgendaSortKey::PriorityDown => a.priority.cmp(&b.priority),
+                AgendaSortKey::DeadlineUp => present_first(a.deadline, b.deadline, false),
+                AgendaSortKey::DeadlineDown => present_first(a.deadline, b.deadline, true),
+                AgendaSortKey::ScheduledUp => present_first(a.scheduled, b.scheduled, false),
+                AgendaSortKey::ScheduledDown => present_first(a.scheduled, b.scheduled, true),
+                AgendaSortKey::TodoStateUp => {
+                    present_first(todo_rank(&a.todo_state), todo_rank(&b.todo_state), false)
+                }
+                AgendaSortKey::TodoStateDown => {
+                    present_first(todo_rank(&a.todo_state), todo_rank(&b.todo_state), true)
+                }
+                AgendaSortKey::CategoryKeep => {
+                    categories[&a.category].cmp(&categories[&b.category])
+                }
+                AgendaSortKey::CategoryUp => a.category.cmp(&b.category),
+                AgendaSortKey::CategoryDown => b.category.cmp(&a.category),
+                AgendaSortKey::AlphaUp => a.heading.cmp(&b.heading),
+                AgendaSortKey::AlphaDown => b.heading.cmp(&a.heading),
+                AgendaSortKey::EffortUp => present_first(a.effort, b.effort, false),
+                AgendaSortKey::EffortDown => present_first(a.effort, b.effort, true),
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+
+    values.extend(keyed.into_iter().map(|(_, v)| v));
+}
+
+/// The listing day, then timed entries by time before untimed ones.
+fn time_key(fields: &SortFields) -> Option<(NaiveDate, bool, Option<NaiveTime>)> {
+    fields
+        .listed
+        .map(|(date, time)| (date, time.is_none(), time))
+}
+
+/// Orders present values ascending, or descending when `down`, with missing
+/// ones after them.
+fn present_first<V: PartialOrd>(a: Option<V>, b: Option<V>, down: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let ordering = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+            if down { ordering.reverse() } else { ordering }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(heading: &str, category: &str) -> AgendaItem {
+        AgendaItem {
+            file_path: "tasks.org".to_string(),
+            heading: heading.to_string(),
+            level: 1,
+            todo_state: Some("TODO".to_string()),
+            priority: None,
+            scheduled: None,
+            deadline: None,
+            tags: Vec::new(),
+            inherited_tags: Vec::new(),
+            category: category.to_string(),
+            properties: Default::default(),
+            position: None,
+            days_overdue: None,
+            days_until_deadline: None,
+            start: None,
+            end: None,
+            duration_minutes: None,
+        }
+    }
+
+    fn sorted(mut items: Vec<AgendaItem>, keys: &[AgendaSortKey]) -> Vec<String> {
+        let keywords = ["TODO", "NEXT", "DONE"].map(String::from);
+        sort_agenda(&mut items, keys, &keywords, SortFields::for_task);
+        items.into_iter().map(|i| i.heading).collect()
+    }
+
+    #[test]
+    fn test_sort_keys_chain_in_order() {
+        let mut water = item("Water plants", "home");
+        water.priority = Some("A".to_string());
+        let mut report = item("report", "work");
+        report.deadline = Some("<2030-01-05 Sat>".to_string());
+        report
+            .properties
+            .insert("EFFORT".to_string(), "2h".to_string());
+        let mut call = item("Call Bob", "work");
+        call.scheduled = Some("<2030-01-02 Wed 10:00>".to_string());
+        call.todo_state = Some("NEXT".to_string());
+        call.properties
+            .insert("EFFORT".to_string(), "0:30".to_string());
+        let items = vec![water, report, call, item("Archive mail", "home")];
+
+        use AgendaSortKey::*;
+        assert_eq!(
+            sorted(items.clone(), &[TimeUp]),
+            ["Call Bob", "report", "Water plants", "Archive mail"]
+        );
+        assert_eq!(
+            sorted(items.clone(), &[PriorityDown, AlphaUp]),
+            ["Water plants", "Archive mail", "Call Bob", "report"]
+        );
+        assert_eq!(
+            sorted(items.clone(), &[CategoryKeep, AlphaDown]),
+            ["Water plants", "Archive mail", "report", "Call Bob"]
+        );
+        assert_eq!(
+            sorted(items.clone(), &[CategoryDown, TodoStateDown]),
+            ["Call Bob", "report", "Water plants", "Archive mail"]
+        );
+        assert_eq!(
+            sorted(items.clone(), &[EffortDown]),
+            ["report", "Call Bob", "Water plants", "Archive mail"]
+        );
+        assert_eq!(
+            sorted(items, &[DeadlineUp, ScheduledDown]),
+            ["report", "Call Bob", "Water plants", "Archive mail"]
+        );
+    }
+
+    #[test]
+    fn test_sort_key_names_round_trip() {
+        for name in ["time-up", "priority-down", "category-keep", "effort-up"] {
+            let key: AgendaSortKey = name.parse().unwrap();
+            assert_eq!(key.to_string(), name);
+        }
+        let err = "priority".parse::<AgendaSortKey>().unwrap_err();
+        assert!(err.starts_with("invalid sort key 'priority': expected one of time-up,"));
+    }
+}
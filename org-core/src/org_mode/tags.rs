@@ -0,0 +1,101 @@
+//! Tag inheritance from `#+FILETAGS:` and ancestor headings.
+
+/// Tags set by the `#+FILETAGS:` lines of a file, in order, without repeats.
+pub(crate) fn file_tags(content: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim_start();
+        let Some(value) = line
+            .get(..11)
+            .filter(|key| key.eq_ignore_ascii_case("#+FILETAGS:"))
+            .map(|_| &line[11..])
+        else {
+            continue;
+        };
+        for tag in value.split(|c: char| c == ':' || c.is_whitespace()) {
+            if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+    }
+    tags
+}
+
+/// Follows a document's headings in order to work out the tags each one
+/// inherits from the file and from its ancestors.
+pub(crate) struct TagInheritance<'a> {
+    exclude: &'a [String],
+    file_tags: Vec<String>,
+    ancestors: Vec<(usize, Vec<String>)>,
+}
+
+impl<'a> TagInheritance<'a> {
+    /// `exclude` lists tags that never pass down to children.
+    pub(crate) fn new(content: &str, exclude: &'a [String]) -> Self {
+        Self {
+            exclude,
+            file_tags: file_tags(content),
+            ancestors: Vec::new(),
+        }
+    }
+
+    /// Moves to the next heading, at `level` and carrying `tags` itself,
+    /// and returns the tags it inherits that it does not already have.
+    pub(crate) fn enter(&mut self, level: usize, tags: &[String]) -> Vec<String> {
+        while self.ancestors.last().is_some_and(|(l, _)| *l >= level) {
+            self.ancestors.pop();
+        }
+
+        let mut inherited: Vec<String> = Vec::new();
+        let candidates = self
+            .file_tags
+            .iter()
+            .chain(self.ancestors.iter().flat_map(|(_, tags)| tags));
+        for tag in candidates {
+            if !self.exclude.contains(tag) && !tags.contains(tag) && !inherited.contains(tag) {
+                inherited.push(tag.clone());
+            }
+        }
+
+        self.ancestors.push((level, tags.to_vec()));
+        inherited
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_file_tags_collects_every_line() {
+        let content = "#+TITLE: Notes\n#+filetags: :work:home:\n#+FILETAGS: home misc\n* A";
+        assert_eq!(file_tags(content), tags(&["work", "home", "misc"]));
+        assert!(file_tags("* Heading :work:").is_empty());
+    }
+
+    #[test]
+    fn test_inheritance_follows_ancestors() {
+        let exclude = tags(&["project"]);
+        let mut inheritance = TagInheritance::new("#+FILETAGS: :work:\n", &exclude);
+
+        assert_eq!(
+            inheritance.enter(1, &tags(&["project", "backend"])),
+            tags(&["work"])
+        );
+        assert_eq!(
+            inheritance.enter(2, &tags(&["urgent"])),
+            tags(&["work", "backend"])
+        );
+        assert_eq!(
+            inheritance.enter(3, &tags(&["work"])),
+            tags(&["backend", "urgent"])
+        );
+        // A sibling of the level-2 heading no longer sees `urgent`.
+        assert_eq!(inheritance.enter(2, &[]), tags(&["work", "backend"]));
+        assert_eq!(inheritance.enter(1, &[]), tags(&["work"]));
+    }
+}
@@ -10,6 +10,7 @@ use std::os::unix::fs::PermissionsExt;
 fn make_org_mode(temp_dir: &tempfile::TempDir) -> OrgMode {
     OrgMode::new(OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     })
     .unwrap()
@@ -961,6 +962,29 @@ fn test_capture_with_deadline_and_time() {
     );
 }
 
+#[test]
+fn test_capture_with_scheduled_ranges() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let org_mode = make_org_mode(&temp_dir);
+
+    let mut entry = capture_minimal("planning.org", "Conference");
+    entry.scheduled = Some("2026-05-01--2026-05-05".to_string());
+    org_mode.capture_append(entry).unwrap();
+    let mut entry = capture_minimal("planning.org", "Standup");
+    entry.scheduled = Some("2026-05-04 09:00-09:15 +1d".to_string());
+    org_mode.capture_append(entry).unwrap();
+
+    let content = fs::read_to_string(temp_dir.path().join("planning.org")).unwrap();
+    assert!(
+        content.contains("SCHEDULED: <2026-05-01 Fri>--<2026-05-05 Tue>"),
+        "missing date range:\n{content}"
+    );
+    assert!(
+        content.contains("SCHEDULED: <2026-05-04 Mon 09:00-09:15 +1d>"),
+        "missing time range:\n{content}"
+    );
+}
+
 #[test]
 fn test_capture_with_closed_inactive_brackets() {
     let temp_dir = tempfile::tempdir().unwrap();
@@ -1017,6 +1041,7 @@ fn test_capture_with_properties() {
     let config_with_no_auto = OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
         org_auto_created_property: false,
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
     let org_mode = OrgMode::new(config_with_no_auto).unwrap();
@@ -1050,6 +1075,7 @@ fn test_capture_empty_properties_omits_drawer() {
     let config = OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
         org_auto_created_property: false,
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
     let org_mode = OrgMode::new(config).unwrap();
@@ -1152,6 +1178,7 @@ fn test_capture_auto_created_disabled_omits_drawer_when_no_user_properties() {
     let org_mode = OrgMode::new(OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
         org_auto_created_property: false,
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     })
     .unwrap();
@@ -1204,6 +1231,7 @@ fn test_datetree_defaults_to_today() {
     let org_mode = OrgMode::new(OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
         org_auto_created_property: false,
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     })
     .unwrap();
@@ -1228,6 +1256,7 @@ fn test_datetree_creates_year_month_day_no_target() {
     let org_mode = OrgMode::new(OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
         org_auto_created_property: false,
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     })
     .unwrap();
@@ -1251,6 +1280,7 @@ fn test_datetree_under_target_heading() {
     let org_mode = OrgMode::new(OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
         org_auto_created_property: false,
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     })
     .unwrap();
@@ -1276,6 +1306,7 @@ fn test_datetree_reuses_existing_day() {
     let org_mode = OrgMode::new(OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
         org_auto_created_property: false,
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     })
     .unwrap();
@@ -1344,6 +1375,7 @@ fn test_capture_with_planning_and_properties() {
     let org_mode = OrgMode::new(OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
         org_auto_created_property: false,
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     })
     .unwrap();
@@ -1377,6 +1409,7 @@ fn test_capture_with_everything() {
     let org_mode = OrgMode::new(OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
         org_auto_created_property: false,
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     })
     .unwrap();
@@ -1,12 +1,17 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
 use chrono::{DateTime, Local};
 use serde::de::Error as _;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::config::OrgConfig;
+use crate::org_mode::index::MetadataIndex;
 
 #[derive(Debug)]
 pub struct OrgMode {
     pub(crate) config: OrgConfig,
+    pub(crate) index: Mutex<MetadataIndex>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,10 +27,166 @@ pub struct TreeNode {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub file_path: String,
+    /// Path of the heading enclosing the hit, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_path: Option<String>,
+    /// Level of that heading; 0 for hits before the first heading.
+    #[serde(default)]
+    pub level: usize,
+    /// 1-based line number of the hit.
+    pub line: usize,
+    /// Byte span of the enclosing heading's subtree.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+    /// `:ID:` of the enclosing heading.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub snippet: String,
     pub score: u32,
+    /// Tags set on the enclosing heading itself.
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub tags: Vec<String>,
+    /// Tags it inherits from `#+FILETAGS:` and its ancestors; the file tags
+    /// for hits before the first heading.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub inherited_tags: Vec<String>,
+}
+
+/// How `OrgMode::search` matches its query against lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SearchMode {
+    /// Fuzzy matching, best hits first.
+    #[default]
+    Fuzzy,
+    /// Plain substring.
+    Exact,
+    /// A `regex` crate pattern.
+    Regex,
+    /// Emacs-style `+must -mustnot "some phrase"` keywords, applied to
+    /// whole entries.
+    Words,
+    /// BM25 relevance over whole heading sections, from the full-text
+    /// index. Stems words, ignores case and stopwords, and weighs title
+    /// words above body words. Each hit is a section: its snippet is the
+    /// section text and its score the BM25 score times 1000.
+    Ranked,
+}
+
+impl std::fmt::Display for SearchMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SearchMode::Fuzzy => "fuzzy",
+            SearchMode::Exact => "exact",
+            SearchMode::Regex => "regex",
+            SearchMode::Words => "words",
+            SearchMode::Ranked => "ranked",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl std::str::FromStr for SearchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fuzzy" => Ok(SearchMode::Fuzzy),
+            "exact" => Ok(SearchMode::Exact),
+            "regex" => Ok(SearchMode::Regex),
+            "words" => Ok(SearchMode::Words),
+            "ranked" => Ok(SearchMode::Ranked),
+            other => Err(format!(
+                "invalid search mode '{other}': expected fuzzy, exact, regex, words, or ranked"
+            )),
+        }
+    }
+}
+
+impl Serialize for SearchMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SearchMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// A kind of line `OrgMode::search` can be restricted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchElement {
+    Headline,
+    /// Text that is not one of the other kinds.
+    Body,
+    /// Lines of `:PROPERTIES:` drawers.
+    Property,
+    /// Lines of `#+BEGIN_SRC` blocks.
+    SrcBlock,
+    Table,
+}
+
+impl std::fmt::Display for SearchElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SearchElement::Headline => "headline",
+            SearchElement::Body => "body",
+            SearchElement::Property => "property",
+            SearchElement::SrcBlock => "src_block",
+            SearchElement::Table => "table",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl std::str::FromStr for SearchElement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "headline" => Ok(SearchElement::Headline),
+            "body" => Ok(SearchElement::Body),
+            "property" => Ok(SearchElement::Property),
+            "src_block" => Ok(SearchElement::SrcBlock),
+            "table" => Ok(SearchElement::Table),
+            other => Err(format!(
+                "invalid element '{other}': expected headline, body, property, src_block, or table"
+            )),
+        }
+    }
+}
+
+impl Serialize for SearchElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SearchElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub mode: SearchMode,
+    /// Case-insensitive unless set.
+    pub case_sensitive: bool,
+    /// Emacs tags/property match string the entry around a hit must satisfy.
+    pub match_string: Option<String>,
+    /// Keeps hits whose heading carries any of these tags.
+    pub tags: Option<Vec<String>>,
+    /// File, directory or glob to search, like an `org_agenda_files` entry,
+    /// instead of the org directory and `org_agenda_text_search_extra_files`.
+    pub files: Option<String>,
+    /// Slash-separated path of the heading whose subtree is searched.
+    pub heading_path: Option<String>,
+    /// Kinds of lines searched; every kind when empty.
+    pub elements: Vec<SearchElement>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -64,10 +225,45 @@ pub struct AgendaItem {
     pub deadline: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub tags: Vec<String>,
+    /// Tags from `#+FILETAGS:` and ancestor headings not set on the heading
+    /// itself.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub inherited_tags: Vec<String>,
+    /// `:CATEGORY:` of the heading or an ancestor, else `#+CATEGORY:`, else
+    /// the file name without extension.
+    #[serde(default)]
+    pub category: String,
+    /// The heading's property drawer plus inherited values for the keys in
+    /// `org_use_property_inheritance`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub properties: BTreeMap<String, String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub position: Option<Position>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub days_overdue: Option<i64>,
+    /// Days from today to the deadline, negative once it has passed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_until_deadline: Option<i64>,
+    /// Start of the entry's first time or date range, `YYYY-MM-DD` with
+    /// ` HH:MM` when timed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    /// End of that range, in the same format.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    /// Length of that range; all-day ranges count each day in full.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_minutes: Option<i64>,
+}
+
+impl AgendaItem {
+    /// Whether `tag` applies to the item, set on it or inherited.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags
+            .iter()
+            .chain(&self.inherited_tags)
+            .any(|t| t == tag)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,6 +273,204 @@ pub struct AgendaView {
     pub start_date: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end_date: Option<String>,
+    /// One bucket per day of the view, empty days included.
+    #[serde(default)]
+    pub days: Vec<AgendaDay>,
+}
+
+/// Why an item shows up on a day of an agenda view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AgendaReason {
+    Scheduled,
+    Deadline,
+    /// An active timestamp in the entry other than SCHEDULED and DEADLINE.
+    Timestamp,
+    /// A later occurrence of a repeating timestamp.
+    Repeat,
+    /// A past timestamp of an open task, carried over to today.
+    Overdue,
+    /// An open task's deadline within its warning period, shown today.
+    Upcoming,
+    /// A day a diary sexp such as `<%%(diary-float t 4 2)>` matches.
+    Diary,
+}
+
+impl std::fmt::Display for AgendaReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AgendaReason::Scheduled => "scheduled",
+            AgendaReason::Deadline => "deadline",
+            AgendaReason::Timestamp => "timestamp",
+            AgendaReason::Repeat => "repeat",
+            AgendaReason::Overdue => "overdue",
+            AgendaReason::Upcoming => "upcoming",
+            AgendaReason::Diary => "diary",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl std::str::FromStr for AgendaReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scheduled" => Ok(AgendaReason::Scheduled),
+            "deadline" => Ok(AgendaReason::Deadline),
+            "timestamp" => Ok(AgendaReason::Timestamp),
+            "repeat" => Ok(AgendaReason::Repeat),
+            "overdue" => Ok(AgendaReason::Overdue),
+            "upcoming" => Ok(AgendaReason::Upcoming),
+            "diary" => Ok(AgendaReason::Diary),
+            other => Err(format!(
+                "invalid agenda reason '{other}': expected scheduled, deadline, timestamp, repeat, overdue, upcoming, or diary"
+            )),
+        }
+    }
+}
+
+impl Serialize for AgendaReason {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AgendaReason {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// An item on one day of an agenda view.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgendaEntry {
+    /// Index into [`AgendaView::items`].
+    pub item: usize,
+    pub reason: AgendaReason,
+    /// `YYYY-MM-DD` of the timestamp occurrence behind the entry, earlier
+    /// than the day for overdue entries and the later days of a date range,
+    /// and later for upcoming deadlines.
+    pub date: String,
+    /// `HH:MM`, for timestamps carrying a time of day.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+}
+
+/// The entries of one day, in the view's sort order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgendaDay {
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    pub entries: Vec<AgendaEntry>,
+}
+
+/// One step of an agenda sorting strategy, named as in Emacs'
+/// `org-agenda-sorting-strategy`. `-up` sorts ascending and `-down`
+/// descending; entries missing the value sort last either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AgendaSortKey {
+    /// Date the entry is listed on, then time of day, timed entries first.
+    TimeUp,
+    TimeDown,
+    /// Priority cookie, `B` when absent, so `priority-down` puts `A` first.
+    PriorityUp,
+    PriorityDown,
+    DeadlineUp,
+    DeadlineDown,
+    ScheduledUp,
+    ScheduledDown,
+    /// Position of the TODO keyword in `org_todo_keywords`.
+    TodoStateUp,
+    TodoStateDown,
+    /// Order in which categories first appear in the agenda files.
+    CategoryKeep,
+    CategoryUp,
+    CategoryDown,
+    /// Heading text, case-insensitively.
+    AlphaUp,
+    AlphaDown,
+    /// `EFFORT` property.
+    EffortUp,
+    EffortDown,
+}
+
+impl AgendaSortKey {
+    const NAMES: [(AgendaSortKey, &'static str); 17] = [
+        (AgendaSortKey::TimeUp, "time-up"),
+        (AgendaSortKey::TimeDown, "time-down"),
+        (AgendaSortKey::PriorityUp, "priority-up"),
+        (AgendaSortKey::PriorityDown, "priority-down"),
+        (AgendaSortKey::DeadlineUp, "deadline-up"),
+        (AgendaSortKey::DeadlineDown, "deadline-down"),
+        (AgendaSortKey::ScheduledUp, "scheduled-up"),
+        (AgendaSortKey::ScheduledDown, "scheduled-down"),
+        (AgendaSortKey::TodoStateUp, "todo-state-up"),
+        (AgendaSortKey::TodoStateDown, "todo-state-down"),
+        (AgendaSortKey::CategoryKeep, "category-keep"),
+        (AgendaSortKey::CategoryUp, "category-up"),
+        (AgendaSortKey::CategoryDown, "category-down"),
+        (AgendaSortKey::AlphaUp, "alpha-up"),
+        (AgendaSortKey::AlphaDown, "alpha-down"),
+        (AgendaSortKey::EffortUp, "effort-up"),
+        (AgendaSortKey::EffortDown, "effort-down"),
+    ];
+}
+
+impl std::fmt::Display for AgendaSortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (_, name) = Self::NAMES
+            .iter()
+            .find(|(key, _)| key == self)
+            .expect("every sort key is named");
+        write!(f, "{name}")
+    }
+}
+
+impl std::str::FromStr for AgendaSortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(key, _)| *key)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::NAMES.iter().map(|(_, name)| *name).collect();
+                format!(
+                    "invalid sort key '{s}': expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl Serialize for AgendaSortKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AgendaSortKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// Filters and ordering for `OrgMode::list_tasks_with_options` and
+/// `OrgMode::get_agenda_view_with_options`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AgendaOptions {
+    pub todo_states: Option<Vec<String>>,
+    pub tags: Option<Vec<String>>,
+    /// Only applies to task lists.
+    pub priority: Option<Priority>,
+    /// Emacs tags/property match string the items must satisfy.
+    pub match_string: Option<String>,
+    /// Overrides `org_agenda_sorting_strategy`.
+    pub sort: Option<Vec<AgendaSortKey>>,
+    pub limit: Option<usize>,
 }
 
 #[derive(Default)]
@@ -229,6 +623,9 @@ pub struct UpdateEntry {
     pub properties: Option<Vec<PropertyPair>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub remove_properties: Option<Vec<String>>,
+    /// Text attached to the state change note in the logbook.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -238,6 +635,339 @@ pub struct UpdateResult {
     pub changes: Vec<String>,
 }
 
+/// Identifies a single headline, either by `:ID:` or by `file` + `heading_path`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeadingTarget {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_path: Option<String>,
+}
+
+impl std::fmt::Display for HeadingTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.id, &self.heading_path) {
+            (Some(id), _) => write!(f, "{id}"),
+            (None, Some(path)) => write!(f, "{path}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClockAction {
+    In,
+    Out,
+    Cancel,
+}
+
+impl std::fmt::Display for ClockAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ClockAction::In => "in",
+            ClockAction::Out => "out",
+            ClockAction::Cancel => "cancel",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl std::str::FromStr for ClockAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "in" => Ok(ClockAction::In),
+            "out" => Ok(ClockAction::Out),
+            "cancel" => Ok(ClockAction::Cancel),
+            other => Err(format!(
+                "invalid clock action '{other}': expected in, out, or cancel"
+            )),
+        }
+    }
+}
+
+impl Serialize for ClockAction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ClockAction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockResult {
+    pub file_path: String,
+    pub heading_line: String,
+    pub action: ClockAction,
+    pub clock_line: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_minutes: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ClockGroupBy {
+    #[default]
+    Heading,
+    File,
+    Tag,
+}
+
+impl std::fmt::Display for ClockGroupBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ClockGroupBy::Heading => "heading",
+            ClockGroupBy::File => "file",
+            ClockGroupBy::Tag => "tag",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl std::str::FromStr for ClockGroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "heading" => Ok(ClockGroupBy::Heading),
+            "file" => Ok(ClockGroupBy::File),
+            "tag" => Ok(ClockGroupBy::Tag),
+            other => Err(format!(
+                "invalid group_by '{other}': expected heading, file, or tag"
+            )),
+        }
+    }
+}
+
+impl Serialize for ClockGroupBy {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ClockGroupBy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockReportRow {
+    /// Heading path, file path, or tag depending on `group_by`.
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    pub minutes: i64,
+    pub duration: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub group_by: ClockGroupBy,
+    pub total_minutes: i64,
+    pub total: String,
+    pub rows: Vec<ClockReportRow>,
+}
+
+/// Where a refiled subtree goes: the end of `heading_path` in `file`, or
+/// the end of `file` as a top-level heading when no path is given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RefileTarget {
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefileResult {
+    pub source_file: String,
+    pub target_file: String,
+    pub heading_line: String,
+    pub level: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub under_target: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ArchiveMode {
+    #[default]
+    Subtree,
+    Tag,
+}
+
+impl std::fmt::Display for ArchiveMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ArchiveMode::Subtree => "subtree",
+            ArchiveMode::Tag => "tag",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl std::str::FromStr for ArchiveMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "subtree" => Ok(ArchiveMode::Subtree),
+            "tag" => Ok(ArchiveMode::Tag),
+            other => Err(format!(
+                "invalid archive mode '{other}': expected subtree or tag"
+            )),
+        }
+    }
+}
+
+impl Serialize for ArchiveMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ArchiveMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveResult {
+    pub file_path: String,
+    pub heading_line: String,
+    pub mode: ArchiveMode,
+    /// Whether the heading ends up archived; `false` only when tag mode
+    /// removed an existing `:ARCHIVE:` tag.
+    pub archived: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_file: Option<String>,
+}
+
+/// A link pointing at a node, as returned by `OrgMode::backlinks`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backlink {
+    pub file_path: String,
+    /// Path of the heading enclosing the link, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_path: Option<String>,
+    /// 1-based line number of the link.
+    pub line: usize,
+    pub link: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub context: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LinkKind {
+    Id,
+    File,
+    Https,
+    Attachment,
+    Custom,
+}
+
+impl std::fmt::Display for LinkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            LinkKind::Id => "id",
+            LinkKind::File => "file",
+            LinkKind::Https => "https",
+            LinkKind::Attachment => "attachment",
+            LinkKind::Custom => "custom",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl std::str::FromStr for LinkKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "id" => Ok(LinkKind::Id),
+            "file" => Ok(LinkKind::File),
+            "https" => Ok(LinkKind::Https),
+            "attachment" => Ok(LinkKind::Attachment),
+            "custom" => Ok(LinkKind::Custom),
+            other => Err(format!(
+                "invalid link kind '{other}': expected id, file, https, attachment, or custom"
+            )),
+        }
+    }
+}
+
+impl Serialize for LinkKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LinkKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// An outgoing link, as returned by `OrgMode::links_in`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrgLink {
+    pub kind: LinkKind,
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub file_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_path: Option<String>,
+    /// 1-based line number of the link.
+    pub line: usize,
+    /// Whether the target exists; `None` for web and custom links and for
+    /// files outside the org directory other than the agenda files, which
+    /// are not checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolves: Option<bool>,
+}
+
+/// Files added, modified or removed since the previous `OrgMode::refresh`.
+/// Paths are relative to the org directory, except for agenda files outside
+/// it, which are absolute.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileChanges {
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+    /// Whether any of the changed files is matched by `org_agenda_files`.
+    pub agenda_changed: bool,
+}
+
+impl FileChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl UpdateEntry {
+    pub fn target(&self) -> HeadingTarget {
+        HeadingTarget {
+            id: self.id.clone(),
+            file: self.file.clone(),
+            heading_path: self.heading_path.clone(),
+        }
+    }
+}
+
 impl TreeNode {
     pub fn new(label: String) -> Self {
         Self {
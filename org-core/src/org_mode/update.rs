@@ -6,8 +6,14 @@ use orgize::export::{Container, Event, from_fn, from_fn_with_ctx};
 use orgize::{Org, ParseConfig};
 
 use crate::OrgModeError;
-use crate::org_mode::capture::ParsedTimestamp;
-use crate::org_mode::{ClearField, OrgMode, UpdateEntry, UpdateResult};
+use crate::config::StateLogging;
+use crate::org_mode::capture::{MAX_HEADING_LEVEL, ParsedTimestamp};
+use crate::org_mode::logbook::{push_logbook_entry, state_change_entry};
+use crate::org_mode::repeat::shift_repeating_timestamp;
+use crate::org_mode::{ClearField, HeadingTarget, OrgMode, UpdateEntry, UpdateResult};
+
+/// Property recording when a repeating task was last marked done.
+const LAST_REPEAT: &str = "LAST_REPEAT";
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub(crate) struct PlanningValues {
@@ -38,25 +44,26 @@ pub(crate) struct ResolvedUpdate {
 }
 
 #[derive(Debug)]
-struct TargetHeadline {
-    line_idx: usize,
-    level: usize,
-    title: String,
-    keyword: Option<String>,
-    priority: Option<String>,
-    tags: Vec<String>,
+pub(crate) struct TargetHeadline {
+    pub line_idx: usize,
+    pub level: usize,
+    pub title: String,
+    pub keyword: Option<String>,
+    pub priority: Option<String>,
+    pub tags: Vec<String>,
     ambiguity: Option<String>,
-    planning_first_line: usize,
-    planning_line_count: usize,
-    planning_values: PlanningValues,
-    property_drawer_first_line: usize,
-    property_drawer_line_count: usize,
-    existing_properties: Vec<(String, String)>,
-    body_first_line: usize,
-    body_last_line: usize,
+    pub planning_first_line: usize,
+    pub planning_line_count: usize,
+    pub planning_values: PlanningValues,
+    pub property_drawer_first_line: usize,
+    pub property_drawer_line_count: usize,
+    pub existing_properties: Vec<(String, String)>,
+    pub body_first_line: usize,
+    pub body_last_line: usize,
+    pub subtree_end_line: usize,
 }
 
-fn line_index_at(content: &str, byte_offset: usize) -> usize {
+pub(crate) fn line_index_at(content: &str, byte_offset: usize) -> usize {
     content[..byte_offset]
         .bytes()
         .filter(|b| *b == b'\n')
@@ -164,6 +171,61 @@ fn extract_property_drawer(
     (start_line, drawer_line_count, properties)
 }
 
+/// Exclusive line index where a node ending at `byte_offset` stops.
+pub(crate) fn line_end_at(content: &str, byte_offset: usize) -> usize {
+    let idx = line_index_at(content, byte_offset);
+    if byte_offset > 0 && !content[..byte_offset].ends_with('\n') {
+        idx + 1
+    } else {
+        idx
+    }
+}
+
+/// Level of a headline line (`** Title` -> 2), or `None` for other lines.
+pub(crate) fn headline_level(line: &str) -> Option<usize> {
+    let stars = line.len() - line.trim_start_matches('*').len();
+    (stars > 0 && line[stars..].starts_with(' ')).then_some(stars)
+}
+
+/// Shifts every headline in `lines` by `delta` levels, keeping the result
+/// within `1..=MAX_HEADING_LEVEL`.
+pub(crate) fn shift_headline_levels(
+    lines: &mut [String],
+    delta: isize,
+) -> Result<(), OrgModeError> {
+    if delta == 0 {
+        return Ok(());
+    }
+    for line in lines.iter_mut() {
+        if let Some(level) = headline_level(line) {
+            let shifted = level as isize + delta;
+            if shifted < 1 || shifted as usize > MAX_HEADING_LEVEL {
+                return Err(OrgModeError::InvalidLevel(shifted.max(0) as usize));
+            }
+            *line = format!("{}{}", "*".repeat(shifted as usize), &line[level..]);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn detect_newline(content: &str) -> &'static str {
+    if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Joins spliced lines back together, keeping the original trailing newline.
+pub(crate) fn join_lines(lines: &[String], original: &str) -> String {
+    let newline = detect_newline(original);
+    let mut out = lines.join(newline);
+    if original.ends_with('\n') && !lines.is_empty() {
+        out.push_str(newline);
+    }
+    out
+}
+
 fn find_body_last_line(content: &str, body_first_line: usize) -> usize {
     let all_lines: Vec<&str> = content.lines().collect();
     all_lines[body_first_line..]
@@ -174,29 +236,28 @@ fn find_body_last_line(content: &str, body_first_line: usize) -> usize {
 }
 
 impl OrgMode {
-    pub(crate) fn validate_update(
-        &self,
-        entry: &UpdateEntry,
-    ) -> Result<ResolvedUpdate, OrgModeError> {
+    /// Checks the id OR (file + heading_path) targeting shared by every
+    /// operation that edits an existing heading in place.
+    pub(crate) fn validate_heading_target(target: &HeadingTarget) -> Result<(), OrgModeError> {
         // Targeting: id OR (file + heading_path); id wins when both are given.
-        if entry.file.is_some() != entry.heading_path.is_some() {
+        if target.file.is_some() != target.heading_path.is_some() {
             return Err(OrgModeError::InvalidUpdate(
                 "file and heading_path must be given together".to_string(),
             ));
         }
-        if entry.id.is_none() && entry.file.is_none() {
+        if target.id.is_none() && target.file.is_none() {
             return Err(OrgModeError::InvalidUpdate(
                 "target required: pass id or file + heading_path".to_string(),
             ));
         }
-        if let Some(ref id) = entry.id
+        if let Some(ref id) = target.id
             && id.trim().is_empty()
         {
             return Err(OrgModeError::InvalidUpdate(
                 "id must not be empty".to_string(),
             ));
         }
-        if let Some(ref path) = entry.heading_path {
+        if let Some(ref path) = target.heading_path {
             for segment in path.split('/') {
                 if segment.trim().is_empty() {
                     return Err(OrgModeError::InvalidHeadingPath(format!(
@@ -205,6 +266,17 @@ impl OrgMode {
                 }
             }
         }
+        if let Some(ref f) = target.file {
+            Self::validate_relative_file_path(f)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn validate_update(
+        &self,
+        entry: &UpdateEntry,
+    ) -> Result<ResolvedUpdate, OrgModeError> {
+        Self::validate_heading_target(&entry.target())?;
 
         // At least one mutation.
         if entry.todo_state.is_none()
@@ -217,6 +289,7 @@ impl OrgMode {
             && entry.body.is_none()
             && entry.properties.is_none()
             && entry.remove_properties.is_none()
+            && entry.note.is_none()
             && entry.clear.is_empty()
         {
             return Err(OrgModeError::InvalidUpdate("nothing to update".to_string()));
@@ -246,17 +319,10 @@ impl OrgMode {
             }
         }
 
-        if let Some(ref kw) = entry.todo_state {
-            let valid_keywords: Vec<&str> = self
-                .config
-                .org_todo_keywords
-                .iter()
-                .filter(|k| k.as_str() != "|")
-                .map(|k| k.as_str())
-                .collect();
-            if !valid_keywords.contains(&kw.as_str()) {
-                return Err(OrgModeError::InvalidTodoKeyword(kw.clone()));
-            }
+        if let Some(ref kw) = entry.todo_state
+            && !self.config.todo_keywords().contains(kw)
+        {
+            return Err(OrgModeError::InvalidTodoKeyword(kw.clone()));
         }
 
         if let Some(ref p) = entry.priority
@@ -328,10 +394,6 @@ impl OrgMode {
             }
         }
 
-        if let Some(ref f) = entry.file {
-            Self::validate_relative_file_path(f)?;
-        }
-
         Ok(ResolvedUpdate {
             scheduled,
             deadline,
@@ -341,25 +403,18 @@ impl OrgMode {
 
     pub fn update_todo(&self, entry: UpdateEntry) -> Result<UpdateResult, OrgModeError> {
         let resolved = self.validate_update(&entry)?;
-        let (file_rel, full_path) = self.resolve_target_file(&entry)?;
-
-        let lock_path = Self::lock_path_for(&full_path)?;
-        let lock_file = Self::acquire_capture_lock(&lock_path)?;
-
-        let result = self.apply_update(&file_rel, &full_path, &entry, &resolved);
+        let (file_rel, full_path) = self.resolve_target_file(&entry.target())?;
 
-        // Same lock-release dance as capture_append.
-        #[cfg(unix)]
-        let _ = fs::remove_file(&lock_path);
-        drop(lock_file);
-        #[cfg(not(unix))]
-        let _ = fs::remove_file(&lock_path);
-
-        result
+        Self::with_capture_lock(&full_path, || {
+            self.apply_update(&file_rel, &full_path, &entry, &resolved)
+        })
     }
 
-    fn resolve_target_file(&self, entry: &UpdateEntry) -> Result<(String, PathBuf), OrgModeError> {
-        if let Some(ref id) = entry.id {
+    pub(crate) fn resolve_target_file(
+        &self,
+        target: &HeadingTarget,
+    ) -> Result<(String, PathBuf), OrgModeError> {
+        if let Some(ref id) = target.id {
             let mut matches: Vec<String> = Vec::new();
             for path in self.list_files(None, None)? {
                 let content = self.read_file(&path)?;
@@ -378,7 +433,7 @@ impl OrgMode {
             };
         }
 
-        let file_rel = entry.file.clone().unwrap();
+        let file_rel = target.file.clone().unwrap();
         let full_path = PathBuf::from(&self.config.org_directory).join(&file_rel);
         if !full_path.is_file() {
             return Err(OrgModeError::HeadingNotFound(format!(
@@ -418,33 +473,13 @@ impl OrgMode {
         resolved: &ResolvedUpdate,
     ) -> Result<UpdateResult, OrgModeError> {
         let content = fs::read_to_string(full_path).map_err(OrgModeError::IoError)?;
-
-        let parse_config = ParseConfig {
-            todo_keywords: (
-                self.config.unfinished_keywords(),
-                self.config.finished_keywords(),
-            ),
-            ..Default::default()
-        };
-        let org = parse_config.parse(&content);
-
-        let target = match self.locate_headline(&org, &content, entry)? {
-            Some(t) if t.ambiguity.is_none() => t,
-            Some(t) => return Err(OrgModeError::AmbiguousTarget(t.ambiguity.unwrap())),
-            None => {
-                let shown = entry
-                    .id
-                    .clone()
-                    .unwrap_or_else(|| entry.heading_path.clone().unwrap_or_default());
-                return Err(OrgModeError::HeadingNotFound(shown));
-            }
-        };
+        let target = self.locate_target(&content, &entry.target())?;
 
         let mut lines: Vec<String> = content.lines().map(String::from).collect();
         // Resulting field values: explicit set > clear > existing.
         let cleared = |f: ClearField| entry.clear.contains(&f);
 
-        let new_keyword = if cleared(ClearField::TodoState) {
+        let mut new_keyword = if cleared(ClearField::TodoState) {
             None
         } else {
             entry.todo_state.clone().or(target.keyword.clone())
@@ -459,7 +494,7 @@ impl OrgMode {
         } else {
             entry.tags.clone().unwrap_or_else(|| target.tags.clone())
         };
-        let new_scheduled = if cleared(ClearField::Scheduled) {
+        let mut new_scheduled = if cleared(ClearField::Scheduled) {
             None
         } else {
             resolved
@@ -468,7 +503,7 @@ impl OrgMode {
                 .map(|ts| Self::format_org_timestamp(ts, true))
                 .or(target.planning_values.scheduled.clone())
         };
-        let new_deadline = if cleared(ClearField::Deadline) {
+        let mut new_deadline = if cleared(ClearField::Deadline) {
             None
         } else {
             resolved
@@ -478,10 +513,64 @@ impl OrgMode {
                 .or(target.planning_values.deadline.clone())
         };
 
-        let is_done = new_keyword
-            .as_ref()
-            .map(|k| self.config.finished_keywords().contains(k))
-            .unwrap_or(false);
+        let now = chrono::Local::now();
+        let stamp = Self::format_org_timestamp(
+            &ParsedTimestamp {
+                date: now.date_naive(),
+                time: Some(now.time()),
+                repeater: None,
+                warning: None,
+                end_date: None,
+                end_time: None,
+            },
+            false,
+        );
+        let requested_keyword = new_keyword.clone();
+        let state_changed = requested_keyword != target.keyword;
+        if entry.note.is_some() && !state_changed {
+            return Err(OrgModeError::InvalidUpdate(
+                "note requires a todo_state change".to_string(),
+            ));
+        }
+
+        // A repeating task moves on to its next occurrence instead of
+        // staying done, leaving LAST_REPEAT and a state note behind.
+        let finished = self.config.finished_keywords();
+        let is_finished = |k: &Option<String>| k.as_ref().is_some_and(|k| finished.contains(k));
+        let mut repeated = false;
+        if is_finished(&new_keyword) && !is_finished(&target.keyword) {
+            let naive_now = now.naive_local();
+            let shift = |ts: &Option<String>| {
+                ts.as_deref()
+                    .and_then(|ts| shift_repeating_timestamp(ts, naive_now))
+            };
+            let (scheduled, deadline) = (shift(&new_scheduled), shift(&new_deadline));
+            if (scheduled.is_some() || deadline.is_some())
+                && let Some(reset) = self.config.unfinished_keywords().into_iter().next()
+            {
+                new_keyword = Some(reset);
+                new_scheduled = scheduled.or(new_scheduled);
+                new_deadline = deadline.or(new_deadline);
+                repeated = true;
+            }
+        }
+
+        // Repeats are always logged, as with Emacs' default `org-log-repeat`.
+        let logging = self
+            .config
+            .state_logging(target.keyword.as_deref(), requested_keyword.as_deref());
+        let state_note = (state_changed
+            && (repeated || entry.note.is_some() || logging != StateLogging::None))
+            .then(|| {
+                state_change_entry(
+                    requested_keyword.as_deref(),
+                    target.keyword.as_deref(),
+                    &stamp,
+                    entry.note.as_deref(),
+                )
+            });
+
+        let is_done = is_finished(&new_keyword);
         let new_closed = if cleared(ClearField::Closed) {
             None
         } else if let Some(ts) = &resolved.closed {
@@ -489,18 +578,7 @@ impl OrgMode {
         } else if is_done {
             match target.planning_values.closed.clone() {
                 Some(existing) => Some(existing),
-                None if self.config.org_auto_closed_timestamp => {
-                    let now = chrono::Local::now();
-                    Some(Self::format_org_timestamp(
-                        &ParsedTimestamp {
-                            date: now.date_naive(),
-                            time: Some(now.time()),
-                            repeater: None,
-                            warning: None,
-                        },
-                        false,
-                    ))
-                }
+                None if self.config.org_auto_closed_timestamp => Some(stamp.clone()),
                 None => None,
             }
         } else if self.config.org_auto_closed_timestamp {
@@ -533,18 +611,15 @@ impl OrgMode {
             replacement,
         );
 
-        let newline = if content.contains("\r\n") {
-            "\r\n"
-        } else {
-            "\n"
-        };
+        let newline = detect_newline(&content);
 
         let plan_delta: isize = replacement_len as isize - target.planning_line_count as isize;
 
         // --- Property drawer splice ---
         let drawer_first = (target.property_drawer_first_line as isize + plan_delta) as usize;
         let drawer_count = target.property_drawer_line_count;
-        let need_drawer_update = entry.properties.is_some() || entry.remove_properties.is_some();
+        let need_drawer_update =
+            entry.properties.is_some() || entry.remove_properties.is_some() || repeated;
         let drawer_delta: isize;
         let mut changes = Vec::new();
 
@@ -572,6 +647,16 @@ impl OrgMode {
                 }
             }
 
+            if repeated {
+                match props
+                    .iter_mut()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(LAST_REPEAT))
+                {
+                    Some(existing) => existing.1 = stamp.clone(),
+                    None => props.push((LAST_REPEAT.to_string(), stamp.clone())),
+                }
+            }
+
             let new_drawer_lines: Vec<String> = if props.is_empty() {
                 vec![]
             } else {
@@ -614,6 +699,14 @@ impl OrgMode {
                     }
                 }
             }
+            if repeated {
+                Self::push_change(
+                    &mut changes,
+                    &format!("property:{LAST_REPEAT}"),
+                    old_map.get(LAST_REPEAT).map(|s| s.as_str()),
+                    Some(stamp.as_str()),
+                );
+            }
 
             lines.splice(drawer_first..drawer_first + drawer_count, new_drawer_lines);
         } else {
@@ -636,19 +729,27 @@ impl OrgMode {
             None
         };
 
+        let mut body_last = body_last;
         let body_diff: Option<(String, String)> = if let Some(new_body_lines) = new_body {
             let old_body_text = lines[body_first..body_last].join(newline);
             let new_body_text = new_body_lines.join(newline);
+            let new_body_len = new_body_lines.len();
             lines.splice(body_first..body_last, new_body_lines);
+            body_last = body_first + new_body_len;
             Some((old_body_text, new_body_text))
         } else {
             None
         };
 
-        let mut out = lines.join(newline);
-        if content.ends_with('\n') {
-            out.push_str(newline);
+        if let Some(ref note) = state_note {
+            if self.config.org_log_into_drawer {
+                push_logbook_entry(&mut lines, body_first, body_last, "", note);
+            } else {
+                lines.splice(body_first..body_first, note.lines().map(String::from));
+            }
         }
+
+        let out = join_lines(&lines, &content);
         Self::atomic_write(full_path, out.as_bytes())?;
 
         Self::push_change(
@@ -695,6 +796,9 @@ impl OrgMode {
             Some(target.title.as_str()),
             Some(new_title),
         );
+        if let Some(ref note) = state_note {
+            Self::push_change(&mut changes, "logbook", None, Some(note.as_str()));
+        }
         if let Some((ref old_body, ref new_body_text)) = body_diff {
             Self::push_change(
                 &mut changes,
@@ -719,7 +823,12 @@ impl OrgMode {
         })
     }
 
-    fn push_change(changes: &mut Vec<String>, name: &str, old: Option<&str>, new: Option<&str>) {
+    pub(crate) fn push_change(
+        changes: &mut Vec<String>,
+        name: &str,
+        old: Option<&str>,
+        new: Option<&str>,
+    ) {
         if old != new {
             changes.push(format!(
                 "{name}: {} -> {}",
@@ -729,15 +838,40 @@ impl OrgMode {
         }
     }
 
+    pub(crate) fn parse_config(&self) -> ParseConfig {
+        ParseConfig {
+            todo_keywords: (
+                self.config.unfinished_keywords(),
+                self.config.finished_keywords(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    /// Parses `content` and resolves `target` to exactly one headline.
+    pub(crate) fn locate_target(
+        &self,
+        content: &str,
+        target: &HeadingTarget,
+    ) -> Result<TargetHeadline, OrgModeError> {
+        let org = self.parse_config().parse(content);
+
+        match self.locate_headline(&org, content, target)? {
+            Some(t) if t.ambiguity.is_none() => Ok(t),
+            Some(t) => Err(OrgModeError::AmbiguousTarget(t.ambiguity.unwrap())),
+            None => Err(OrgModeError::HeadingNotFound(target.to_string())),
+        }
+    }
+
     fn locate_headline(
         &self,
         org: &Org,
         content: &str,
-        entry: &UpdateEntry,
+        target: &HeadingTarget,
     ) -> Result<Option<TargetHeadline>, OrgModeError> {
         let mut matches: Vec<TargetHeadline> = Vec::new();
 
-        if let Some(ref id) = entry.id {
+        if let Some(ref id) = target.id {
             let mut handler = from_fn_with_ctx(|event, ctx| {
                 if let Event::Enter(Container::Headline(ref h)) = event {
                     let has_id = h
@@ -777,6 +911,7 @@ impl OrgMode {
                             existing_properties,
                             body_first_line,
                             body_last_line,
+                            subtree_end_line: line_end_at(content, h.end().into()),
                         });
                         // Two matches suffice to report ambiguity; stop early.
                         if matches.len() == 2 {
@@ -787,7 +922,7 @@ impl OrgMode {
             });
             org.traverse(&mut handler);
         } else {
-            let path = entry.heading_path.clone().unwrap_or_default();
+            let path = target.heading_path.clone().unwrap_or_default();
             let parts: Vec<&str> = path.split('/').collect();
             let mut stack: Vec<(usize, String)> = Vec::new();
             let mut handler = from_fn(|event| {
@@ -833,6 +968,7 @@ impl OrgMode {
                             existing_properties,
                             body_first_line,
                             body_last_line,
+                            subtree_end_line: line_end_at(content, h.end().into()),
                         });
                     }
                 }
@@ -844,10 +980,7 @@ impl OrgMode {
             0 => Ok(None),
             1 => Ok(matches.pop().map(Some).unwrap()),
             _ => {
-                let shown = entry
-                    .id
-                    .clone()
-                    .unwrap_or_else(|| entry.heading_path.clone().unwrap_or_default());
+                let shown = target.to_string();
                 Ok(Some(TargetHeadline {
                     line_idx: 0,
                     level: 0,
@@ -864,6 +997,7 @@ impl OrgMode {
                     existing_properties: vec![],
                     body_first_line: 0,
                     body_last_line: 0,
+                    subtree_end_line: 0,
                 }))
             }
         }
@@ -878,6 +1012,7 @@ mod tests {
     fn make_org_mode(temp_dir: &tempfile::TempDir) -> OrgMode {
         OrgMode::new(OrgConfig {
             org_directory: temp_dir.path().to_str().unwrap().to_string(),
+            org_cache_directory: String::new(),
             ..OrgConfig::default()
         })
         .unwrap()
@@ -899,6 +1034,7 @@ mod tests {
             body: None,
             properties: None,
             remove_properties: None,
+            note: None,
         }
     }
 
@@ -1081,6 +1217,7 @@ Body line must survive.
             body: None,
             properties: None,
             remove_properties: None,
+            note: None,
         }
     }
 
@@ -1128,6 +1265,7 @@ Body line must survive.
             body: None,
             properties: None,
             remove_properties: None,
+            note: None,
         };
         let result = org_mode.update_todo(e.clone()).unwrap();
         assert_eq!(result.heading_line, "** TODO Read book");
@@ -1181,6 +1319,7 @@ Body line must survive.
             body: None,
             properties: None,
             remove_properties: None,
+            note: None,
         };
         let result = org_mode.update_todo(e.clone()).unwrap();
         assert_eq!(result.heading_line, "*** TODO [#A] Refactor API :backend:");
@@ -1201,6 +1340,62 @@ Body line must survive.
         assert!(after.starts_with("* Daily Tasks\n:PROPERTIES:\n:ID: daily-tasks-123"));
     }
 
+    #[test]
+    fn test_done_on_repeating_task_advances_schedule() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        setup_fixture(&temp_dir);
+        let org_mode = make_org_mode(&temp_dir);
+
+        let e = UpdateEntry {
+            id: None,
+            file: Some("notes.org".to_string()),
+            heading_path: Some("Projects/Work/Refactor API".to_string()),
+            todo_state: Some("DONE".to_string()),
+            priority: None,
+            tags: None,
+            scheduled: None,
+            deadline: None,
+            closed: None,
+            clear: vec![],
+            title: None,
+            body: None,
+            properties: None,
+            remove_properties: None,
+            note: None,
+        };
+        let result = org_mode.update_todo(e).unwrap();
+
+        assert_eq!(result.heading_line, "*** TODO Refactor API");
+        let content = fs::read_to_string(temp_dir.path().join("notes.org")).unwrap();
+        let section = &content[content.find("*** TODO Refactor API").unwrap()..];
+        let lines: Vec<&str> = section.lines().collect();
+
+        // ++1w keeps the weekday and lands strictly after today.
+        let scheduled = lines[1]
+            .strip_prefix("SCHEDULED: <")
+            .and_then(|s| s.strip_suffix(" Fri ++1w>"))
+            .unwrap_or_else(|| panic!("unexpected planning line: {}", lines[1]));
+        let scheduled = chrono::NaiveDate::parse_from_str(scheduled, "%Y-%m-%d").unwrap();
+        assert!(scheduled > chrono::Local::now().date_naive());
+        assert!(!section.contains("CLOSED:"));
+
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(lines[2], ":PROPERTIES:");
+        assert!(lines[3].starts_with(&format!(":LAST_REPEAT: [{today} ")));
+        assert_eq!(lines[4], ":END:");
+        assert_eq!(lines[5], ":LOGBOOK:");
+        assert!(
+            lines[6].starts_with(&format!(
+                "- State \"DONE\"       from \"TODO\"       [{today} "
+            )),
+            "unexpected state note: {}",
+            lines[6]
+        );
+        assert_eq!(lines[7], ":END:");
+        assert_eq!(lines[8], "Body line must survive.");
+        assert!(result.changes.iter().any(|c| c.starts_with("scheduled: ")));
+    }
+
     #[test]
     fn test_update_target_errors() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1329,6 +1524,7 @@ Body line must survive.
             body: None,
             properties: None,
             remove_properties: None,
+            note: None,
         };
         let result = org_mode.update_todo(e).unwrap();
         assert_eq!(result.heading_line, "*** TODO Refactor API");
@@ -1390,6 +1586,7 @@ Body line must survive.
             body: None,
             properties: None,
             remove_properties: None,
+            note: None,
         };
         let result = org_mode.update_todo(e).unwrap();
         assert_eq!(result.heading_line, "* TODO Tagged task");
@@ -1418,6 +1615,98 @@ Body line must survive.
         );
     }
 
+    fn logging_org_mode(temp_dir: &tempfile::TempDir, into_drawer: bool) -> OrgMode {
+        OrgMode::new(OrgConfig {
+            org_directory: temp_dir.path().to_str().unwrap().to_string(),
+            org_todo_keywords: vec![
+                "TODO(t)".to_string(),
+                "WAIT(w@/!)".to_string(),
+                "|".to_string(),
+                "DONE(d!)".to_string(),
+            ],
+            org_log_into_drawer: into_drawer,
+            org_cache_directory: String::new(),
+            ..OrgConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_state_change_logged_per_keyword_spec() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        setup_fixture(&temp_dir);
+        let org_mode = logging_org_mode(&temp_dir, true);
+
+        let mut e = update_by_id("task-groceries-456");
+        e.todo_state = Some("WAIT".to_string());
+        e.note = Some("Shop closed until Monday".to_string());
+        let result = org_mode.update_todo(e).unwrap();
+        assert!(result.changes.iter().any(|c| c.starts_with("logbook: ")));
+
+        let mut e = update_by_id("task-groceries-456");
+        e.todo_state = Some("DONE".to_string());
+        org_mode.update_todo(e).unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join("notes.org")).unwrap();
+        let section = &content[content.find("** DONE Buy groceries").unwrap()..];
+        let lines: Vec<&str> = section.lines().collect();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert!(lines[1].starts_with(&format!("CLOSED: [{today} ")));
+        assert_eq!(
+            lines[2..5],
+            [":PROPERTIES:", ":ID: task-groceries-456", ":END:"]
+        );
+        assert_eq!(lines[5], ":LOGBOOK:");
+        assert!(lines[6].starts_with(&format!(
+            "- State \"DONE\"       from \"WAIT\"       [{today} "
+        )));
+        assert!(lines[7].starts_with(&format!(
+            "- State \"WAIT\"       from \"TODO\"       [{today} "
+        )));
+        assert!(lines[7].ends_with("] \\\\"));
+        assert_eq!(lines[8], "  Shop closed until Monday");
+        assert_eq!(lines[9], ":END:");
+    }
+
+    #[test]
+    fn test_state_change_logged_under_heading_without_drawer() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        setup_fixture(&temp_dir);
+        let org_mode = logging_org_mode(&temp_dir, false);
+
+        let mut e = update_by_id("task-groceries-456");
+        e.todo_state = Some("DONE".to_string());
+        org_mode.update_todo(e).unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join("notes.org")).unwrap();
+        let section = &content[content.find("** DONE Buy groceries").unwrap()..];
+        let lines: Vec<&str> = section.lines().collect();
+        assert_eq!(lines[4], ":END:");
+        assert!(lines[5].starts_with("- State \"DONE\"       from \"TODO\""));
+        assert_eq!(lines[6], "** DONE Read book");
+        assert!(!content.contains(":LOGBOOK:"));
+    }
+
+    #[test]
+    fn test_unlogged_state_change_and_note_without_change() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        setup_fixture(&temp_dir);
+        let org_mode = logging_org_mode(&temp_dir, true);
+
+        // TODO logs nothing on entry and DONE nothing on exit.
+        let mut e = update_by_id("task-book-789");
+        e.todo_state = Some("TODO".to_string());
+        org_mode.update_todo(e).unwrap();
+        let content = fs::read_to_string(temp_dir.path().join("notes.org")).unwrap();
+        assert!(!content.contains(":LOGBOOK:"));
+
+        let mut e = update_by_id("task-book-789");
+        e.todo_state = Some("TODO".to_string());
+        e.note = Some("Still reading".to_string());
+        let err = org_mode.update_todo(e).unwrap_err();
+        assert!(matches!(err, OrgModeError::InvalidUpdate(msg) if msg.contains("note")));
+    }
+
     #[test]
     fn test_auto_closed_disabled_keeps_stale_closed() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1425,6 +1714,7 @@ Body line must survive.
         let org_mode = OrgMode::new(OrgConfig {
             org_directory: temp_dir.path().to_str().unwrap().to_string(),
             org_auto_closed_timestamp: false,
+            org_cache_directory: String::new(),
             ..OrgConfig::default()
         })
         .unwrap();
@@ -1488,6 +1778,7 @@ Body line must survive.
             body: None,
             properties: None,
             remove_properties: None,
+            note: None,
         };
         org_mode.update_todo(e).unwrap();
         let content = fs::read_to_string(temp_dir.path().join("eof.org")).unwrap();
@@ -1554,6 +1845,7 @@ Body line must survive.
             body: None,
             properties: None,
             remove_properties: None,
+            note: None,
         };
         org_mode.update_todo(e).unwrap();
         let content = fs::read_to_string(temp_dir.path().join("multi.org")).unwrap();
@@ -1704,6 +1996,7 @@ Body line must survive.
             body: Some("New body content.\nSecond line.".to_string()),
             properties: None,
             remove_properties: None,
+            note: None,
         };
         org_mode.update_todo(e.clone()).unwrap();
         let content = fs::read_to_string(temp_dir.path().join("notes.org")).unwrap();
@@ -1777,6 +2070,7 @@ Body line must survive.
                 value: "work".to_string(),
             }]),
             remove_properties: None,
+            note: None,
         };
         org_mode.update_todo(e).unwrap();
         let content = fs::read_to_string(temp_dir.path().join("nodrawer.org")).unwrap();
@@ -1816,6 +2110,7 @@ Body line must survive.
             body: None,
             properties: None,
             remove_properties: Some(vec!["EFFORT".to_string()]),
+            note: None,
         };
         org_mode.update_todo(e).unwrap();
         let content = fs::read_to_string(temp_dir.path().join("props.org")).unwrap();
@@ -1847,6 +2142,7 @@ Body line must survive.
             body: None,
             properties: None,
             remove_properties: Some(vec!["NONEXISTENT".to_string()]),
+            note: None,
         };
         let before = fs::read_to_string(temp_dir.path().join("noop.org")).unwrap();
         org_mode.update_todo(e).unwrap();
@@ -1878,6 +2174,7 @@ Body line must survive.
             body: None,
             properties: None,
             remove_properties: Some(vec!["EFFORT".to_string()]),
+            note: None,
         };
         org_mode.update_todo(e).unwrap();
         let content = fs::read_to_string(temp_dir.path().join("drain.org")).unwrap();
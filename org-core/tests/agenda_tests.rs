@@ -2,7 +2,9 @@ mod common;
 
 use chrono::{Datelike, Days, Local, TimeZone};
 use org_core::org_mode::AgendaViewType;
-use org_core::{OrgConfig, OrgMode, OrgModeError, Priority};
+use org_core::{
+    AgendaOptions, AgendaReason, AgendaSortKey, OrgConfig, OrgMode, OrgModeError, Priority,
+};
 use serial_test::serial;
 use std::convert::TryFrom;
 use test_utils::fixtures;
@@ -453,6 +455,7 @@ fn test_list_tasks_custom_todo_keywords() {
             "DONE".to_string(),
             "CANCELLED".to_string(),
         ],
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
     let org_mode = OrgMode::new(config).expect("Failed to create test OrgMode");
@@ -471,6 +474,7 @@ fn test_list_tasks_empty_agenda_files() {
     let config = OrgConfig {
         org_directory: org_dir.path().to_string_lossy().to_string(),
         org_agenda_files: vec!["empty.org".to_string()],
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
     let org_mode = OrgMode::new(config).expect("Failed to create test OrgMode");
@@ -488,6 +492,7 @@ fn test_list_tasks_glob_patterns() {
     let config = OrgConfig {
         org_directory: org_dir.path().to_string_lossy().to_string(),
         org_agenda_files: vec!["*.org".to_string()],
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
     let org_mode = OrgMode::new(config).expect("Failed to create test OrgMode");
@@ -581,7 +586,7 @@ fn test_list_tasks_with_tag_filter() {
     // Verify all returned tasks have the 'work' tag
     for task in &work_tasks {
         assert!(
-            task.tags.contains(&"work".to_string()),
+            task.has_tag("work"),
             "Task '{}' should have 'work' tag, has {:?}",
             task.heading,
             task.tags
@@ -601,7 +606,7 @@ fn test_list_tasks_with_tag_filter() {
     // Verify all returned tasks have the 'personal' tag
     for task in &personal_tasks {
         assert!(
-            task.tags.contains(&"personal".to_string()),
+            task.has_tag("personal"),
             "Task '{}' should have 'personal' tag, has {:?}",
             task.heading,
             task.tags
@@ -620,7 +625,7 @@ fn test_list_tasks_with_tag_filter() {
     );
     for task in &urgent_tasks {
         assert!(
-            task.tags.contains(&"urgent".to_string()),
+            task.has_tag("urgent"),
             "Task '{}' should have 'urgent' tag, has {:?}",
             task.heading,
             task.tags
@@ -744,7 +749,7 @@ fn test_list_tasks_combined_filters() {
     );
     for task in &todo_work_tasks {
         assert_eq!(task.todo_state.as_deref(), Some("TODO"));
-        assert!(task.tags.contains(&"work".to_string()));
+        assert!(task.has_tag("work"));
     }
 
     // Combine all three filters: TODO + work + urgent
@@ -765,8 +770,8 @@ fn test_list_tasks_combined_filters() {
     );
     for task in &todo_work_urgent {
         assert_eq!(task.todo_state.as_deref(), Some("TODO"));
-        assert!(task.tags.contains(&"work".to_string()));
-        assert!(task.tags.contains(&"urgent".to_string()));
+        assert!(task.has_tag("work"));
+        assert!(task.has_tag("urgent"));
     }
 }
 
@@ -791,8 +796,8 @@ fn test_list_tasks_multiple_tags() {
         review_urgent_tasks.len()
     );
     for task in &review_urgent_tasks {
-        assert!(task.tags.contains(&"review".to_string()));
-        assert!(task.tags.contains(&"urgent".to_string()));
+        assert!(task.has_tag("review"));
+        assert!(task.has_tag("urgent"));
     }
 
     // Filtering by ["work", "review"] should find tasks with both tags
@@ -811,11 +816,170 @@ fn test_list_tasks_multiple_tags() {
         work_review_tasks.len()
     );
     for task in &work_review_tasks {
-        assert!(task.tags.contains(&"work".to_string()));
-        assert!(task.tags.contains(&"review".to_string()));
+        assert!(task.has_tag("work"));
+        assert!(task.has_tag("review"));
     }
 }
 
+#[test]
+fn test_list_tasks_matches_inherited_tags() {
+    let (org_mode, _temp_dir) = create_test_org_mode_with_agenda_files();
+
+    // "Call dentist" is tagged :work: under the :personal: heading
+    let tasks = org_mode
+        .list_tasks(
+            None,
+            Some(&["personal".to_string(), "work".to_string()]),
+            None,
+            None,
+        )
+        .expect("Failed to list tasks with personal+work tags");
+
+    assert_eq!(tasks.len(), 1, "Got {tasks:?}");
+    assert_eq!(tasks[0].heading, "Call dentist");
+    assert_eq!(tasks[0].tags, vec!["work".to_string()]);
+    assert_eq!(tasks[0].inherited_tags, vec!["personal".to_string()]);
+
+    // Grandchildren inherit through untagged parents
+    let project_tasks = org_mode
+        .list_tasks(None, Some(&["project".to_string()]), None, None)
+        .expect("Failed to list tasks with project tag");
+    let define_tables = project_tasks
+        .iter()
+        .find(|task| task.heading == "Define user tables")
+        .expect("Should find 'Define user tables' through inheritance");
+    assert!(define_tables.tags.is_empty());
+    assert_eq!(
+        define_tables.inherited_tags,
+        vec!["project".to_string(), "work".to_string()]
+    );
+}
+
+#[test]
+fn test_list_tasks_with_filetags_and_excluded_tags() {
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        temp_dir.path().join("tagged.org"),
+        "#+FILETAGS: :home:\n\n* Chores :project:\n** TODO Fix the sink :plumbing:\n",
+    )
+    .expect("Failed to write org file");
+
+    let config = OrgConfig {
+        org_directory: temp_dir.path().to_string_lossy().to_string(),
+        org_tags_exclude_from_inheritance: vec!["project".to_string()],
+        org_cache_directory: String::new(),
+        ..OrgConfig::default()
+    };
+    let org_mode = OrgMode::new(config).expect("Failed to create OrgMode");
+
+    let tasks = org_mode
+        .list_tasks(None, Some(&["home".to_string()]), None, None)
+        .expect("Failed to list tasks with home tag");
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].inherited_tags, vec!["home".to_string()]);
+    assert!(tasks[0].has_tag("plumbing"));
+    assert!(!tasks[0].has_tag("project"));
+
+    let project_tasks = org_mode
+        .list_tasks(None, Some(&["project".to_string()]), None, None)
+        .expect("Failed to list tasks with project tag");
+    assert!(project_tasks.is_empty());
+}
+
+#[test]
+fn test_list_tasks_category_and_inherited_properties() {
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        temp_dir.path().join("work.org"),
+        "#+CATEGORY: Job\n\
+         * Infra\n:PROPERTIES:\n:CATEGORY: infra\n:OWNER: ana\n:END:\n\
+         ** TODO Rotate keys\n:PROPERTIES:\n:EFFORT: 1:00\n:END:\n\
+         * TODO Expense report\n",
+    )
+    .expect("Failed to write org file");
+    std::fs::write(temp_dir.path().join("home.org"), "* TODO Water plants\n")
+        .expect("Failed to write org file");
+
+    let config = OrgConfig {
+        org_directory: temp_dir.path().to_string_lossy().to_string(),
+        org_use_property_inheritance: vec!["OWNER".to_string()],
+        org_cache_directory: String::new(),
+        ..OrgConfig::default()
+    };
+    let org_mode = OrgMode::new(config).expect("Failed to create OrgMode");
+
+    let tasks = org_mode
+        .list_tasks(None, None, None, None)
+        .expect("Failed to list tasks");
+    let task = |heading: &str| {
+        tasks
+            .iter()
+            .find(|task| task.heading == heading)
+            .unwrap_or_else(|| panic!("Should find '{heading}'"))
+    };
+
+    let rotate = task("Rotate keys");
+    assert_eq!(rotate.category, "infra");
+    assert_eq!(
+        rotate.properties.get("EFFORT").map(String::as_str),
+        Some("1:00")
+    );
+    assert_eq!(
+        rotate.properties.get("OWNER").map(String::as_str),
+        Some("ana")
+    );
+    assert!(!rotate.properties.contains_key("CATEGORY"));
+
+    let expense = task("Expense report");
+    assert_eq!(expense.category, "Job");
+    assert!(expense.properties.is_empty());
+
+    assert_eq!(task("Water plants").category, "home");
+}
+
+#[test]
+fn test_list_tasks_matching_emacs_match_string() {
+    let (org_mode, _temp_dir) = create_test_org_mode_with_agenda_files();
+    let list = |match_string: &str| {
+        org_mode.list_tasks_with_options(&AgendaOptions {
+            match_string: Some(match_string.to_string()),
+            ..AgendaOptions::default()
+        })
+    };
+
+    let tasks = list("+work-urgent+review/!TODO").expect("Failed to list tasks with match string");
+    let headings: Vec<&str> = tasks.iter().map(|t| t.heading.as_str()).collect();
+    // Tagged headings come without the padding before their tags.
+    assert!(
+        headings.iter().all(|h| *h == h.trim_end()),
+        "Got {headings:?}"
+    );
+    assert!(
+        headings.contains(&"Review documentation"),
+        "Got {headings:?}"
+    );
+    assert!(
+        !headings.contains(&"Code review session"),
+        "Got {headings:?}"
+    );
+
+    let tasks =
+        list("work+PRIORITY=\"A\"|personal").expect("Failed to list tasks with match string");
+    let headings: Vec<&str> = tasks.iter().map(|t| t.heading.as_str()).collect();
+    assert!(
+        headings.contains(&"Code review session"),
+        "Got {headings:?}"
+    );
+    assert!(headings.contains(&"Buy groceries"), "Got {headings:?}");
+    assert!(
+        !headings.contains(&"Review documentation"),
+        "Got {headings:?}"
+    );
+
+    let err = list("EFFORT>").unwrap_err();
+    assert!(matches!(err, OrgModeError::InvalidQuery(_)));
+}
+
 #[test]
 fn test_list_tasks_no_match_filters() {
     let (org_mode, _temp_dir) = create_test_org_mode_with_agenda_files();
@@ -843,20 +1007,20 @@ fn test_list_tasks_no_match_filters() {
         done_a_tasks.len()
     );
 
-    // Filter by impossible combination (personal + work tags together on same task)
-    let personal_work_tasks = org_mode
+    // Filter by impossible combination (personal + project tags, own or inherited)
+    let personal_project_tasks = org_mode
         .list_tasks(
             None,
-            Some(&["personal".to_string(), "work".to_string()]),
+            Some(&["personal".to_string(), "project".to_string()]),
             None,
             None,
         )
-        .expect("Failed to list tasks with personal+work tags");
+        .expect("Failed to list tasks with personal+project tags");
 
     assert!(
-        personal_work_tasks.is_empty(),
-        "Should have no tasks with both personal and work tags, got {}",
-        personal_work_tasks.len()
+        personal_project_tasks.is_empty(),
+        "Should have no tasks with both personal and project tags, got {}",
+        personal_project_tasks.len()
     );
 }
 
@@ -1041,7 +1205,7 @@ fn test_agenda_today_finds_scheduled_tasks() {
 
     if let Some(task) = review_task {
         assert_eq!(task.todo_state, Some("TODO".to_string()));
-        assert!(task.tags.contains(&"work".to_string()));
+        assert!(task.has_tag("work"));
         assert!(task.scheduled.is_some());
     }
 }
@@ -1049,6 +1213,12 @@ fn test_agenda_today_finds_scheduled_tasks() {
 #[test]
 fn test_agenda_today_excludes_future_tasks() {
     let (org_mode, _temp_dir) = create_test_org_mode_with_agenda_files();
+    // Without deadline warnings, upcoming deadlines stay out of today's view.
+    let org_mode = OrgMode::new(OrgConfig {
+        org_deadline_warning_days: 0,
+        ..org_mode.config().clone()
+    })
+    .unwrap();
     let view = org_mode
         .get_agenda_view(AgendaViewType::Today, None, None, None)
         .expect("Failed to get today's agenda");
@@ -1065,6 +1235,52 @@ fn test_agenda_today_excludes_future_tasks() {
     );
 }
 
+#[test]
+fn test_agenda_today_warns_of_upcoming_deadlines() {
+    let (org_mode, temp_dir) = create_test_org_mode_with_agenda_files();
+    let view = org_mode
+        .get_agenda_view(AgendaViewType::Today, None, None, None)
+        .expect("Failed to get today's agenda");
+
+    // "Buy groceries" is due in two days, within the default 14-day warning.
+    let groceries = view
+        .items
+        .iter()
+        .find(|item| item.heading.contains("Buy groceries"))
+        .expect("Today's agenda should warn of the upcoming deadline");
+    assert_eq!(groceries.days_until_deadline, Some(2));
+    assert_eq!(groceries.days_overdue, None);
+
+    let overdue = view
+        .items
+        .iter()
+        .find(|item| item.heading.contains("Overdue deadline-only task"))
+        .expect("Should find the deadline-only overdue task");
+    assert_eq!(overdue.days_until_deadline, Some(-14));
+
+    // A timestamp's own warning period overrides the default.
+    let in_two_days = Local::now()
+        .checked_add_days(Days::new(2))
+        .unwrap()
+        .format("%Y-%m-%d %a");
+    std::fs::write(
+        temp_dir.path().join("agenda.org"),
+        format!(
+            "* TODO Short notice\n  DEADLINE: <{in_two_days} -1d>\n\
+             * TODO Long notice\n  DEADLINE: <{in_two_days} -2d>\n\
+             * DONE Finished\n  DEADLINE: <{in_two_days}>\n"
+        ),
+    )
+    .unwrap();
+    let view = org_mode
+        .get_agenda_view(AgendaViewType::Today, None, None, None)
+        .expect("Failed to get today's agenda");
+    let headings: Vec<&str> = view.items.iter().map(|i| i.heading.as_str()).collect();
+    assert!(headings.contains(&"Long notice"), "Got {headings:?}");
+    assert!(!headings.contains(&"Short notice"), "Got {headings:?}");
+    assert!(!headings.contains(&"Finished"), "Got {headings:?}");
+}
+
 #[test]
 fn test_agenda_week_includes_all_week_tasks() {
     let (org_mode, _temp_dir) = create_test_org_mode_with_agenda_files();
@@ -1210,3 +1426,345 @@ fn test_agenda_today_deadline_only_overdue_has_days_overdue() {
         overdue.days_overdue
     );
 }
+
+#[test]
+fn test_agenda_view_groups_entries_by_day() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        temp_dir.path().join("week.org"),
+        "* TODO Weekly sync\n  SCHEDULED: <2030-01-07 Mon 10:00 +1w>\n\
+         * TODO Ship release\n  DEADLINE: <2030-01-09 Wed>\n\
+         * TODO Lunch with Ana\n  <2030-01-09 Wed 12:30>\n\
+         * TODO Early call\n  SCHEDULED: <2030-01-09 Wed 08:00>\n",
+    )
+    .unwrap();
+    let org_mode = OrgMode::new(OrgConfig {
+        org_directory: temp_dir.path().to_string_lossy().to_string(),
+        org_cache_directory: String::new(),
+        ..OrgConfig::default()
+    })
+    .unwrap();
+
+    let from = Local.with_ymd_and_hms(2030, 1, 7, 0, 0, 0).unwrap();
+    let to = Local.with_ymd_and_hms(2030, 1, 20, 23, 59, 59).unwrap();
+    let view = org_mode
+        .get_agenda_view(AgendaViewType::Custom { from, to }, None, None, None)
+        .expect("Failed to get agenda view");
+
+    assert_eq!(view.items.len(), 4);
+    assert_eq!(view.days.len(), 14);
+    assert_eq!(view.days[0].date, "2030-01-07");
+    assert!(view.days[1].entries.is_empty());
+
+    let entries = |date: &str| {
+        let day = view.days.iter().find(|d| d.date == date).unwrap();
+        day.entries
+            .iter()
+            .map(|e| {
+                (
+                    view.items[e.item].heading.as_str(),
+                    e.reason,
+                    e.date.as_str(),
+                    e.time.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        entries("2030-01-07"),
+        [(
+            "Weekly sync",
+            AgendaReason::Scheduled,
+            "2030-01-07",
+            Some("10:00")
+        )]
+    );
+    // Timed entries come first, in time order.
+    assert_eq!(
+        entries("2030-01-09"),
+        [
+            (
+                "Early call",
+                AgendaReason::Scheduled,
+                "2030-01-09",
+                Some("08:00")
+            ),
+            (
+                "Lunch with Ana",
+                AgendaReason::Timestamp,
+                "2030-01-09",
+                Some("12:30")
+            ),
+            ("Ship release", AgendaReason::Deadline, "2030-01-09", None),
+        ]
+    );
+    assert_eq!(
+        entries("2030-01-14"),
+        [(
+            "Weekly sync",
+            AgendaReason::Repeat,
+            "2030-01-14",
+            Some("10:00")
+        )]
+    );
+}
+
+#[test]
+fn test_agenda_today_entries_carry_overdue_and_upcoming() {
+    let (org_mode, _temp_dir) = create_test_org_mode_with_agenda_files();
+    let view = org_mode
+        .get_agenda_view(AgendaViewType::Today, None, None, None)
+        .expect("Failed to get today's agenda");
+
+    assert_eq!(view.days.len(), 1);
+    let entry = |heading: &str| {
+        view.days[0]
+            .entries
+            .iter()
+            .find(|e| view.items[e.item].heading.contains(heading))
+            .unwrap_or_else(|| panic!("No entry for {heading}"))
+    };
+    let day = |offset: i64| {
+        (Local::now().date_naive() + chrono::Duration::days(offset))
+            .format("%Y-%m-%d")
+            .to_string()
+    };
+
+    let overdue = entry("Overdue task from three weeks ago");
+    assert_eq!(overdue.reason, AgendaReason::Overdue);
+    assert_eq!(overdue.date, day(-21));
+
+    let groceries = entry("Buy groceries");
+    assert_eq!(groceries.reason, AgendaReason::Upcoming);
+    assert_eq!(groceries.date, day(2));
+
+    assert_eq!(
+        entry("Review pull requests").reason,
+        AgendaReason::Scheduled
+    );
+}
+
+#[test]
+fn test_agenda_sorting_strategy_and_override() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        temp_dir.path().join("a.org"),
+        "* TODO Book flights\n  SCHEDULED: <2030-01-08 Tue>\n  :PROPERTIES:\n  :EFFORT: 1:00\n  :END:\n\
+         * TODO Call plumber\n  SCHEDULED: <2030-01-08 Tue 09:00>\n",
+    )
+    .unwrap();
+    std::fs::write(
+        temp_dir.path().join("b.org"),
+        "* TODO [#C] Tidy desk\n  SCHEDULED: <2030-01-07 Mon>\n  :PROPERTIES:\n  :EFFORT: 0:15\n  :END:\n\
+         * TODO [#A] Pay rent\n  DEADLINE: <2030-01-08 Tue>\n",
+    )
+    .unwrap();
+    let org_mode = OrgMode::new(OrgConfig {
+        org_directory: temp_dir.path().to_string_lossy().to_string(),
+        org_agenda_sorting_strategy: vec![AgendaSortKey::PriorityDown, AgendaSortKey::AlphaUp],
+        org_cache_directory: String::new(),
+        ..OrgConfig::default()
+    })
+    .unwrap();
+    let headings = |items: &[org_core::AgendaItem]| {
+        items
+            .iter()
+            .map(|item| item.heading.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let tasks = org_mode.list_tasks(None, None, None, None).unwrap();
+    assert_eq!(
+        headings(&tasks),
+        ["Pay rent", "Book flights", "Call plumber", "Tidy desk"]
+    );
+
+    // The limit applies after sorting.
+    let tasks = org_mode
+        .list_tasks_with_options(&AgendaOptions {
+            sort: Some(vec![AgendaSortKey::EffortUp]),
+            limit: Some(2),
+            ..AgendaOptions::default()
+        })
+        .unwrap();
+    assert_eq!(headings(&tasks), ["Tidy desk", "Book flights"]);
+
+    let from = Local.with_ymd_and_hms(2030, 1, 7, 0, 0, 0).unwrap();
+    let to = Local.with_ymd_and_hms(2030, 1, 8, 23, 59, 59).unwrap();
+    let view = org_mode
+        .get_agenda_view_with_options(
+            AgendaViewType::Custom { from, to },
+            &AgendaOptions {
+                sort: Some(vec![AgendaSortKey::TimeUp, AgendaSortKey::AlphaDown]),
+                ..AgendaOptions::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        headings(&view.items),
+        ["Tidy desk", "Call plumber", "Pay rent", "Book flights"]
+    );
+    let day = view
+        .days
+        .iter()
+        .map(|day| {
+            day.entries
+                .iter()
+                .map(|e| view.items[e.item].heading.as_str())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        day,
+        [
+            vec!["Tidy desk"],
+            vec!["Call plumber", "Pay rent", "Book flights"]
+        ]
+    );
+}
+
+#[test]
+fn test_agenda_view_expands_time_and_date_ranges() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        temp_dir.path().join("events.org"),
+        "* TODO Conference\n  <2030-01-06 Sun>--<2030-01-09 Wed>\n\
+         * TODO Planning meeting\n  SCHEDULED: <2030-01-08 Tue 10:00-11:30>\n",
+    )
+    .unwrap();
+    let org_mode = OrgMode::new(OrgConfig {
+        org_directory: temp_dir.path().to_string_lossy().to_string(),
+        org_cache_directory: String::new(),
+        ..OrgConfig::default()
+    })
+    .unwrap();
+
+    let from = Local.with_ymd_and_hms(2030, 1, 7, 0, 0, 0).unwrap();
+    let to = Local.with_ymd_and_hms(2030, 1, 13, 23, 59, 59).unwrap();
+    let view = org_mode
+        .get_agenda_view(AgendaViewType::Custom { from, to }, None, None, None)
+        .expect("Failed to get agenda view");
+
+    let conference = view
+        .items
+        .iter()
+        .find(|item| item.heading == "Conference")
+        .expect("Conference started before the range but still covers it");
+    assert_eq!(conference.start.as_deref(), Some("2030-01-06"));
+    assert_eq!(conference.end.as_deref(), Some("2030-01-09"));
+    assert_eq!(conference.duration_minutes, Some(4 * 24 * 60));
+
+    let meeting = view
+        .items
+        .iter()
+        .find(|item| item.heading == "Planning meeting")
+        .unwrap();
+    assert_eq!(meeting.start.as_deref(), Some("2030-01-08 10:00"));
+    assert_eq!(meeting.end.as_deref(), Some("2030-01-08 11:30"));
+    assert_eq!(meeting.duration_minutes, Some(90));
+
+    let listed: Vec<(&str, Vec<&str>)> = view
+        .days
+        .iter()
+        .filter(|day| !day.entries.is_empty())
+        .map(|day| {
+            (
+                day.date.as_str(),
+                day.entries
+                    .iter()
+                    .map(|e| view.items[e.item].heading.as_str())
+                    .collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        listed,
+        [
+            ("2030-01-07", vec!["Conference"]),
+            ("2030-01-08", vec!["Planning meeting", "Conference"]),
+            ("2030-01-09", vec!["Conference"]),
+        ]
+    );
+    // Every day of the range points back at its first day.
+    assert!(
+        view.days
+            .iter()
+            .flat_map(|day| &day.entries)
+            .filter(|e| view.items[e.item].heading == "Conference")
+            .all(|e| e.date == "2030-01-06" && e.time.is_none())
+    );
+}
+
+#[test]
+fn test_agenda_view_lists_diary_sexps_and_calendar_entries() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        temp_dir.path().join("calendar.org"),
+        "* Mom's birthday\n  <%%(diary-anniversary 5 14 1990)>\n\
+         * Book club\n  <%%(diary-float t 4 2) 18:00>\n\
+         * Lunch with Ana\n  <2030-05-20 Mon 12:30>\n\
+         * Reading notes\n  Nothing scheduled here.\n\
+         * TODO Renew passport\n  SCHEDULED: <2030-05-09 Thu>\n",
+    )
+    .unwrap();
+    let org_mode = OrgMode::new(OrgConfig {
+        org_directory: temp_dir.path().to_string_lossy().to_string(),
+        org_cache_directory: String::new(),
+        ..OrgConfig::default()
+    })
+    .unwrap();
+
+    let from = Local.with_ymd_and_hms(2030, 5, 1, 0, 0, 0).unwrap();
+    let to = Local.with_ymd_and_hms(2030, 5, 31, 23, 59, 59).unwrap();
+    let view = org_mode
+        .get_agenda_view(AgendaViewType::Custom { from, to }, None, None, None)
+        .expect("Failed to get agenda view");
+
+    let listed: Vec<(&str, &str, AgendaReason, Option<&str>)> = view
+        .days
+        .iter()
+        .flat_map(|day| {
+            day.entries.iter().map(|e| {
+                (
+                    day.date.as_str(),
+                    view.items[e.item].heading.as_str(),
+                    e.reason,
+                    e.time.as_deref(),
+                )
+            })
+        })
+        .collect();
+    assert_eq!(
+        listed,
+        [
+            (
+                "2030-05-09",
+                "Book club",
+                AgendaReason::Diary,
+                Some("18:00")
+            ),
+            (
+                "2030-05-09",
+                "Renew passport",
+                AgendaReason::Scheduled,
+                None
+            ),
+            ("2030-05-14", "Mom's birthday", AgendaReason::Diary, None),
+            (
+                "2030-05-20",
+                "Lunch with Ana",
+                AgendaReason::Timestamp,
+                Some("12:30")
+            ),
+        ]
+    );
+    assert!(!view.items.iter().any(|i| i.heading == "Reading notes"));
+
+    // Asking for TODO states leaves the calendar entries out.
+    let todo = ["TODO".to_string()];
+    let view = org_mode
+        .get_agenda_view(AgendaViewType::Custom { from, to }, Some(&todo), None, None)
+        .unwrap();
+    let headings: Vec<&str> = view.items.iter().map(|i| i.heading.as_str()).collect();
+    assert_eq!(headings, ["Renew passport"]);
+}
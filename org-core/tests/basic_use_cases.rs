@@ -1,7 +1,7 @@
 mod common;
 
 use org_core::org_mode::TreeNode;
-use org_core::{OrgConfig, OrgMode, OrgModeError};
+use org_core::{OrgConfig, OrgMode, OrgModeError, SearchElement, SearchMode, SearchOptions};
 use test_utils::fixtures;
 
 use crate::common::create_test_org_mode;
@@ -490,6 +490,353 @@ fn test_search_all_terms_must_match() {
     }
 }
 
+#[test]
+fn test_search_matching_restricts_to_matching_entries() {
+    let org_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        org_dir.path().join("bills.org"),
+        "* Work :work:\nInvoice for ACME\n** Home :home:\nInvoice for the plumber\n",
+    )
+    .unwrap();
+    let org_mode = create_test_org_mode(org_dir.path());
+
+    let search = |match_string: &str| {
+        let options = SearchOptions {
+            match_string: Some(match_string.to_string()),
+            ..SearchOptions::default()
+        };
+        org_mode.search_with_options("invoice", &options, None, None)
+    };
+
+    // Home inherits :work:, so it has to be excluded explicitly
+    let results = search("work-home").expect("Search failed");
+    assert_eq!(results.len(), 1, "Got {results:?}");
+    assert!(results[0].snippet.contains("ACME"));
+
+    let results = search("home").expect("Search failed");
+    assert_eq!(results.len(), 1, "Got {results:?}");
+    assert!(results[0].snippet.contains("plumber"));
+
+    let err = search("+work|").unwrap_err();
+    assert!(matches!(err, OrgModeError::InvalidQuery(_)));
+}
+
+#[test]
+fn test_search_results_locate_enclosing_heading() {
+    let org_dir = tempfile::TempDir::new().unwrap();
+    let content = "#+FILETAGS: :bills:\nInvoice archive\n* Work :work:\nInvoice for ACME\n\
+                   ** Home :home:\n:PROPERTIES:\n:ID: home-1\n:END:\nInvoice for the plumber\n";
+    std::fs::write(org_dir.path().join("bills.org"), content).unwrap();
+    let org_mode = create_test_org_mode(org_dir.path());
+
+    let results = org_mode
+        .search("invoice", None, None)
+        .expect("Search failed");
+    let hit = |text: &str| {
+        results
+            .iter()
+            .find(|r| r.snippet.contains(text))
+            .unwrap_or_else(|| panic!("No hit for {text} in {results:?}"))
+    };
+
+    let preamble = hit("archive");
+    assert_eq!(preamble.line, 2);
+    assert_eq!(preamble.heading_path, None);
+    assert_eq!(preamble.level, 0);
+    assert!(preamble.position.is_none());
+    assert!(preamble.tags.is_empty());
+
+    let work = hit("ACME");
+    assert_eq!(work.line, 4);
+    assert_eq!(work.heading_path.as_deref(), Some("Work"));
+    assert_eq!(work.level, 1);
+    assert_eq!(work.tags, vec!["work".to_string()]);
+    assert_eq!(work.id, None);
+    let position = work.position.as_ref().expect("Heading position");
+    assert_eq!(
+        &content[position.start as usize..position.end as usize],
+        &content[content.find("* Work").unwrap()..]
+    );
+
+    let home = hit("plumber");
+    assert_eq!(home.line, 9);
+    assert_eq!(home.heading_path.as_deref(), Some("Work/Home"));
+    assert_eq!(home.level, 2);
+    assert_eq!(home.id.as_deref(), Some("home-1"));
+    assert_eq!(home.tags, vec!["home".to_string()]);
+    assert_eq!(home.inherited_tags, vec!["bills", "work"]);
+}
+
+#[test]
+fn test_search_with_tags_matches_inherited_tags() {
+    let org_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        org_dir.path().join("bills.org"),
+        "#+FILETAGS: :bills:\n* Water\nInvoice for water\n",
+    )
+    .unwrap();
+    std::fs::write(
+        org_dir.path().join("projects.org"),
+        "* Project :work:\n** Sub\nInvoice for ACME\n* Garden\nInvoice for seeds\n",
+    )
+    .unwrap();
+    let org_mode = create_test_org_mode(org_dir.path());
+    let snippets = |tag: &str| {
+        let mut found: Vec<String> = org_mode
+            .search_with_tags("invoice", Some(&[tag.to_string()]), None, None)
+            .expect("Search with tags failed")
+            .into_iter()
+            .map(|r| r.snippet)
+            .collect();
+        found.sort();
+        found
+    };
+
+    assert_eq!(snippets("bills"), ["Invoice for water"]);
+    assert_eq!(snippets("work"), ["Invoice for ACME"]);
+}
+
+#[test]
+fn test_search_with_options_modes() {
+    let org_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        org_dir.path().join("bills.org"),
+        "* Work :work:\nInvoice INV-42 for ACME\nPaid in full\n* Home :home:\nInvoice for the plumber\n",
+    )
+    .unwrap();
+    let org_mode = create_test_org_mode(org_dir.path());
+    let search = |query: &str, mode: SearchMode, case_sensitive: bool| {
+        let options = SearchOptions {
+            mode,
+            case_sensitive,
+            ..SearchOptions::default()
+        };
+        org_mode.search_with_options(query, &options, None, None)
+    };
+
+    let results = search("INV-", SearchMode::Exact, true).expect("Search failed");
+    assert_eq!(results.len(), 1, "Got {results:?}");
+    assert_eq!(results[0].line, 2);
+
+    let results = search(r"inv-\d+", SearchMode::Regex, false).expect("Search failed");
+    assert_eq!(results.len(), 1, "Got {results:?}");
+
+    // `paid` is on another line of the Work entry, which rules it out.
+    let results = search("+invoice -paid", SearchMode::Words, false).expect("Search failed");
+    assert_eq!(results.len(), 1, "Got {results:?}");
+    assert!(results[0].snippet.contains("plumber"));
+
+    let err = search("inv(", SearchMode::Regex, false).unwrap_err();
+    assert!(matches!(err, OrgModeError::InvalidQuery(_)));
+}
+
+#[test]
+fn test_search_with_options_ranked() {
+    let org_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        org_dir.path().join("db.org"),
+        "* Database migration :db:\nWe migrated to Postgres after the review.\n\
+         * Standup\nMigrations were mentioned briefly, among lunch plans and other news.\n\
+         * Lunch\nPizza on Friday\n",
+    )
+    .unwrap();
+    std::fs::write(
+        org_dir.path().join("other.org"),
+        "* Reading list\nDatabases\n",
+    )
+    .unwrap();
+    let org_mode = create_test_org_mode(org_dir.path());
+    let options = SearchOptions {
+        mode: SearchMode::Ranked,
+        ..SearchOptions::default()
+    };
+
+    let results = org_mode
+        .search_with_options(
+            "what did we decide about DB migrations",
+            &options,
+            None,
+            None,
+        )
+        .expect("Search failed");
+    let paths: Vec<_> = results.iter().map(|r| r.heading_path.as_deref()).collect();
+    assert_eq!(paths, [Some("Database migration"), Some("Standup")]);
+    assert_eq!(results[0].line, 1);
+    assert_eq!(results[0].tags, ["db"]);
+    assert_eq!(
+        results[0].snippet,
+        "* Database migration :db:\nWe migrated to Postgres after the review."
+    );
+    assert!(results[0].score > results[1].score);
+
+    let results = org_mode
+        .search_with_options("migration", &options, None, Some(10))
+        .expect("Search failed");
+    assert_eq!(results[0].snippet, "* Database...");
+
+    let scoped = SearchOptions {
+        heading_path: Some("Standup".to_string()),
+        ..options.clone()
+    };
+    let results = org_mode
+        .search_with_options("migration", &scoped, None, None)
+        .expect("Search failed");
+    assert_eq!(results.len(), 1, "Got {results:?}");
+    assert_eq!(results[0].line, 3);
+
+    let with_elements = SearchOptions {
+        elements: vec![SearchElement::Body],
+        ..options
+    };
+    let err = org_mode
+        .search_with_options("migration", &with_elements, None, None)
+        .unwrap_err();
+    assert!(matches!(err, OrgModeError::InvalidQuery(_)));
+}
+
+#[test]
+fn test_search_with_options_scopes() {
+    let org_dir = tempfile::TempDir::new().unwrap();
+    let extra_dir = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir(org_dir.path().join("projects")).unwrap();
+    std::fs::write(
+        org_dir.path().join("projects/web.org"),
+        "* Website\n:PROPERTIES:\n:DEPLOY: deploy via rsync\n:END:\nDeploy on Fridays\n\
+         #+begin_src sh\nmake deploy\n#+end_src\n| deploy | weekly |\n\
+         ** Deploy checklist\n* Blog\nDeploy the blog\n",
+    )
+    .unwrap();
+    std::fs::write(org_dir.path().join("notes.org"), "* Ideas\nDeploy ideas\n").unwrap();
+    std::fs::write(
+        org_dir.path().join("notes.org_archive"),
+        "* Old\nDeploy archived\n",
+    )
+    .unwrap();
+    std::fs::write(
+        extra_dir.path().join("extra.org"),
+        "* Elsewhere\nDeploy outside\n",
+    )
+    .unwrap();
+
+    let config = OrgConfig {
+        org_directory: org_dir.path().to_str().unwrap().to_string(),
+        org_agenda_files: vec!["notes.org".to_string()],
+        org_agenda_text_search_extra_files: vec![
+            "agenda-archives".to_string(),
+            extra_dir
+                .path()
+                .join("extra.org")
+                .to_str()
+                .unwrap()
+                .to_string(),
+        ],
+        org_cache_directory: String::new(),
+        ..OrgConfig::default()
+    };
+    let org_mode = OrgMode::new(config).unwrap();
+    let search = |options: SearchOptions| {
+        let options = SearchOptions {
+            mode: SearchMode::Exact,
+            ..options
+        };
+        org_mode.search_with_options("deploy", &options, None, None)
+    };
+    let lines = |options: SearchOptions| {
+        let mut found: Vec<(String, usize)> = search(options)
+            .expect("Search failed")
+            .into_iter()
+            .map(|r| (r.file_path, r.line))
+            .collect();
+        found.sort();
+        found
+    };
+
+    // Extra files are searched next to the org directory.
+    let files: Vec<String> = lines(SearchOptions::default())
+        .into_iter()
+        .map(|(file, _)| file)
+        .collect();
+    assert!(
+        files.contains(&"notes.org_archive".to_string()),
+        "{files:?}"
+    );
+    assert!(files.iter().any(|f| f.ends_with("extra.org")), "{files:?}");
+
+    let web = |line: usize| ("projects/web.org".to_string(), line);
+    let only_web = SearchOptions {
+        files: Some("projects/*.org".to_string()),
+        ..SearchOptions::default()
+    };
+    assert_eq!(
+        lines(only_web.clone()),
+        [web(3), web(5), web(7), web(9), web(10), web(12)]
+    );
+
+    let subtree = SearchOptions {
+        heading_path: Some("Website".to_string()),
+        ..only_web.clone()
+    };
+    assert_eq!(lines(subtree), [web(3), web(5), web(7), web(9), web(10)]);
+
+    let headlines = SearchOptions {
+        elements: vec![SearchElement::Headline],
+        ..only_web.clone()
+    };
+    assert_eq!(lines(headlines), [web(10)]);
+
+    let code_and_tables = SearchOptions {
+        elements: vec![SearchElement::SrcBlock, SearchElement::Table],
+        ..only_web.clone()
+    };
+    assert_eq!(lines(code_and_tables), [web(7), web(9)]);
+
+    let properties = SearchOptions {
+        elements: vec![SearchElement::Property],
+        ..only_web.clone()
+    };
+    assert_eq!(lines(properties), [web(3)]);
+
+    let missing = SearchOptions {
+        heading_path: Some("Website/Nope".to_string()),
+        ..only_web
+    };
+    assert!(matches!(
+        search(missing),
+        Err(OrgModeError::InvalidHeadingPath(_))
+    ));
+}
+
+#[test]
+fn test_search_files_stay_inside_org_directory() {
+    let org_dir = tempfile::tempdir().unwrap();
+    std::fs::write(org_dir.path().join("notes.org"), "* Notes\nsecret plan\n").unwrap();
+    std::fs::write(org_dir.path().join("notes.txt"), "secret plan\n").unwrap();
+    let org_mode = create_test_org_mode(org_dir.path());
+    let search = |files: &str| {
+        let options = SearchOptions {
+            mode: SearchMode::Regex,
+            files: Some(files.to_string()),
+            ..SearchOptions::default()
+        };
+        org_mode.search_with_options(".", &options, None, None)
+    };
+
+    for files in ["/etc/*", "../x.org", "notes/../../x.org", "~/notes.org"] {
+        assert!(
+            matches!(search(files), Err(OrgModeError::InvalidQuery(_))),
+            "{files} should be rejected"
+        );
+    }
+
+    let files: Vec<String> = search("*")
+        .expect("Search failed")
+        .into_iter()
+        .map(|r| r.file_path)
+        .collect();
+    assert!(!files.is_empty());
+    assert!(files.iter().all(|f| f == "notes.org"), "{files:?}");
+}
+
 #[test]
 fn test_search_snippet_max_size_default() {
     let org_dir = fixtures::setup_test_org_files().unwrap();
@@ -634,7 +981,8 @@ fn test_search_with_tags_single_tag() {
 
     for result in &results {
         assert!(
-            result.tags.contains(&"work".to_string()),
+            result.tags.contains(&"work".to_string())
+                || result.inherited_tags.contains(&"work".to_string()),
             "Result should have work tag"
         );
     }
@@ -655,9 +1003,15 @@ fn test_search_with_tags_multiple_tags() {
         .expect("Search with tags failed");
 
     for result in &results {
+        let has = |tag: &str| {
+            result
+                .tags
+                .iter()
+                .chain(&result.inherited_tags)
+                .any(|t| t == tag)
+        };
         assert!(
-            result.tags.contains(&"work".to_string())
-                || result.tags.contains(&"personal".to_string()),
+            has("work") || has("personal"),
             "Result should have work or personal tag"
         );
     }
@@ -715,6 +1069,7 @@ fn test_read_file_directory_error() {
 
     let config = OrgConfig {
         org_directory: temp_dir.path().to_str().unwrap().to_string(),
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
     let org_mode = OrgMode::new(config).expect("Failed to create OrgMode");
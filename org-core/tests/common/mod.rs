@@ -8,6 +8,7 @@ use test_utils::fixtures;
 pub fn create_test_org_mode(path: &Path) -> OrgMode {
     let config = OrgConfig {
         org_directory: path.to_string_lossy().to_string(),
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
     OrgMode::new(config).expect("Failed to create test OrgMode")
@@ -24,6 +25,7 @@ pub fn create_test_org_mode_with_agenda_files() -> (OrgMode, TempDir) {
     let config = OrgConfig {
         org_directory: temp_dir.path().to_string_lossy().to_string(),
         org_agenda_files: vec!["agenda.org".to_string(), "project.org".to_string()],
+        org_cache_directory: String::new(),
         ..OrgConfig::default()
     };
 
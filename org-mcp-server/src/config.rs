@@ -6,17 +6,37 @@ use org_core::{
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+/// How the server talks to its clients
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    /// A single client over stdin/stdout
+    #[default]
+    Stdio,
+    /// Streamable HTTP, shared by several clients
+    Http,
+}
+
 /// MCP server-specific configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
+    #[serde(default)]
+    pub transport: Transport,
+    #[serde(default = "default_bind")]
+    pub bind: String,
     #[serde(default = "default_max_connections")]
     pub max_connections: usize,
+    #[serde(default = "default_watch_interval_secs")]
+    pub watch_interval_secs: u64,
 }
 
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
+            transport: Transport::default(),
+            bind: default_bind(),
             max_connections: default_max_connections(),
+            watch_interval_secs: default_watch_interval_secs(),
         }
     }
 }
@@ -48,10 +68,17 @@ impl ServerAppConfig {
     }
 
     fn load_server_config(config_file: Option<&str>) -> Result<ServerConfig, OrgModeError> {
-        let builder = ConfigRs::builder().set_default(
-            "server.max_connections",
-            default_max_connections().to_string(),
-        )?;
+        let builder = ConfigRs::builder()
+            .set_default("server.transport", "stdio")?
+            .set_default("server.bind", default_bind())?
+            .set_default(
+                "server.max_connections",
+                default_max_connections().to_string(),
+            )?
+            .set_default(
+                "server.watch_interval_secs",
+                default_watch_interval_secs().to_string(),
+            )?;
 
         let config = build_config_with_file_and_env(config_file, builder)?;
 
@@ -93,10 +120,18 @@ impl ServerAppConfig {
     }
 }
 
+fn default_bind() -> String {
+    "127.0.0.1:8000".to_string()
+}
+
 fn default_max_connections() -> usize {
     10
 }
 
+pub(crate) fn default_watch_interval_secs() -> u64 {
+    2
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,6 +141,9 @@ mod tests {
     fn test_default_server_config() {
         let config = ServerConfig::default();
         assert_eq!(config.max_connections, 10);
+        assert_eq!(config.watch_interval_secs, 2);
+        assert_eq!(config.transport, Transport::Stdio);
+        assert_eq!(config.bind, "127.0.0.1:8000");
     }
 
     #[test]
@@ -121,7 +159,10 @@ mod tests {
 org_directory = "{}"
 
 [server]
+transport = "http"
+bind = "0.0.0.0:9000"
 max_connections = 20
+watch_interval_secs = 0
 
 [logging]
 level = "debug"
@@ -137,6 +178,9 @@ level = "debug"
 
         assert_eq!(config.org.org_directory, path_str);
         assert_eq!(config.server.max_connections, 20);
+        assert_eq!(config.server.watch_interval_secs, 0);
+        assert_eq!(config.server.transport, Transport::Http);
+        assert_eq!(config.server.bind, "0.0.0.0:9000");
         assert_eq!(config.logging.level, "debug");
     }
 
@@ -191,6 +235,7 @@ max_connections = 5
             },
             server: ServerConfig {
                 max_connections: 25,
+                ..ServerConfig::default()
             },
             logging: LoggingConfig {
                 level: "warn".to_string(),
@@ -1,11 +1,19 @@
-use std::{error, sync::Arc};
-use tokio::sync::Mutex;
+use std::{collections::HashSet, error, sync::Arc, time::Duration};
+use tokio::sync::{Mutex, OwnedSemaphorePermit};
 
 use org_core::{OrgMode, config::OrgConfig};
 use rmcp::handler::server::tool::ToolRouter;
 
+use crate::config::default_watch_interval_secs;
+use crate::watcher::FileWatcher;
+
 pub struct OrgModeRouter {
     pub(crate) org_mode: Arc<Mutex<OrgMode>>,
+    /// Resource URIs the client subscribed to, as it sent them.
+    pub(crate) subscriptions: Arc<Mutex<HashSet<String>>>,
+    pub(crate) watcher: Arc<FileWatcher>,
+    /// Held for as long as an HTTP session is open.
+    _session: Option<OwnedSemaphorePermit>,
 }
 
 impl OrgModeRouter {
@@ -13,9 +21,32 @@ impl OrgModeRouter {
         let org_mode = OrgMode::new(config)?;
         Ok(Self {
             org_mode: Arc::new(Mutex::new(org_mode)),
+            subscriptions: Arc::new(Mutex::new(HashSet::new())),
+            watcher: Arc::new(FileWatcher::new(Duration::from_secs(
+                default_watch_interval_secs(),
+            ))),
+            _session: None,
         })
     }
 
+    /// Sets how often the org files are polled for changes; zero disables
+    /// watching.
+    pub fn with_watch_interval(mut self, interval: Duration) -> Self {
+        self.watcher = Arc::new(FileWatcher::new(interval));
+        self
+    }
+
+    /// A handler for one more client session, sharing the org files and
+    /// the file watcher but with its own resource subscriptions.
+    pub(crate) fn session(&self, permit: OwnedSemaphorePermit) -> Self {
+        Self {
+            org_mode: Arc::clone(&self.org_mode),
+            subscriptions: Arc::new(Mutex::new(HashSet::new())),
+            watcher: Arc::clone(&self.watcher),
+            _session: Some(permit),
+        }
+    }
+
     pub fn with_directory(org_dir: &str) -> Result<Self, Box<dyn error::Error>> {
         let config = OrgConfig {
             org_directory: org_dir.to_string(),
@@ -31,5 +62,12 @@ impl OrgModeRouter {
             + Self::tool_router_agenda()
             + Self::tool_router_capture()
             + Self::tool_router_update_todo()
+            + Self::tool_router_clock()
+            + Self::tool_router_clock_report()
+            + Self::tool_router_refile()
+            + Self::tool_router_archive()
+            + Self::tool_router_promote()
+            + Self::tool_router_links()
+            + Self::tool_router_query()
     }
 }
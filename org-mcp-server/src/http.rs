@@ -0,0 +1,46 @@
+//! Streamable HTTP transport, letting several clients share one server.
+
+use std::{error, io, sync::Arc};
+
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+};
+use tokio::{net::TcpListener, sync::Semaphore};
+use tracing::info;
+
+use crate::{config::ServerConfig, core::OrgModeRouter};
+
+/// Path the MCP endpoint is mounted on.
+pub const MCP_PATH: &str = "/mcp";
+
+/// Serves `router` on `config.bind` until Ctrl-C, accepting at most
+/// `config.max_connections` concurrent sessions.
+pub async fn serve(
+    router: OrgModeRouter,
+    config: &ServerConfig,
+) -> Result<(), Box<dyn error::Error>> {
+    let sessions = Arc::new(Semaphore::new(config.max_connections));
+
+    let service = StreamableHttpService::new(
+        move || {
+            let permit = Arc::clone(&sessions)
+                .try_acquire_owned()
+                .map_err(|_| io::Error::other("maximum number of concurrent sessions reached"))?;
+            Ok(router.session(permit))
+        },
+        LocalSessionManager::default().into(),
+        StreamableHttpServerConfig::default(),
+    );
+
+    let app = axum::Router::new().nest_service(MCP_PATH, service);
+    let listener = TcpListener::bind(&config.bind).await?;
+    info!("Listening on http://{}{MCP_PATH}", listener.local_addr()?);
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+
+    Ok(())
+}
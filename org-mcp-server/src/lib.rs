@@ -1,5 +1,7 @@
 pub mod config;
 pub mod core;
+pub mod http;
 pub(crate) mod resources;
 pub(crate) mod tools;
 pub(crate) mod utils;
+pub(crate) mod watcher;
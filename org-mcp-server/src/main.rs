@@ -1,9 +1,13 @@
 use clap::Parser;
-use org_mcp_server::{config::ServerAppConfig, core::OrgModeRouter};
+use org_mcp_server::{
+    config::{ServerAppConfig, Transport},
+    core::OrgModeRouter,
+    http,
+};
 use rmcp::{ServiceExt, transport::stdio};
 use tracing::{error, info};
 
-use std::error;
+use std::{error, time::Duration};
 use tracing_subscriber::EnvFilter;
 
 #[derive(Parser)]
@@ -22,6 +26,14 @@ struct Cli {
     /// Log level (trace, debug, info, warn, error)
     #[arg(long)]
     log_level: Option<String>,
+
+    /// Transport to serve MCP over (stdio, http)
+    #[arg(long, value_enum)]
+    transport: Option<Transport>,
+
+    /// Address to listen on with the http transport
+    #[arg(long)]
+    bind: Option<String>,
 }
 
 #[tokio::main]
@@ -29,7 +41,13 @@ async fn main() -> Result<(), Box<dyn error::Error>> {
     let cli = Cli::parse();
 
     // Load configuration with CLI overrides
-    let config = ServerAppConfig::load(cli.config, cli.root_directory, cli.log_level.clone())?;
+    let mut config = ServerAppConfig::load(cli.config, cli.root_directory, cli.log_level.clone())?;
+    if let Some(transport) = cli.transport {
+        config.server.transport = transport;
+    }
+    if let Some(bind) = cli.bind {
+        config.server.bind = bind;
+    }
 
     // Initialize logging with config
     let log_level = cli
@@ -49,13 +67,18 @@ async fn main() -> Result<(), Box<dyn error::Error>> {
         config.org.org_directory
     );
 
-    let service = OrgModeRouter::with_config(config.org)?
-        .serve(stdio())
-        .await
-        .inspect_err(|e| {
-            error!("Error starting server: {e}");
-        })?;
-    service.waiting().await?;
+    let router = OrgModeRouter::with_config(config.org)?
+        .with_watch_interval(Duration::from_secs(config.server.watch_interval_secs));
+
+    match config.server.transport {
+        Transport::Stdio => {
+            let service = router.serve(stdio()).await.inspect_err(|e| {
+                error!("Error starting server: {e}");
+            })?;
+            service.waiting().await?;
+        }
+        Transport::Http => http::serve(router, &config.server).await?,
+    }
 
     Ok(())
 }
@@ -1,8 +1,10 @@
 mod org_agenda;
+mod org_backlinks;
 mod org_file;
 mod org_file_list;
 mod org_heading;
 mod org_id;
+mod org_links;
 mod org_outline;
 mod utils;
 
@@ -13,9 +15,9 @@ use org_core::org_mode::AgendaViewType;
 use rmcp::model::{
     Implementation, InitializeRequestParams, InitializeResult, ListResourceTemplatesResult,
     ListResourcesResult, PaginatedRequestParams, ReadResourceRequestParams, ReadResourceResponse,
-    Resource, ResourceTemplate,
+    Resource, ResourceTemplate, SubscribeRequestParams, UnsubscribeRequestParams,
 };
-use rmcp::service::RequestContext;
+use rmcp::service::{NotificationContext, RequestContext};
 use rmcp::{
     ErrorData as McpError,
     model::{ServerCapabilities, ServerInfo},
@@ -34,6 +36,8 @@ pub enum OrgResource {
     OrgHeading { path: String, heading: String },
     OrgId { id: String },
     OrgAgenda { agenda_view_type: AgendaViewType },
+    OrgBacklinks { target: String },
+    OrgLinks { path: String },
 }
 
 #[tool_handler]
@@ -46,12 +50,22 @@ impl ServerHandler for OrgModeRouter {
             "- org-search\n",
             "- org-agenda\n",
             "- org-capture\n",
+            "- org-clock\n",
+            "- org-clock-report\n",
+            "- org-refile\n",
+            "- org-archive\n",
+            "- org-promote\n",
+            "- org-demote\n",
+            "- org-links\n",
+            "- org-query\n",
             "Resources:\n",
             "- org:// (List all org-mode files in the configured directory tree)\n",
             "- org://{file} (Access the raw content of an allowed Org file)\n",
             "- org-outline://{file} (Get the hierarchical structure of an Org file)\n",
             "- org-heading://{file}#{heading} (Access the content of a specific headline by its path)\n",
             "- org-id://{uuid} (Access Org node content by its unique ID property)\n",
+            "- org-backlinks://{id} (Links pointing at an ID, a file, file::*Heading, or *Heading)\n",
+            "- org-links://{file} (Outgoing links of an Org file, with type and whether they resolve)\n",
             "- org-agenda:// (List all agenda items/tasks)\n",
             "- org-agenda://today (Today's scheduled tasks)\n",
             "- org-agenda://week (This week's scheduled tasks)\n",
@@ -61,6 +75,8 @@ impl ServerHandler for OrgModeRouter {
             ServerCapabilities::builder()
                 .enable_tools()
                 .enable_resources()
+                .enable_resources_subscribe()
+                .enable_resources_list_changed()
                 .enable_completions()
                 .build(),
         )
@@ -113,6 +129,16 @@ impl ServerHandler for OrgModeRouter {
                     "Access the content of any org-mode element by its unique ID property",
                 )
                 .with_mime_type("plain/text"),
+            ResourceTemplate::new("org-backlinks://{id}", "org-backlinks")
+                .with_description(
+                    "List the links pointing at an ID, an org file, a file::*Heading, or a *Heading title in any file, with the referencing heading and a context snippet",
+                )
+                .with_mime_type("application/json"),
+            ResourceTemplate::new("org-links://{file}", "org-links-file")
+                .with_description(
+                    "List the outgoing links of an org-mode file with their type, description, and whether the target exists",
+                )
+                .with_mime_type("application/json"),
             ResourceTemplate::new("org-agenda://day/{date}", "org-agenda-day")
                 .with_description("Access the agenda items for a specific day (YYYY-MM-DD)")
                 .with_mime_type("application/json"),
@@ -144,6 +170,8 @@ impl ServerHandler for OrgModeRouter {
             Some(OrgResource::OrgAgenda { agenda_view_type }) => {
                 self.read_agenda(uri, agenda_view_type).await
             }
+            Some(OrgResource::OrgBacklinks { target }) => self.backlinks(uri, target).await,
+            Some(OrgResource::OrgLinks { path }) => self.links(uri, path).await,
 
             None => Err(McpError::resource_not_found(
                 format!("Invalid resource URI format: {}", uri),
@@ -153,6 +181,34 @@ impl ServerHandler for OrgModeRouter {
         .map(Into::into)
     }
 
+    async fn subscribe(
+        &self,
+        SubscribeRequestParams { uri, .. }: SubscribeRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        if OrgModeRouter::parse_resource(uri.clone()).is_none() {
+            return Err(McpError::resource_not_found(
+                format!("Invalid resource URI format: {}", uri),
+                Some(json!({"uri": uri})),
+            ));
+        }
+        self.subscriptions.lock().await.insert(uri);
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        UnsubscribeRequestParams { uri, .. }: UnsubscribeRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.subscriptions.lock().await.remove(&uri);
+        Ok(())
+    }
+
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        self.spawn_watcher(context.peer);
+    }
+
     async fn initialize(
         &self,
         _request: InitializeRequestParams,
@@ -163,7 +219,7 @@ impl ServerHandler for OrgModeRouter {
 }
 
 impl OrgModeRouter {
-    fn parse_resource(uri: String) -> Option<OrgResource> {
+    pub(crate) fn parse_resource(uri: String) -> Option<OrgResource> {
         let uri = Self::decode_uri_path(&uri);
 
         if uri == "org://" {
@@ -178,6 +234,18 @@ impl OrgModeRouter {
             && !id.is_empty()
         {
             Some(OrgResource::OrgId { id: id.to_string() })
+        } else if let Some(path) = uri.strip_prefix("org-links://")
+            && !path.is_empty()
+        {
+            Some(OrgResource::OrgLinks {
+                path: path.to_string(),
+            })
+        } else if let Some(target) = uri.strip_prefix("org-backlinks://")
+            && !target.is_empty()
+        {
+            Some(OrgResource::OrgBacklinks {
+                target: target.to_string(),
+            })
         } else if let Some(path) = uri.strip_prefix("org-outline://")
             && !path.is_empty()
         {
@@ -443,4 +511,38 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_org_backlinks_resource_parsing() {
+        let cases = vec![
+            ("org-backlinks://task-groceries-456", "task-groceries-456"),
+            ("org-backlinks://notes.org", "notes.org"),
+            (
+                "org-backlinks://notes.org::*Daily%20Tasks",
+                "notes.org::*Daily Tasks",
+            ),
+        ];
+
+        for (uri, expected_target) in cases {
+            match OrgModeRouter::parse_resource(uri.to_string()) {
+                Some(OrgResource::OrgBacklinks { target }) => {
+                    assert_eq!(target, expected_target, "Failed for URI: {}", uri);
+                }
+                _ => {
+                    unreachable!("Expected OrgBacklinks resource for URI: {}", uri);
+                }
+            }
+        }
+
+        assert!(OrgModeRouter::parse_resource("org-backlinks://".to_string()).is_none());
+    }
+
+    #[test]
+    fn test_org_links_resource_parsing() {
+        match OrgModeRouter::parse_resource("org-links://path%2Fto%2Ffile.org".to_string()) {
+            Some(OrgResource::OrgLinks { path }) => assert_eq!(path, "path/to/file.org"),
+            _ => unreachable!("Expected OrgLinks resource"),
+        }
+        assert!(OrgModeRouter::parse_resource("org-links://".to_string()).is_none());
+    }
 }
@@ -0,0 +1,27 @@
+use rmcp::model::{ReadResourceResult, ResourceContents};
+use rmcp::{ErrorData as McpError, model::ErrorCode};
+
+use serde_json::json;
+
+use crate::core::OrgModeRouter;
+
+impl OrgModeRouter {
+    pub(crate) async fn backlinks(
+        &self,
+        uri: String,
+        target: String,
+    ) -> Result<ReadResourceResult, McpError> {
+        let org_mode = self.org_mode.lock().await;
+        match org_mode.backlinks(&target) {
+            Ok(backlinks) => Ok(ReadResourceResult::new(vec![
+                ResourceContents::text(serde_json::to_string(&backlinks).unwrap_or_default(), uri)
+                    .with_mime_type("json"),
+            ])),
+            Err(e) => Err(McpError {
+                code: ErrorCode::INTERNAL_ERROR,
+                message: format!("Failed to get backlinks for '{}': {}", target, e).into(),
+                data: Some(json!({"target": target, "uri": uri})),
+            }),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use rmcp::model::{ReadResourceResult, ResourceContents};
+use rmcp::{ErrorData as McpError, model::ErrorCode};
+
+use serde_json::json;
+
+use crate::core::OrgModeRouter;
+
+impl OrgModeRouter {
+    pub(crate) async fn links(
+        &self,
+        uri: String,
+        path: String,
+    ) -> Result<ReadResourceResult, McpError> {
+        let org_mode = self.org_mode.lock().await;
+        match org_mode.links_in(&path, None) {
+            Ok(links) => Ok(ReadResourceResult::new(vec![
+                ResourceContents::text(serde_json::to_string(&links).unwrap_or_default(), uri)
+                    .with_mime_type("json"),
+            ])),
+            Err(e) => Err(McpError {
+                code: ErrorCode::INTERNAL_ERROR,
+                message: format!("Failed to get links for '{}': {}", path, e).into(),
+                data: Some(json!({"path": path, "uri": uri})),
+            }),
+        }
+    }
+}
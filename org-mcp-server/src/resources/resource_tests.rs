@@ -1,4 +1,5 @@
 use crate::core::OrgModeRouter;
+use org_core::OrgConfig;
 use std::fs;
 use tempfile::TempDir;
 
@@ -35,7 +36,12 @@ Regular heading content.
     )
     .unwrap();
 
-    let router = OrgModeRouter::with_directory(temp_path.to_str().unwrap()).unwrap();
+    let config = OrgConfig {
+        org_directory: temp_path.to_str().unwrap().to_string(),
+        org_cache_directory: String::new(),
+        ..OrgConfig::default()
+    };
+    let router = OrgModeRouter::with_config(config.validate().unwrap()).unwrap();
     (router, temp_dir)
 }
 
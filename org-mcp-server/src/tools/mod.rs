@@ -1,5 +1,12 @@
 mod org_agenda;
+mod org_archive;
 mod org_capture;
+mod org_clock;
+mod org_clock_report;
 mod org_file_list;
+mod org_links;
+mod org_promote;
+mod org_query;
+mod org_refile;
 mod org_search;
 mod org_update_todo;
@@ -1,5 +1,5 @@
 use chrono::{Local, NaiveDate, TimeZone};
-use org_core::{OrgModeError, Priority, org_mode::AgendaViewType};
+use org_core::{AgendaOptions, AgendaSortKey, OrgModeError, Priority, org_mode::AgendaViewType};
 use rmcp::{
     ErrorData as McpError,
     handler::server::wrapper::Parameters,
@@ -22,17 +22,26 @@ pub struct AgendaRequest {
     )]
     pub todo_states: Option<Vec<String>>,
     #[schemars(
-        description = "Filter results by tags (optional, matches any of the provided tags)"
+        description = "Filter results by tags (optional, matches any of the provided tags, including ones inherited from #+FILETAGS and parent headings)"
     )]
     pub tags: Option<Vec<String>>,
     #[schemars(description = "Filter by priority level (optional: A, B, or C)")]
     pub priority: Option<String>,
+    #[schemars(
+        description = "Emacs tags/property match string (optional), e.g. '+work-someday|urgent+PRIORITY=\"A\"+EFFORT>1/!TODO|WAIT'"
+    )]
+    #[serde(rename = "match")]
+    pub match_string: Option<String>,
     #[schemars(description = "Maximum number of items to return (optional)")]
     #[serde(
         default,
         deserialize_with = "crate::utils::deserialize_string_or_number"
     )]
     pub limit: Option<usize>,
+    #[schemars(
+        description = "Sorting strategy overriding org_agenda_sorting_strategy (optional), keys applied in order, e.g. ['priority-down', 'deadline-up']. Keys: time, priority, deadline, scheduled, todo-state, category, alpha and effort, each with -up or -down, plus category-keep"
+    )]
+    pub sort: Option<Vec<String>>,
     #[schemars(
         description = "View mode: 'list' for all tasks, 'view' for date-organized agenda (default: 'list')"
     )]
@@ -43,7 +52,7 @@ pub struct AgendaRequest {
 impl OrgModeRouter {
     #[tool(
         name = "org-agenda",
-        description = "Query agenda items (TODO/DONE tasks) with support for filtering by dates, states, tags, and priorities. You can also specify a limit to the number of results returned to save context window space. Use 'list' mode to get all tasks, or 'view' mode for calendar-like agenda organized by scheduled/deadline dates.",
+        description = "Query agenda items (TODO/DONE tasks) with support for filtering by dates, states, tags, and priorities. You can also specify a limit to the number of results returned to save context window space. Use 'list' mode to get all tasks, or 'view' mode for calendar-like agenda organized by scheduled/deadline dates: besides the items, 'days' holds one bucket per day whose entries point at an item and give the reason it appears (scheduled, deadline, timestamp, repeat, overdue, upcoming, diary), the occurrence date and time of day. Views also list headings without a TODO keyword that have active timestamps, and evaluate diary sexps such as <%%(diary-anniversary 5 14 1990)> or <%%(diary-float t 4 2)>. Time and date ranges appear on every day they cover, and their items carry start, end and duration_minutes. Items and each day's entries follow the sorting strategy, by default time, then priority, then category. Each item carries its category and its property drawer merged with inherited properties.",
         annotations(title = "org-agenda tool")
     )]
     async fn tool_agenda(
@@ -54,7 +63,9 @@ impl OrgModeRouter {
             todo_states,
             tags,
             priority,
+            match_string,
             limit,
+            sort,
             mode,
         }): Parameters<AgendaRequest>,
     ) -> Result<CallToolResult, McpError> {
@@ -79,14 +90,31 @@ impl OrgModeRouter {
             None
         };
 
+        let sort = sort
+            .map(|keys| {
+                keys.iter()
+                    .map(|key| key.parse::<AgendaSortKey>())
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()
+            .map_err(|e| McpError {
+                code: ErrorCode::INVALID_PARAMS,
+                message: format!("Invalid sort: {e}").into(),
+                data: None,
+            })?;
+
+        let options = AgendaOptions {
+            todo_states,
+            tags,
+            priority: priority_filter,
+            match_string,
+            sort,
+            limit,
+        };
+
         match mode_str {
             "list" => {
-                let tasks = org_mode.list_tasks(
-                    todo_states.as_deref(),
-                    tags.as_deref(),
-                    priority_filter,
-                    limit,
-                );
+                let tasks = org_mode.list_tasks_with_options(&options);
 
                 match tasks {
                     Ok(tasks) => match ContentBlock::json(tasks) {
@@ -133,12 +161,7 @@ impl OrgModeRouter {
                     _ => AgendaViewType::default(),
                 };
 
-                let view = org_mode.get_agenda_view(
-                    agenda_view_type,
-                    todo_states.as_deref(),
-                    tags.as_deref(),
-                    limit,
-                );
+                let view = org_mode.get_agenda_view_with_options(agenda_view_type, &options);
 
                 match view {
                     Ok(view) => match ContentBlock::json(view) {
@@ -164,7 +187,9 @@ impl OrgModeRouter {
 impl OrgModeRouter {
     fn map_org_error(e: OrgModeError) -> McpError {
         let error_code = match &e {
-            OrgModeError::InvalidDirectory(_) => ErrorCode::INVALID_PARAMS,
+            OrgModeError::InvalidDirectory(_) | OrgModeError::InvalidQuery(_) => {
+                ErrorCode::INVALID_PARAMS
+            }
             OrgModeError::WalkError(_) => ErrorCode::INTERNAL_ERROR,
             OrgModeError::IoError(_) => ErrorCode::INTERNAL_ERROR,
             _ => ErrorCode::INTERNAL_ERROR,
@@ -0,0 +1,90 @@
+use org_core::{ArchiveMode, HeadingTarget, OrgModeError};
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{CallToolResult, ContentBlock, ErrorCode},
+    schemars, tool, tool_router,
+};
+
+use crate::core::OrgModeRouter;
+
+#[derive(Debug, schemars::JsonSchema, serde::Deserialize)]
+pub struct ArchiveRequest {
+    #[schemars(
+        description = "Org ID property of the heading to archive. Wins when file/heading_path are also given."
+    )]
+    pub id: Option<String>,
+    #[schemars(
+        description = "Relative file path of the heading to archive. Required together with heading_path."
+    )]
+    pub file: Option<String>,
+    #[schemars(
+        description = "Slash-separated path of the heading to archive (e.g., 'Projects/Website'). Required together with file."
+    )]
+    pub heading_path: Option<String>,
+    #[schemars(
+        description = "'subtree' moves the heading to the archive file (default); 'tag' toggles the :ARCHIVE: tag in place"
+    )]
+    pub mode: Option<String>,
+}
+
+#[tool_router(router = "tool_router_archive", vis = "pub(crate)")]
+impl OrgModeRouter {
+    #[tool(
+        name = "org-archive",
+        description = "Archive a finished heading. In 'subtree' mode (default) the heading and its subtree move to the configured org_archive_location (<file>_archive by default) with ARCHIVE_TIME, ARCHIVE_FILE, ARCHIVE_OLPATH, ARCHIVE_CATEGORY and ARCHIVE_TODO properties recording where it came from. In 'tag' mode the :ARCHIVE: tag is toggled and the heading stays put. Target by org ID property or by file + slash heading path.",
+        annotations(title = "org-archive tool")
+    )]
+    async fn tool_archive(
+        &self,
+        Parameters(ArchiveRequest {
+            id,
+            file,
+            heading_path,
+            mode,
+        }): Parameters<ArchiveRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let mode = match mode {
+            Some(value) => value.parse::<ArchiveMode>().map_err(|e| McpError {
+                code: ErrorCode::INVALID_PARAMS,
+                message: format!("Invalid mode: {e}").into(),
+                data: None,
+            })?,
+            None => ArchiveMode::default(),
+        };
+        let target = HeadingTarget {
+            id,
+            file,
+            heading_path,
+        };
+
+        let org_mode = self.org_mode.lock().await;
+
+        match org_mode.archive(&target, mode) {
+            Ok(result) => match ContentBlock::json(&result) {
+                Ok(serialized) => Ok(CallToolResult::success(vec![serialized])),
+                Err(e) => Err(McpError {
+                    code: ErrorCode::INTERNAL_ERROR,
+                    message: format!("Failed to serialize archive result: {e}").into(),
+                    data: None,
+                }),
+            },
+            Err(e) => {
+                let error_code = match &e {
+                    OrgModeError::InvalidHeadingPath(_)
+                    | OrgModeError::InvalidDirectory(_)
+                    | OrgModeError::InvalidLevel(_)
+                    | OrgModeError::HeadingNotFound(_)
+                    | OrgModeError::AmbiguousTarget(_)
+                    | OrgModeError::InvalidUpdate(_) => ErrorCode::INVALID_PARAMS,
+                    _ => ErrorCode::INTERNAL_ERROR,
+                };
+                Err(McpError {
+                    code: error_code,
+                    message: format!("Failed to archive heading: {e}").into(),
+                    data: None,
+                })
+            }
+        }
+    }
+}
@@ -54,7 +54,7 @@ pub struct CaptureRequest {
     )]
     pub target_heading: Option<String>,
     #[schemars(
-        description = "SCHEDULED active timestamp (ISO 'YYYY-MM-DD' or 'YYYY-MM-DD HH:MM', optional repeater +N|++N|.+N{h|d|w|m|y} and warning -N{h|d|w|m|y})."
+        description = "SCHEDULED active timestamp (ISO 'YYYY-MM-DD' or 'YYYY-MM-DD HH:MM', a time range 'YYYY-MM-DD HH:MM-HH:MM' or a date range 'YYYY-MM-DD[ HH:MM]--YYYY-MM-DD[ HH:MM]', optional repeater +N|++N|.+N{h|d|w|m|y} and warning -N{h|d|w|m|y})."
     )]
     pub scheduled: Option<String>,
     #[schemars(description = "DEADLINE active timestamp; same grammar as scheduled.")]
@@ -0,0 +1,99 @@
+use org_core::{ClockAction, HeadingTarget, OrgModeError};
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{CallToolResult, ContentBlock, ErrorCode},
+    schemars, tool, tool_router,
+};
+
+use crate::core::OrgModeRouter;
+
+#[derive(Debug, schemars::JsonSchema, serde::Deserialize)]
+pub struct ClockRequest {
+    #[schemars(description = "Clock action: 'in', 'out', or 'cancel'")]
+    pub action: String,
+    #[schemars(
+        description = "Org ID property of the target heading. Wins when file/heading_path are also given."
+    )]
+    pub id: Option<String>,
+    #[schemars(
+        description = "Relative file path within org directory. Required together with heading_path."
+    )]
+    pub file: Option<String>,
+    #[schemars(
+        description = "Slash-separated heading path (e.g., 'Projects/Work'). Required together with file."
+    )]
+    pub heading_path: Option<String>,
+    #[schemars(
+        description = "Clock in/out time as ISO 'YYYY-MM-DD HH:MM'. Defaults to now. Ignored for 'cancel'."
+    )]
+    pub at: Option<String>,
+}
+
+#[tool_router(router = "tool_router_clock", vis = "pub(crate)")]
+impl OrgModeRouter {
+    #[tool(
+        name = "org-clock",
+        description = "Clock time on an existing heading. 'in' starts a clock by adding an open CLOCK line to the heading's :LOGBOOK: drawer (created when absent) and fails if another clock is already running; 'out' closes the running clock with its => H:MM duration; 'cancel' discards the running clock. Target by org ID property or by file + slash heading path.",
+        annotations(title = "org-clock tool")
+    )]
+    async fn tool_clock(
+        &self,
+        Parameters(ClockRequest {
+            action,
+            id,
+            file,
+            heading_path,
+            at,
+        }): Parameters<ClockRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let action = action.parse::<ClockAction>().map_err(|e| McpError {
+            code: ErrorCode::INVALID_PARAMS,
+            message: format!("Invalid action: {e}").into(),
+            data: None,
+        })?;
+
+        let target = HeadingTarget {
+            id,
+            file,
+            heading_path,
+        };
+
+        let org_mode = self.org_mode.lock().await;
+
+        let result = match action {
+            ClockAction::In => org_mode.clock_in(&target, at.as_deref()),
+            ClockAction::Out => org_mode.clock_out(&target, at.as_deref()),
+            ClockAction::Cancel => org_mode.clock_cancel(&target),
+        };
+
+        match result {
+            Ok(result) => match ContentBlock::json(&result) {
+                Ok(serialized) => Ok(CallToolResult::success(vec![serialized])),
+                Err(e) => Err(McpError {
+                    code: ErrorCode::INTERNAL_ERROR,
+                    message: format!("Failed to serialize clock result: {e}").into(),
+                    data: None,
+                }),
+            },
+            Err(e) => {
+                let error_code = match &e {
+                    OrgModeError::InvalidHeadingPath(_)
+                    | OrgModeError::InvalidDirectory(_)
+                    | OrgModeError::InvalidTimestamp { .. }
+                    | OrgModeError::HeadingNotFound(_)
+                    | OrgModeError::AmbiguousTarget(_)
+                    | OrgModeError::InvalidUpdate(_)
+                    | OrgModeError::ClockAlreadyRunning(_)
+                    | OrgModeError::NoRunningClock(_) => ErrorCode::INVALID_PARAMS,
+                    _ => ErrorCode::INTERNAL_ERROR,
+                };
+                Err(McpError {
+                    code: error_code,
+                    message: format!("Failed to clock {action}: {e}").into(),
+                    data: None,
+                })
+            }
+        }
+    }
+}
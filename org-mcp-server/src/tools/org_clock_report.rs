@@ -0,0 +1,91 @@
+use org_core::{ClockGroupBy, OrgModeError, org_mode::AgendaViewType};
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{CallToolResult, ContentBlock, ErrorCode},
+    schemars, tool, tool_router,
+};
+
+use crate::core::OrgModeRouter;
+
+#[derive(Debug, schemars::JsonSchema, serde::Deserialize)]
+pub struct ClockReportRequest {
+    #[schemars(
+        description = "Agenda range: 'today', 'week', 'month', 'day/YYYY-MM-DD', 'week/N' or 'month/N' (default: 'week'). Ignored when start_date and end_date are given."
+    )]
+    pub range: Option<String>,
+    #[schemars(description = "Start date in ISO 8601 format (YYYY-MM-DD, optional)")]
+    pub start_date: Option<String>,
+    #[schemars(description = "End date in ISO 8601 format (YYYY-MM-DD, optional)")]
+    pub end_date: Option<String>,
+    #[schemars(description = "Group totals by 'heading', 'file', or 'tag' (default: 'heading')")]
+    pub group_by: Option<String>,
+}
+
+#[tool_router(router = "tool_router_clock_report", vis = "pub(crate)")]
+impl OrgModeRouter {
+    #[tool(
+        name = "org-clock-report",
+        description = "Sum clocked time (closed CLOCK lines in :LOGBOOK: drawers) across agenda files over a date range, grouped per heading, file, or tag. Clocks crossing the range boundaries only count the overlapping minutes. Use it to answer questions like 'how long did I spend on project X this week'.",
+        annotations(title = "org-clock-report tool")
+    )]
+    async fn tool_clock_report(
+        &self,
+        Parameters(ClockReportRequest {
+            range,
+            start_date,
+            end_date,
+            group_by,
+        }): Parameters<ClockReportRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let group_by = match group_by {
+            Some(value) => value.parse::<ClockGroupBy>().map_err(|e| McpError {
+                code: ErrorCode::INVALID_PARAMS,
+                message: format!("Invalid group_by: {e}").into(),
+                data: None,
+            })?,
+            None => ClockGroupBy::default(),
+        };
+
+        let range = match (start_date, end_date) {
+            (Some(start), Some(end)) => format!("query/from/{start}/to/{end}"),
+            (None, None) => range.unwrap_or_else(|| "week".to_string()),
+            _ => {
+                return Err(McpError {
+                    code: ErrorCode::INVALID_PARAMS,
+                    message: "start_date and end_date must be given together".into(),
+                    data: None,
+                });
+            }
+        };
+        let range = AgendaViewType::try_from(range.as_str()).map_err(|e| McpError {
+            code: ErrorCode::INVALID_PARAMS,
+            message: format!("Invalid range: {e}").into(),
+            data: None,
+        })?;
+
+        let org_mode = self.org_mode.lock().await;
+
+        match org_mode.clock_report(range, group_by) {
+            Ok(report) => match ContentBlock::json(&report) {
+                Ok(serialized) => Ok(CallToolResult::success(vec![serialized])),
+                Err(e) => Err(McpError {
+                    code: ErrorCode::INTERNAL_ERROR,
+                    message: format!("Failed to serialize clock report: {e}").into(),
+                    data: None,
+                }),
+            },
+            Err(e) => {
+                let error_code = match &e {
+                    OrgModeError::InvalidAgendaViewType(_) => ErrorCode::INVALID_PARAMS,
+                    _ => ErrorCode::INTERNAL_ERROR,
+                };
+                Err(McpError {
+                    code: error_code,
+                    message: format!("Clock report failed: {e}").into(),
+                    data: None,
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use org_core::OrgModeError;
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{CallToolResult, ContentBlock, ErrorCode},
+    schemars, tool, tool_router,
+};
+
+use crate::core::OrgModeRouter;
+
+#[derive(Debug, schemars::JsonSchema, serde::Deserialize)]
+pub struct LinksRequest {
+    #[schemars(
+        description = "Node to find backlinks for: an ID property value, an org file path relative to the org directory (e.g., 'notes.org'), a heading in one (e.g., 'notes.org::*Reading list'), or a heading title in any file (e.g., '*Reading list')"
+    )]
+    pub target: String,
+}
+
+#[tool_router(router = "tool_router_links", vis = "pub(crate)")]
+impl OrgModeRouter {
+    #[tool(
+        name = "org-links",
+        description = "Find backlinks: every [[id:...]], [[file:...]] and [[file:x.org::*Heading]] link across the org files that points at the target. Each result has the referencing file, the enclosing heading path, the line number and a context snippet.",
+        annotations(title = "org-links tool")
+    )]
+    async fn tool_links(
+        &self,
+        Parameters(LinksRequest { target }): Parameters<LinksRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let org_mode = self.org_mode.lock().await;
+
+        match org_mode.backlinks(&target) {
+            Ok(backlinks) => match ContentBlock::json(&backlinks) {
+                Ok(serialized) => Ok(CallToolResult::success(vec![serialized])),
+                Err(e) => Err(McpError {
+                    code: ErrorCode::INTERNAL_ERROR,
+                    message: format!("Failed to serialize backlinks: {e}").into(),
+                    data: None,
+                }),
+            },
+            Err(e) => {
+                let error_code = match &e {
+                    OrgModeError::InvalidElementId(_) => ErrorCode::INVALID_PARAMS,
+                    _ => ErrorCode::INTERNAL_ERROR,
+                };
+                Err(McpError {
+                    code: error_code,
+                    message: format!("Failed to find backlinks: {e}").into(),
+                    data: None,
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use org_core::{HeadingTarget, OrgModeError};
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{CallToolResult, ContentBlock, ErrorCode},
+    schemars, tool, tool_router,
+};
+
+use crate::core::OrgModeRouter;
+
+#[derive(Debug, schemars::JsonSchema, serde::Deserialize)]
+pub struct LevelRequest {
+    #[schemars(
+        description = "Org ID property of the target heading. Wins when file/heading_path are also given."
+    )]
+    pub id: Option<String>,
+    #[schemars(
+        description = "Relative file path of the target heading. Required together with heading_path."
+    )]
+    pub file: Option<String>,
+    #[schemars(
+        description = "Slash-separated path of the target heading (e.g., 'Projects/Website'). Required together with file."
+    )]
+    pub heading_path: Option<String>,
+    #[schemars(description = "Number of levels to shift (default: 1)")]
+    pub levels: Option<u32>,
+    #[schemars(
+        description = "Shift every descendant heading too (default: false, only the headline changes)"
+    )]
+    pub subtree: Option<bool>,
+}
+
+impl OrgModeRouter {
+    async fn shift_heading_level(
+        &self,
+        request: LevelRequest,
+        sign: isize,
+    ) -> Result<CallToolResult, McpError> {
+        let LevelRequest {
+            id,
+            file,
+            heading_path,
+            levels,
+            subtree,
+        } = request;
+        let delta = sign * levels.unwrap_or(1) as isize;
+        let target = HeadingTarget {
+            id,
+            file,
+            heading_path,
+        };
+
+        let org_mode = self.org_mode.lock().await;
+
+        match org_mode.shift_level(&target, delta, subtree.unwrap_or(false)) {
+            Ok(result) => match ContentBlock::json(&result) {
+                Ok(serialized) => Ok(CallToolResult::success(vec![serialized])),
+                Err(e) => Err(McpError {
+                    code: ErrorCode::INTERNAL_ERROR,
+                    message: format!("Failed to serialize level change result: {e}").into(),
+                    data: None,
+                }),
+            },
+            Err(e) => {
+                let error_code = match &e {
+                    OrgModeError::InvalidHeadingPath(_)
+                    | OrgModeError::InvalidDirectory(_)
+                    | OrgModeError::InvalidLevel(_)
+                    | OrgModeError::HeadingNotFound(_)
+                    | OrgModeError::AmbiguousTarget(_)
+                    | OrgModeError::InvalidUpdate(_) => ErrorCode::INVALID_PARAMS,
+                    _ => ErrorCode::INTERNAL_ERROR,
+                };
+                Err(McpError {
+                    code: error_code,
+                    message: format!("Failed to change heading level: {e}").into(),
+                    data: None,
+                })
+            }
+        }
+    }
+}
+
+#[tool_router(router = "tool_router_promote", vis = "pub(crate)")]
+impl OrgModeRouter {
+    #[tool(
+        name = "org-promote",
+        description = "Promote a heading by one or more levels (fewer stars). Set subtree to shift its descendants along with it; promoting past level 1 is rejected. Target by org ID property or by file + slash heading path. Returns the before/after heading lines.",
+        annotations(title = "org-promote tool")
+    )]
+    async fn tool_promote(
+        &self,
+        Parameters(request): Parameters<LevelRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        self.shift_heading_level(request, -1).await
+    }
+
+    #[tool(
+        name = "org-demote",
+        description = "Demote a heading by one or more levels (more stars). Set subtree to shift its descendants along with it; levels beyond the maximum heading depth are rejected. Target by org ID property or by file + slash heading path. Returns the before/after heading lines.",
+        annotations(title = "org-demote tool")
+    )]
+    async fn tool_demote(
+        &self,
+        Parameters(request): Parameters<LevelRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        self.shift_heading_level(request, 1).await
+    }
+}
@@ -0,0 +1,60 @@
+use org_core::OrgModeError;
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{CallToolResult, ContentBlock, ErrorCode},
+    schemars, tool, tool_router,
+};
+
+use crate::core::OrgModeRouter;
+
+#[derive(Debug, schemars::JsonSchema, serde::Deserialize)]
+pub struct QueryRequest {
+    #[schemars(
+        description = "Query expression, e.g. '(and (todo \"TODO\" \"WAIT\") (tags \"work\") (not (priority \"C\")) (scheduled :from today :to +7))'"
+    )]
+    pub query: String,
+    #[schemars(description = "Maximum number of items to return (optional)")]
+    #[serde(
+        default,
+        deserialize_with = "crate::utils::deserialize_string_or_number"
+    )]
+    pub limit: Option<usize>,
+}
+
+#[tool_router(router = "tool_router_query", vis = "pub(crate)")]
+impl OrgModeRouter {
+    #[tool(
+        name = "org-query",
+        description = "Find headings in the agenda files with an org-ql style expression. Predicates: (and ...), (or ...), (not X), (todo [KW...]), (done [KW...]), (tags TAG...) for any, (tags-all TAG...), (priority [P...]) or (priority \">=\" \"B\"), (scheduled|deadline [:from D] [:to D] [:on D]) where D is today, tomorrow, yesterday, +N, -N or YYYY-MM-DD, (property KEY [[OP] VALUE]) comparing durations like 1:30 or 2h and numbers, (regexp RE...) on the heading and its body, (heading TEXT...) and (category NAME...). Tags and properties include inherited ones. Returns agenda items.",
+        annotations(title = "org-query tool")
+    )]
+    async fn tool_query(
+        &self,
+        Parameters(QueryRequest { query, limit }): Parameters<QueryRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let org_mode = self.org_mode.lock().await;
+
+        match org_mode.query(&query, limit) {
+            Ok(items) => match ContentBlock::json(&items) {
+                Ok(serialized) => Ok(CallToolResult::success(vec![serialized])),
+                Err(e) => Err(McpError {
+                    code: ErrorCode::INTERNAL_ERROR,
+                    message: format!("Failed to serialize query results: {e}").into(),
+                    data: None,
+                }),
+            },
+            Err(e) => {
+                let error_code = match &e {
+                    OrgModeError::InvalidQuery(_) => ErrorCode::INVALID_PARAMS,
+                    _ => ErrorCode::INTERNAL_ERROR,
+                };
+                Err(McpError {
+                    code: error_code,
+                    message: format!("Query failed: {e}").into(),
+                    data: None,
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use org_core::{HeadingTarget, OrgModeError, RefileTarget};
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{CallToolResult, ContentBlock, ErrorCode},
+    schemars, tool, tool_router,
+};
+
+use crate::core::OrgModeRouter;
+
+#[derive(Debug, schemars::JsonSchema, serde::Deserialize)]
+pub struct RefileRequest {
+    #[schemars(
+        description = "Org ID property of the heading to move. Wins when file/heading_path are also given."
+    )]
+    pub id: Option<String>,
+    #[schemars(
+        description = "Relative file path of the heading to move. Required together with heading_path."
+    )]
+    pub file: Option<String>,
+    #[schemars(
+        description = "Slash-separated path of the heading to move (e.g., 'Inbox/Call plumber'). Required together with file."
+    )]
+    pub heading_path: Option<String>,
+    #[schemars(description = "Relative path of the destination file (must exist)")]
+    pub target_file: String,
+    #[schemars(
+        description = "Slash-separated path of the new parent heading in target_file. Omit to refile as a top-level heading at the end of the file."
+    )]
+    pub target_heading_path: Option<String>,
+}
+
+#[tool_router(router = "tool_router_refile", vis = "pub(crate)")]
+impl OrgModeRouter {
+    #[tool(
+        name = "org-refile",
+        description = "Move a heading and its whole subtree to another parent heading, in the same or a different file. The subtree is re-levelled to sit one level below the new parent (or at level 1 when no target heading is given) and appended after the parent's existing children. Source is targeted by org ID property or by file + slash heading path; the target heading must already exist.",
+        annotations(title = "org-refile tool")
+    )]
+    async fn tool_refile(
+        &self,
+        Parameters(RefileRequest {
+            id,
+            file,
+            heading_path,
+            target_file,
+            target_heading_path,
+        }): Parameters<RefileRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let source = HeadingTarget {
+            id,
+            file,
+            heading_path,
+        };
+        let target = RefileTarget {
+            file: target_file,
+            heading_path: target_heading_path,
+        };
+
+        let org_mode = self.org_mode.lock().await;
+
+        match org_mode.refile(&source, &target) {
+            Ok(result) => match ContentBlock::json(&result) {
+                Ok(serialized) => Ok(CallToolResult::success(vec![serialized])),
+                Err(e) => Err(McpError {
+                    code: ErrorCode::INTERNAL_ERROR,
+                    message: format!("Failed to serialize refile result: {e}").into(),
+                    data: None,
+                }),
+            },
+            Err(e) => {
+                let error_code = match &e {
+                    OrgModeError::InvalidHeadingPath(_)
+                    | OrgModeError::InvalidDirectory(_)
+                    | OrgModeError::InvalidLevel(_)
+                    | OrgModeError::HeadingNotFound(_)
+                    | OrgModeError::AmbiguousTarget(_)
+                    | OrgModeError::InvalidUpdate(_) => ErrorCode::INVALID_PARAMS,
+                    _ => ErrorCode::INTERNAL_ERROR,
+                };
+                Err(McpError {
+                    code: error_code,
+                    message: format!("Failed to refile heading: {e}").into(),
+                    data: None,
+                })
+            }
+        }
+    }
+}
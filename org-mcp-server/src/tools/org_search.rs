@@ -1,4 +1,4 @@
-use org_core::OrgModeError;
+use org_core::{OrgModeError, SearchElement, SearchMode, SearchOptions};
 use rmcp::{
     ErrorData as McpError,
     handler::server::wrapper::Parameters,
@@ -25,16 +25,39 @@ pub struct SearchRequest {
     )]
     pub snippet_max_size: Option<usize>,
     #[schemars(
-        description = "Filter results by tags (optional, matches any of the provided tags)"
+        description = "Filter results by the enclosing heading's tags, including those inherited from ancestors and #+FILETAGS (optional, matches any of the provided tags)"
     )]
     pub tags: Option<Vec<String>>,
+    #[schemars(
+        description = "Only search entries satisfying an Emacs tags/property match string (optional), e.g. '+work-someday+PRIORITY=\"A\"'"
+    )]
+    #[serde(rename = "match")]
+    pub match_string: Option<String>,
+    #[schemars(
+        description = "How to match the query: 'fuzzy' (default), 'exact' substring, 'regex', or 'words' for Emacs-style '+must -mustnot \"some phrase\"' keywords matched against whole entries, or 'ranked' for heading sections ordered by BM25 relevance (stemmed, case-insensitive, title words weigh more; snippets hold the whole section unless snippet_max_size is set)"
+    )]
+    pub mode: Option<String>,
+    #[schemars(description = "Match letter case exactly (default: false)")]
+    pub case_sensitive: Option<bool>,
+    #[schemars(
+        description = "File, directory or glob to search, relative to the org directory without '..' (optional, e.g. 'projects/*.org'); only org files inside it are read. By default every org file plus org_agenda_text_search_extra_files"
+    )]
+    pub files: Option<String>,
+    #[schemars(
+        description = "Only search the subtree of this slash-separated heading path (optional, e.g. 'Projects/Website')"
+    )]
+    pub heading_path: Option<String>,
+    #[schemars(
+        description = "Only search these kinds of lines (optional): 'headline', 'body', 'property', 'src_block', 'table'"
+    )]
+    pub elements: Option<Vec<String>>,
 }
 
 #[tool_router(router = "tool_router_search", vis = "pub(crate)")]
 impl OrgModeRouter {
     #[tool(
         name = "org-search",
-        description = "Search for text content across all org files using fuzzy matching. You can optionally specify a limit to the number of results returned.",
+        description = "Search for text content across all org files using fuzzy matching, or exact, regex or boolean keyword matching via mode. You can optionally specify a limit to the number of results returned. Each hit reports its line and the enclosing heading's path, level, position, ID, own and inherited tags, ready for org-heading:// or org-update-todo.",
         annotations(title = "org-search tool")
     )]
     async fn tool_search(
@@ -44,15 +67,45 @@ impl OrgModeRouter {
             limit,
             snippet_max_size,
             tags,
+            match_string,
+            mode,
+            case_sensitive,
+            files,
+            heading_path,
+            elements,
         }): Parameters<SearchRequest>,
     ) -> Result<CallToolResult, McpError> {
+        let mode = match mode {
+            Some(value) => value.parse::<SearchMode>().map_err(|e| McpError {
+                code: ErrorCode::INVALID_PARAMS,
+                message: format!("Invalid mode: {e}").into(),
+                data: None,
+            })?,
+            None => SearchMode::default(),
+        };
+        let elements = elements
+            .unwrap_or_default()
+            .iter()
+            .map(|element| element.parse::<SearchElement>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| McpError {
+                code: ErrorCode::INVALID_PARAMS,
+                message: format!("Invalid elements: {e}").into(),
+                data: None,
+            })?;
+        let options = SearchOptions {
+            mode,
+            case_sensitive: case_sensitive.unwrap_or(false),
+            match_string,
+            tags,
+            files,
+            heading_path,
+            elements,
+        };
+
         let org_mode = self.org_mode.lock().await;
 
-        let results = if tags.is_some() {
-            org_mode.search_with_tags(&query, tags.as_deref(), limit, snippet_max_size)
-        } else {
-            org_mode.search(&query, limit, snippet_max_size)
-        };
+        let results = org_mode.search_with_options(&query, &options, limit, snippet_max_size);
 
         match results {
             Ok(results) => match ContentBlock::json(results) {
@@ -65,7 +118,10 @@ impl OrgModeRouter {
             },
             Err(e) => {
                 let error_code = match &e {
-                    OrgModeError::InvalidDirectory(_) => ErrorCode::INVALID_PARAMS,
+                    OrgModeError::InvalidDirectory(_)
+                    | OrgModeError::InvalidQuery(_)
+                    | OrgModeError::InvalidHeadingPath(_)
+                    | OrgModeError::GlobError(_) => ErrorCode::INVALID_PARAMS,
                     OrgModeError::WalkError(_) => ErrorCode::INTERNAL_ERROR,
                     OrgModeError::IoError(_) => ErrorCode::INTERNAL_ERROR,
                     _ => ErrorCode::INTERNAL_ERROR,
@@ -48,7 +48,7 @@ pub struct UpdateTodoRequest {
     )]
     pub tags: Option<Vec<String>>,
     #[schemars(
-        description = "New SCHEDULED active timestamp (ISO 'YYYY-MM-DD' or 'YYYY-MM-DD HH:MM', optional repeater +N|++N|.+N{h|d|w|m|y} and warning -N{h|d|w|m|y})."
+        description = "New SCHEDULED active timestamp (ISO 'YYYY-MM-DD' or 'YYYY-MM-DD HH:MM', a time range 'YYYY-MM-DD HH:MM-HH:MM' or a date range 'YYYY-MM-DD[ HH:MM]--YYYY-MM-DD[ HH:MM]', optional repeater +N|++N|.+N{h|d|w|m|y} and warning -N{h|d|w|m|y})."
     )]
     pub scheduled: Option<String>,
     #[schemars(description = "New DEADLINE active timestamp; same grammar as scheduled.")]
@@ -73,13 +73,17 @@ pub struct UpdateTodoRequest {
         Removing a non-existent key is a no-op. \
         A key must not appear in both properties and remove_properties.")]
     pub remove_properties: Option<Vec<String>>,
+    #[schemars(description = "Note for the state change, logged as \
+        '- State \"NEW\" from \"OLD\" [timestamp]' in the LOGBOOK drawer. \
+        Requires a todo_state change.")]
+    pub note: Option<String>,
 }
 
 #[tool_router(router = "tool_router_update_todo", vis = "pub(crate)")]
 impl OrgModeRouter {
     #[tool(
         name = "org-update-todo",
-        description = "Update the TODO state and planning metadata of an existing heading. Target by org ID property or by file + slash heading path. Set todo_state, priority, tags (replaced wholesale), SCHEDULED/DEADLINE/CLOSED timestamps, and/or remove fields via the clear list. CLOSED is auto-managed on done/active transitions unless org_auto_closed_timestamp is disabled.",
+        description = "Update the TODO state and planning metadata of an existing heading. Target by org ID property or by file + slash heading path. Set todo_state, priority, tags (replaced wholesale), SCHEDULED/DEADLINE/CLOSED timestamps, and/or remove fields via the clear list. CLOSED is auto-managed on done/active transitions unless org_auto_closed_timestamp is disabled. Marking a task with a repeating SCHEDULED/DEADLINE done advances those timestamps instead, resets it to the first TODO state and records LAST_REPEAT plus a LOGBOOK state note. State changes are also logged when the keyword's org_todo_keywords spec asks for it (e.g. 'WAIT(w@/!)') or a note is given.",
         annotations(title = "org-update-todo tool")
     )]
     async fn tool_update_todo(
@@ -99,6 +103,7 @@ impl OrgModeRouter {
             body,
             properties,
             remove_properties,
+            note,
         }): Parameters<UpdateTodoRequest>,
     ) -> Result<CallToolResult, McpError> {
         let clear: Vec<ClearField> = match clear {
@@ -129,6 +134,7 @@ impl OrgModeRouter {
             body,
             properties: properties.map(|v| v.into_iter().map(Into::into).collect()),
             remove_properties,
+            note,
         };
 
         let org_mode = self.org_mode.lock().await;
@@ -0,0 +1,206 @@
+//! Polls the org directory and the agenda files for changes and tells each
+//! client which of its subscribed resources are out of date.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use org_core::{FileChanges, OrgMode};
+use rmcp::model::ResourceUpdatedNotificationParam;
+use rmcp::service::Peer;
+use rmcp::{RoleServer, ServiceError};
+use tokio::sync::Mutex;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::{MissedTickBehavior, interval};
+use tracing::{debug, warn};
+
+use crate::core::OrgModeRouter;
+use crate::resources::OrgResource;
+
+/// Change batches a slow session may fall behind before missing some.
+const CHANGES_BACKLOG: usize = 16;
+
+/// One poller per server, shared by every session: `OrgMode::refresh`
+/// hands each change out once, so sessions must not poll on their own.
+pub(crate) struct FileWatcher {
+    interval: Duration,
+    changes: broadcast::Sender<Arc<FileChanges>>,
+    started: AtomicBool,
+}
+
+impl FileWatcher {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            changes: broadcast::channel(CHANGES_BACKLOG).0,
+            started: AtomicBool::new(false),
+        }
+    }
+
+    /// Subscribes to file changes, starting the poller on first use.
+    /// Returns `None` when watching is disabled.
+    fn subscribe(
+        &self,
+        org_mode: &Arc<Mutex<OrgMode>>,
+    ) -> Option<broadcast::Receiver<Arc<FileChanges>>> {
+        if self.interval.is_zero() {
+            return None;
+        }
+        let receiver = self.changes.subscribe();
+        if !self.started.swap(true, Ordering::SeqCst) {
+            self.spawn_poller(Arc::clone(org_mode));
+        }
+        Some(receiver)
+    }
+
+    fn spawn_poller(&self, org_mode: Arc<Mutex<OrgMode>>) {
+        let changes = self.changes.clone();
+        let mut ticker = interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        tokio::spawn(async move {
+            // The first scan only sets the baseline: everything is new to it.
+            ticker.tick().await;
+            if let Err(e) = org_mode.lock().await.refresh() {
+                warn!("Initial file scan failed: {e}");
+            }
+
+            loop {
+                ticker.tick().await;
+                match org_mode.lock().await.refresh() {
+                    Ok(batch) if batch.is_empty() => {}
+                    Ok(batch) => {
+                        debug!("Org files changed: {batch:?}");
+                        // No receivers just means no session is listening.
+                        let _ = changes.send(Arc::new(batch));
+                    }
+                    Err(e) => warn!("File scan failed: {e}"),
+                }
+            }
+        });
+    }
+}
+
+impl OrgModeRouter {
+    /// Forwards file changes affecting this session's subscriptions to
+    /// `peer` until the client goes away.
+    pub(crate) fn spawn_watcher(&self, peer: Peer<RoleServer>) {
+        let Some(mut changes) = self.watcher.subscribe(&self.org_mode) else {
+            return;
+        };
+        let subscriptions = Arc::clone(&self.subscriptions);
+
+        tokio::spawn(async move {
+            loop {
+                let batch = match changes.recv().await {
+                    Ok(batch) => batch,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Skipped {skipped} file change batches");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                let uris = Self::affected_uris(&*subscriptions.lock().await, &batch);
+                if let Err(e) = Self::notify(&peer, uris, &batch).await {
+                    debug!("Stopping file change notifications: {e}");
+                    break;
+                }
+            }
+        });
+    }
+
+    /// The subscribed URIs whose content depends on the changed files.
+    fn affected_uris(subscriptions: &HashSet<String>, changes: &FileChanges) -> Vec<String> {
+        let changed: HashSet<&str> = changes
+            .added
+            .iter()
+            .chain(&changes.modified)
+            .chain(&changes.removed)
+            .map(String::as_str)
+            .collect();
+        let listing_changed = !changes.added.is_empty() || !changes.removed.is_empty();
+
+        let mut uris: Vec<String> = subscriptions
+            .iter()
+            .filter(|uri| match Self::parse_resource(uri.to_string()) {
+                Some(OrgResource::OrgFiles) => listing_changed,
+                Some(
+                    OrgResource::Org { path }
+                    | OrgResource::OrgOutline { path }
+                    | OrgResource::OrgHeading { path, .. },
+                ) => changed.contains(path.as_str()),
+                Some(OrgResource::OrgAgenda { .. }) => changes.agenda_changed,
+                _ => false,
+            })
+            .cloned()
+            .collect();
+        uris.sort();
+        uris
+    }
+
+    async fn notify(
+        peer: &Peer<RoleServer>,
+        uris: Vec<String>,
+        changes: &FileChanges,
+    ) -> Result<(), ServiceError> {
+        for uri in uris {
+            peer.notify_resource_updated(ResourceUpdatedNotificationParam::new(uri))
+                .await?;
+        }
+        if !changes.added.is_empty() || !changes.removed.is_empty() {
+            peer.notify_resource_list_changed().await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscriptions(uris: &[&str]) -> HashSet<String> {
+        uris.iter().map(|uri| uri.to_string()).collect()
+    }
+
+    #[test]
+    fn test_affected_uris_by_file() {
+        let subscribed = subscriptions(&[
+            "org://",
+            "org://notes.org",
+            "org-outline://notes.org",
+            "org-heading://notes.org#Inbox",
+            "org://other.org",
+            "org-agenda://today",
+        ]);
+        let changes = FileChanges {
+            modified: vec!["notes.org".to_string()],
+            ..FileChanges::default()
+        };
+
+        assert_eq!(
+            OrgModeRouter::affected_uris(&subscribed, &changes),
+            [
+                "org-heading://notes.org#Inbox",
+                "org-outline://notes.org",
+                "org://notes.org",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_affected_uris_for_listing_and_agenda() {
+        let subscribed = subscriptions(&["org://", "org-agenda://week", "org://notes.org"]);
+        let changes = FileChanges {
+            added: vec!["new.org".to_string()],
+            agenda_changed: true,
+            ..FileChanges::default()
+        };
+
+        assert_eq!(
+            OrgModeRouter::affected_uris(&subscribed, &changes),
+            ["org-agenda://week", "org://"]
+        );
+    }
+}
@@ -32,7 +32,7 @@ pub(crate) mod integration_tests {
 }
 
 // Re-export the macro and utilities for use by test modules
-pub(crate) use utils::{get_binary_path, setup_test_org_files};
+pub(crate) use utils::{NO_CACHE_ENV, get_binary_path, setup_test_org_files};
 
 #[macro_export]
 macro_rules! create_mcp_service {
@@ -45,7 +45,8 @@ macro_rules! create_mcp_service {
 
         let command = tokio::process::Command::new($crate::get_binary_path("org-mcp-server"))
             .configure(|cmd| {
-                cmd.args(["--root-directory", $temp_dir.path().to_str().unwrap()]);
+                cmd.args(["--root-directory", $temp_dir.path().to_str().unwrap()])
+                    .env($crate::NO_CACHE_ENV, "");
             });
 
         ().serve(TokioChildProcess::new(command)?)
@@ -80,6 +80,7 @@ async fn test_list_resource_templates() -> Result<(), Box<dyn std::error::Error>
     assert!(template_uris.contains(&"org-outline://{file}"));
     assert!(template_uris.contains(&"org-heading://{file}#{heading}"));
     assert!(template_uris.contains(&"org-id://{id}"));
+    assert!(template_uris.contains(&"org-backlinks://{id}"));
 
     // Verify each template has required metadata fields
     for template in &templates.resource_templates {
@@ -568,3 +569,96 @@ async fn test_org_agenda_resource_error_handling() -> Result<(), Box<dyn std::er
 
     Ok(())
 }
+
+/// Tests org-backlinks://{id} resource functionality.
+///
+/// Verifies that:
+/// - id: and file::*Heading links to the node are both reported
+/// - Each backlink carries the referencing file and enclosing heading
+#[tokio::test]
+#[traced_test]
+async fn test_read_org_backlinks_resource() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    std::fs::write(
+        temp_dir.path().join("links.org"),
+        "* Shopping\nRemember [[id:task-groceries-456][groceries]].\n\
+         * Reading\nSee [[file:notes.org::*Read book]].\n",
+    )?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let result = service
+        .read_resource(ReadResourceRequestParams::new(
+            "org-backlinks://task-groceries-456".to_string(),
+        ))
+        .await?;
+
+    if let Some(rmcp::model::ResourceContents::TextResourceContents { text, .. }) =
+        result.contents.first()
+    {
+        let backlinks: serde_json::Value = serde_json::from_str(text)?;
+        let backlinks = backlinks.as_array().expect("Expected a JSON array");
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0]["file_path"], "links.org");
+        assert_eq!(backlinks[0]["heading_path"], "Shopping");
+        assert_eq!(backlinks[0]["description"], "groceries");
+    } else {
+        panic!("Expected text content in backlinks result");
+    }
+
+    let result = service
+        .read_resource(ReadResourceRequestParams::new(
+            "org-backlinks://task-book-789".to_string(),
+        ))
+        .await?;
+    if let Some(rmcp::model::ResourceContents::TextResourceContents { text, .. }) =
+        result.contents.first()
+    {
+        assert!(text.contains("file:notes.org::*Read book"));
+    } else {
+        panic!("Expected text content in backlinks result");
+    }
+
+    service.cancel().await?;
+    Ok(())
+}
+
+/// Tests org-links://{file} outgoing link resource functionality.
+///
+/// Verifies that:
+/// - Links are typed (id, file, https)
+/// - Dangling targets are reported as not resolving
+#[tokio::test]
+#[traced_test]
+async fn test_read_org_links_resource() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    std::fs::write(
+        temp_dir.path().join("links.org"),
+        "* Refs\n[[id:task-book-789][Book]] [[file:missing.org]] [[https://orgmode.org][Org]]\n",
+    )?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let result = service
+        .read_resource(ReadResourceRequestParams::new(
+            "org-links://links.org".to_string(),
+        ))
+        .await?;
+
+    if let Some(rmcp::model::ResourceContents::TextResourceContents { text, .. }) =
+        result.contents.first()
+    {
+        let links: serde_json::Value = serde_json::from_str(text)?;
+        let links = links.as_array().expect("Expected a JSON array");
+        assert_eq!(links.len(), 3);
+        assert_eq!(links[0]["kind"], "id");
+        assert_eq!(links[0]["resolves"], true);
+        assert_eq!(links[1]["kind"], "file");
+        assert_eq!(links[1]["resolves"], false);
+        assert_eq!(links[2]["kind"], "https");
+        assert!(links[2].get("resolves").is_none());
+    } else {
+        panic!("Expected text content in links result");
+    }
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -3,6 +3,8 @@
 //! This module contains tests that verify basic server functionality including
 //! startup, shutdown, connection establishment, and server information exchange.
 
+use std::time::Duration;
+
 use rmcp::transport::ConfigureCommandExt;
 use tokio::process::Command;
 use tracing::{error, info};
@@ -25,7 +27,8 @@ async fn test_graceful_close_mcp_server() -> Result<(), Box<dyn std::error::Erro
     let org_dir = setup_test_org_files()?;
     let binary = get_binary_path("org-mcp-server");
     let mut command = Command::new(binary).configure(|cmd| {
-        cmd.args(["--root-directory", org_dir.path().to_str().unwrap()]);
+        cmd.args(["--root-directory", org_dir.path().to_str().unwrap()])
+            .env(crate::NO_CACHE_ENV, "");
     });
 
     let mut child = command.stdin(std::process::Stdio::piped()).spawn()?;
@@ -138,7 +141,8 @@ level = "debug"
 
     let command =
         tokio::process::Command::new(crate::get_binary_path("org-mcp-server")).configure(|cmd| {
-            cmd.args(["--config", config_path.to_str().unwrap()]);
+            cmd.args(["--config", config_path.to_str().unwrap()])
+                .env(crate::NO_CACHE_ENV, "");
         });
 
     let service = ().serve(TokioChildProcess::new(command)?).await?;
@@ -154,3 +158,156 @@ level = "debug"
 
     Ok(())
 }
+
+/// Tests that the server advertises resource subscriptions and pushes
+/// `notifications/resources/updated` when a subscribed file changes on disk.
+#[tokio::test]
+#[traced_test]
+async fn test_resource_updated_notification_on_file_change()
+-> Result<(), Box<dyn std::error::Error>> {
+    use rmcp::{
+        ClientHandler, RoleClient, ServiceExt,
+        model::{ResourceUpdatedNotificationParam, SubscribeRequestParams},
+        service::NotificationContext,
+        transport::TokioChildProcess,
+    };
+    use std::fs;
+    use tokio::sync::mpsc;
+
+    #[derive(Clone)]
+    struct UpdateRecorder(mpsc::UnboundedSender<String>);
+
+    impl ClientHandler for UpdateRecorder {
+        async fn on_resource_updated(
+            &self,
+            params: ResourceUpdatedNotificationParam,
+            _context: NotificationContext<RoleClient>,
+        ) {
+            let _ = self.0.send(params.uri);
+        }
+    }
+
+    let temp_dir = setup_test_org_files()?;
+    let config_path = temp_dir.path().join("test-config.toml");
+    let path_str = temp_dir.path().to_str().unwrap().replace('\\', "/");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+[org]
+org_directory = "{path_str}"
+org_cache_directory = ""
+
+[server]
+watch_interval_secs = 1
+"#
+        ),
+    )?;
+
+    let command = Command::new(get_binary_path("org-mcp-server")).configure(|cmd| {
+        cmd.args(["--config", config_path.to_str().unwrap()]);
+    });
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let service = UpdateRecorder(tx)
+        .serve(TokioChildProcess::new(command)?)
+        .await?;
+
+    let resources = service
+        .peer_info()
+        .and_then(|info| info.capabilities.resources.clone())
+        .expect("Server should declare resource capabilities");
+    assert_eq!(resources.subscribe, Some(true));
+    assert_eq!(resources.list_changed, Some(true));
+
+    service
+        .subscribe(SubscribeRequestParams::new("org://notes.org"))
+        .await?;
+
+    // Let the watcher take its baseline before touching the file.
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    fs::write(
+        temp_dir.path().join("notes.org"),
+        "* Rewritten\nThe file changed on disk.\n",
+    )?;
+
+    let uri = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+        .await?
+        .expect("notification channel closed");
+    assert_eq!(uri, "org://notes.org");
+
+    service.cancel().await?;
+    Ok(())
+}
+
+/// Sends an MCP `initialize` request over HTTP and returns the status line.
+fn http_initialize(addr: &str) -> std::io::Result<String> {
+    use std::io::{BufRead, BufReader, Write};
+
+    let body = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"0.0.0"}}}"#;
+    let mut stream = std::net::TcpStream::connect(addr)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    write!(
+        stream,
+        "POST /mcp HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nAccept: application/json, text/event-stream\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )?;
+
+    let mut status = String::new();
+    BufReader::new(&stream).read_line(&mut status)?;
+    Ok(status.trim_end().to_string())
+}
+
+/// Tests that `--transport http` serves MCP on `/mcp` and refuses sessions
+/// beyond `max_connections`.
+#[tokio::test]
+#[traced_test]
+async fn test_http_transport_limits_sessions() -> Result<(), Box<dyn std::error::Error>> {
+    use std::fs;
+
+    let temp_dir = setup_test_org_files()?;
+    let config_path = temp_dir.path().join("test-config.toml");
+    let path_str = temp_dir.path().to_str().unwrap().replace('\\', "/");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+[org]
+org_directory = "{path_str}"
+
+[server]
+max_connections = 1
+"#
+        ),
+    )?;
+
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")?
+        .local_addr()?
+        .to_string();
+    let _server = Command::new(get_binary_path("org-mcp-server"))
+        .args(["--config", config_path.to_str().unwrap()])
+        .args(["--transport", "http", "--bind", &addr])
+        .env(crate::NO_CACHE_ENV, "")
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let mut first = None;
+    for _ in 0..50 {
+        match http_initialize(&addr) {
+            Ok(status) => {
+                first = Some(status);
+                break;
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(200)).await,
+        }
+    }
+    let first = first.expect("HTTP server should accept connections");
+    assert!(first.ends_with("200 OK"), "unexpected status: {first}");
+
+    let second = http_initialize(&addr)?;
+    assert!(
+        !second.ends_with("200 OK"),
+        "second session should be refused, got: {second}"
+    );
+
+    Ok(())
+}
@@ -441,6 +441,97 @@ async fn test_org_search_tool_with_multiple_tags() -> Result<(), Box<dyn std::er
     Ok(())
 }
 
+/// Tests the org-search tool's matching modes and scopes.
+///
+/// Verifies that:
+/// - Regex mode returns every line the pattern matches
+/// - Case-sensitive exact mode skips lines differing in case
+/// - Files and element types narrow the search down
+/// - An unknown mode or element is rejected
+#[tokio::test]
+#[traced_test]
+async fn test_org_search_tool_modes() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let search = |query: &str, mode: &str, case_sensitive: bool| {
+        let mut args = Map::new();
+        args.insert("query".to_string(), Value::String(query.into()));
+        args.insert("mode".to_string(), Value::String(mode.into()));
+        args.insert("case_sensitive".to_string(), Value::Bool(case_sensitive));
+        CallToolRequestParams::new("org-search").with_arguments(args)
+    };
+
+    let snippets = |result: rmcp::model::CallToolResult| -> Vec<String> {
+        let text = result
+            .content
+            .first()
+            .and_then(|content| content.as_text())
+            .map(|text| text.text.clone())
+            .expect("Expected text content in org-search result");
+        let results: serde_json::Value =
+            serde_json::from_str(&text).expect("Search results should be valid JSON");
+        results
+            .as_array()
+            .expect("Expected a JSON array")
+            .iter()
+            .map(|result| result["snippet"].as_str().unwrap_or_default().to_string())
+            .collect()
+    };
+
+    let result = service
+        .call_tool(search("bug in (authentication|dashboard)", "regex", false))
+        .await?;
+    let found = snippets(result);
+    assert_eq!(found.len(), 2, "Got {found:?}");
+
+    let result = service
+        .call_tool(search("CRITICAL BUG", "exact", true))
+        .await?;
+    assert!(snippets(result).is_empty());
+
+    let result = service.call_tool(search("bug", "soundex", false)).await;
+    assert!(result.is_err(), "Expected error for an unknown mode");
+
+    let mut args = Map::new();
+    args.insert("query".to_string(), Value::String("bug".into()));
+    args.insert("mode".to_string(), Value::String("exact".into()));
+    args.insert("files".to_string(), Value::String("search_test.org".into()));
+    args.insert(
+        "elements".to_string(),
+        Value::Array(vec![Value::String("headline".into())]),
+    );
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-search").with_arguments(args))
+        .await?;
+    assert_eq!(snippets(result), ["* Bug Reports"]);
+
+    let mut args = Map::new();
+    args.insert("query".to_string(), Value::String("bug".into()));
+    args.insert(
+        "elements".to_string(),
+        Value::Array(vec![Value::String("comment".into())]),
+    );
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-search").with_arguments(args))
+        .await;
+    assert!(result.is_err(), "Expected error for an unknown element");
+
+    for files in ["/etc/*", "../x.org"] {
+        let mut args = Map::new();
+        args.insert("query".to_string(), Value::String(".".into()));
+        args.insert("mode".to_string(), Value::String("regex".into()));
+        args.insert("files".to_string(), Value::String(files.into()));
+        let result = service
+            .call_tool(CallToolRequestParams::new("org-search").with_arguments(args))
+            .await;
+        assert!(result.is_err(), "Expected {files} to be rejected");
+    }
+
+    service.cancel().await?;
+    Ok(())
+}
+
 /// Tests basic org-agenda tool functionality in list mode.
 ///
 /// Verifies that:
@@ -571,6 +662,60 @@ async fn test_org_agenda_tool_list_with_tags() -> Result<(), Box<dyn std::error:
     Ok(())
 }
 
+/// Tests org-agenda tool with an Emacs match string.
+///
+/// Verifies that:
+/// - Tag, negated tag and TODO keyword terms all apply
+/// - A malformed match string is rejected
+#[tokio::test]
+#[traced_test]
+async fn test_org_agenda_tool_list_with_match() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let mut args = Map::new();
+    args.insert("mode".to_string(), Value::String("list".into()));
+    args.insert(
+        "match".to_string(),
+        Value::String("+work+review-urgent/!TODO".into()),
+    );
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-agenda").with_arguments(args))
+        .await?;
+
+    if let Some(content) = result.content.first() {
+        if let Some(text) = content.as_text() {
+            let items: serde_json::Value =
+                serde_json::from_str(&text.text).expect("Should be valid JSON");
+            let headings: Vec<_> = items
+                .as_array()
+                .expect("Expected a JSON array")
+                .iter()
+                .map(|item| item["heading"].as_str().unwrap_or_default())
+                .collect();
+            assert!(headings.contains(&"Review documentation"));
+            assert!(!headings.contains(&"Code review session"));
+            assert!(headings.iter().all(|h| *h == h.trim_end()));
+        } else {
+            panic!("Expected text content in org-agenda result");
+        }
+    } else {
+        panic!("No content in org-agenda result");
+    }
+
+    let mut args = Map::new();
+    args.insert("mode".to_string(), Value::String("list".into()));
+    args.insert("match".to_string(), Value::String("+work|".into()));
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-agenda").with_arguments(args))
+        .await;
+    assert!(result.is_err(), "Expected error for an empty match group");
+
+    service.cancel().await?;
+    Ok(())
+}
+
 /// Tests org-agenda tool with priority filtering.
 ///
 /// Verifies that:
@@ -732,6 +877,12 @@ async fn test_org_agenda_tool_view_mode_custom_range() -> Result<(), Box<dyn std
                 serde_json::from_str(&text.text).expect("View should be valid JSON");
 
             assert!(view["items"].is_array(), "View should have items array");
+            let days = view["days"]
+                .as_array()
+                .expect("View should have days array");
+            assert_eq!(days.len(), 6, "One bucket per day of the range");
+            assert_eq!(days[0]["date"], "2025-10-20");
+            assert_eq!(days[5]["date"], "2025-10-25");
         } else {
             panic!("Expected text content in org-agenda result");
         }
@@ -800,6 +951,64 @@ async fn test_org_agenda_tool_invalid_priority() -> Result<(), Box<dyn std::erro
     Ok(())
 }
 
+/// Tests org-agenda tool with a sort override.
+///
+/// Verifies that:
+/// - Items come back in the order of the given sort keys
+/// - Unknown sort keys are rejected
+#[tokio::test]
+#[traced_test]
+async fn test_org_agenda_tool_list_with_sort() -> Result<(), Box<dyn std::error::Error>> {
+    info!("Starting MCP client to test org-agenda tool with sort");
+
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let mut args = Map::new();
+    args.insert("mode".to_string(), Value::String("list".into()));
+    args.insert(
+        "sort".to_string(),
+        Value::Array(vec![Value::String("alpha-up".into())]),
+    );
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-agenda").with_arguments(args))
+        .await?;
+
+    let text = result
+        .content
+        .first()
+        .and_then(|content| content.as_text())
+        .expect("Expected text content in org-agenda result");
+    let tasks: serde_json::Value = serde_json::from_str(&text.text)?;
+    let headings: Vec<String> = tasks
+        .as_array()
+        .expect("Result should be a JSON array")
+        .iter()
+        .map(|task| task["heading"].as_str().unwrap().to_lowercase())
+        .collect();
+    assert!(headings.len() > 1);
+    assert!(
+        headings.is_sorted(),
+        "Headings should be sorted: {headings:?}"
+    );
+
+    let mut args = Map::new();
+    args.insert(
+        "sort".to_string(),
+        Value::Array(vec![Value::String("alpha-sideways".into())]),
+    );
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-agenda").with_arguments(args))
+        .await;
+    assert!(result.is_err(), "Expected error for invalid sort key");
+
+    service.cancel().await?;
+    info!("org-agenda with sort test completed successfully");
+
+    Ok(())
+}
+
 /// Tests org-agenda tool error handling for invalid date format.
 ///
 /// Verifies that:
@@ -1485,3 +1694,371 @@ async fn test_org_update_todo_not_found() -> Result<(), Box<dyn std::error::Erro
     service.cancel().await?;
     Ok(())
 }
+
+// --- org-clock tool tests ---
+
+/// Tests clocking in and out of a heading by ID through the org-clock tool.
+#[tokio::test]
+#[traced_test]
+async fn test_org_clock_in_and_out() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let mut args = Map::new();
+    args.insert("action".to_string(), Value::String("in".into()));
+    args.insert("id".to_string(), Value::String("task-groceries-456".into()));
+    args.insert("at".to_string(), Value::String("2026-05-15 09:00".into()));
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-clock").with_arguments(args))
+        .await?;
+    assert!(!result.content.is_empty());
+
+    let mut args = Map::new();
+    args.insert("action".to_string(), Value::String("out".into()));
+    args.insert("id".to_string(), Value::String("task-groceries-456".into()));
+    args.insert("at".to_string(), Value::String("2026-05-15 10:30".into()));
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-clock").with_arguments(args))
+        .await?;
+
+    if let Some(content) = result.content.first() {
+        if let Some(text) = content.as_text() {
+            let clock_result: serde_json::Value =
+                serde_json::from_str(&text.text).expect("Should be valid JSON");
+            assert_eq!(clock_result["file_path"], "notes.org");
+            assert_eq!(clock_result["action"], "out");
+            assert_eq!(clock_result["duration_minutes"], 90);
+        } else {
+            panic!("Expected text content in org-clock result");
+        }
+    }
+
+    let content = std::fs::read_to_string(temp_dir.path().join("notes.org"))?;
+    assert!(content.contains("CLOCK: [2026-05-15 Fri 09:00]--[2026-05-15 Fri 10:30] =>  1:30"));
+
+    service.cancel().await?;
+    Ok(())
+}
+
+/// Tests that an unknown clock action is rejected.
+#[tokio::test]
+#[traced_test]
+#[allow(clippy::single_match)]
+async fn test_org_clock_invalid_action() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let mut args = Map::new();
+    args.insert("action".to_string(), Value::String("pause".into()));
+    args.insert("id".to_string(), Value::String("task-groceries-456".into()));
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-clock").with_arguments(args))
+        .await;
+
+    match result {
+        Ok(r) => assert_eq!(r.is_error, Some(true), "expected tool error result"),
+        Err(_) => {} // protocol-level error is also acceptable
+    }
+
+    service.cancel().await?;
+    Ok(())
+}
+
+// --- org-clock-report tool tests ---
+
+/// Tests that clocked time shows up in the org-clock-report totals.
+#[tokio::test]
+#[traced_test]
+async fn test_org_clock_report_by_heading() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    for (action, at) in [("in", "2026-05-15 09:00"), ("out", "2026-05-15 09:45")] {
+        let mut args = Map::new();
+        args.insert("action".to_string(), Value::String(action.into()));
+        args.insert("id".to_string(), Value::String("task-groceries-456".into()));
+        args.insert("at".to_string(), Value::String(at.into()));
+        service
+            .call_tool(CallToolRequestParams::new("org-clock").with_arguments(args))
+            .await?;
+    }
+
+    let mut args = Map::new();
+    args.insert("start_date".to_string(), Value::String("2026-05-15".into()));
+    args.insert("end_date".to_string(), Value::String("2026-05-15".into()));
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-clock-report").with_arguments(args))
+        .await?;
+
+    if let Some(content) = result.content.first() {
+        if let Some(text) = content.as_text() {
+            let report: serde_json::Value =
+                serde_json::from_str(&text.text).expect("Should be valid JSON");
+            assert_eq!(report["total_minutes"], 45);
+            assert_eq!(report["group_by"], "heading");
+            assert_eq!(report["rows"][0]["key"], "Daily Tasks/Buy groceries");
+            assert_eq!(report["rows"][0]["file_path"], "notes.org");
+        } else {
+            panic!("Expected text content in org-clock-report result");
+        }
+    }
+
+    service.cancel().await?;
+    Ok(())
+}
+
+// --- org-refile tool tests ---
+
+/// Tests refiling a heading by ID to the top level of another file.
+#[tokio::test]
+#[traced_test]
+async fn test_org_refile_to_other_file() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let mut args = Map::new();
+    args.insert("id".to_string(), Value::String("task-groceries-456".into()));
+    args.insert(
+        "target_file".to_string(),
+        Value::String("simple.org".into()),
+    );
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-refile").with_arguments(args))
+        .await?;
+
+    if let Some(content) = result.content.first() {
+        if let Some(text) = content.as_text() {
+            let refile_result: serde_json::Value =
+                serde_json::from_str(&text.text).expect("Should be valid JSON");
+            assert_eq!(refile_result["source_file"], "notes.org");
+            assert_eq!(refile_result["target_file"], "simple.org");
+            assert_eq!(refile_result["level"], 1);
+        } else {
+            panic!("Expected text content in org-refile result");
+        }
+    }
+
+    let notes = std::fs::read_to_string(temp_dir.path().join("notes.org"))?;
+    assert!(!notes.contains("Buy groceries"));
+    let simple = std::fs::read_to_string(temp_dir.path().join("simple.org"))?;
+    assert!(simple.contains("* TODO Buy groceries"));
+
+    service.cancel().await?;
+    Ok(())
+}
+
+// --- org-archive tool tests ---
+
+/// Tests archiving a subtree by ID to the default `<file>_archive` location.
+#[tokio::test]
+#[traced_test]
+async fn test_org_archive_subtree() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let mut args = Map::new();
+    args.insert("id".to_string(), Value::String("task-groceries-456".into()));
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-archive").with_arguments(args))
+        .await?;
+
+    if let Some(content) = result.content.first() {
+        if let Some(text) = content.as_text() {
+            let archive_result: serde_json::Value =
+                serde_json::from_str(&text.text).expect("Should be valid JSON");
+            assert_eq!(archive_result["file_path"], "notes.org");
+            assert_eq!(archive_result["archive_file"], "notes.org_archive");
+            assert_eq!(archive_result["mode"], "subtree");
+            assert_eq!(archive_result["archived"], true);
+        } else {
+            panic!("Expected text content in org-archive result");
+        }
+    }
+
+    let notes = std::fs::read_to_string(temp_dir.path().join("notes.org"))?;
+    assert!(!notes.contains("Buy groceries"));
+    let archive = std::fs::read_to_string(temp_dir.path().join("notes.org_archive"))?;
+    assert!(archive.contains("Buy groceries"));
+    assert!(archive.contains(":ARCHIVE_OLPATH: Daily Tasks"));
+    assert!(archive.contains(":ARCHIVE_TODO: TODO"));
+
+    service.cancel().await?;
+    Ok(())
+}
+
+/// Tests that an unknown archive mode is rejected.
+#[tokio::test]
+#[traced_test]
+async fn test_org_archive_invalid_mode() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let mut args = Map::new();
+    args.insert("id".to_string(), Value::String("task-groceries-456".into()));
+    args.insert("mode".to_string(), Value::String("trash".into()));
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-archive").with_arguments(args))
+        .await;
+    assert!(result.is_err(), "Expected error for invalid mode");
+
+    service.cancel().await?;
+    Ok(())
+}
+
+// --- org-promote / org-demote tool tests ---
+
+/// Tests demoting a heading and promoting it back, reporting the level change.
+#[tokio::test]
+#[traced_test]
+async fn test_org_demote_and_promote() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let mut args = Map::new();
+    args.insert("id".to_string(), Value::String("task-groceries-456".into()));
+    args.insert("subtree".to_string(), Value::Bool(true));
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-demote").with_arguments(args.clone()))
+        .await?;
+
+    if let Some(content) = result.content.first() {
+        if let Some(text) = content.as_text() {
+            let update_result: serde_json::Value =
+                serde_json::from_str(&text.text).expect("Should be valid JSON");
+            assert_eq!(update_result["file_path"], "notes.org");
+            assert_eq!(update_result["heading_line"], "*** TODO Buy groceries");
+            assert_eq!(update_result["changes"][0], "level: 2 -> 3");
+        } else {
+            panic!("Expected text content in org-demote result");
+        }
+    }
+
+    service
+        .call_tool(CallToolRequestParams::new("org-promote").with_arguments(args))
+        .await?;
+    let notes = std::fs::read_to_string(temp_dir.path().join("notes.org"))?;
+    assert!(notes.contains("\n** TODO Buy groceries\n"));
+
+    service.cancel().await?;
+    Ok(())
+}
+
+/// Tests that promoting a top-level heading is rejected.
+#[tokio::test]
+#[traced_test]
+async fn test_org_promote_past_level_one() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let mut args = Map::new();
+    args.insert("id".to_string(), Value::String("daily-tasks-123".into()));
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-promote").with_arguments(args))
+        .await;
+    assert!(
+        result.is_err(),
+        "Expected error when promoting past level 1"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+// --- org-links tool tests ---
+
+/// Tests finding backlinks to a heading through its ID.
+#[tokio::test]
+#[traced_test]
+async fn test_org_links_backlinks() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    std::fs::write(
+        temp_dir.path().join("links.org"),
+        "* Weekend\n- [[id:daily-tasks-123][Daily tasks]]\n- [[file:notes.org::*Daily Tasks]]\n",
+    )?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let mut args = Map::new();
+    args.insert(
+        "target".to_string(),
+        Value::String("daily-tasks-123".into()),
+    );
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-links").with_arguments(args))
+        .await?;
+
+    if let Some(content) = result.content.first() {
+        if let Some(text) = content.as_text() {
+            let backlinks: serde_json::Value =
+                serde_json::from_str(&text.text).expect("Should be valid JSON");
+            let backlinks = backlinks.as_array().expect("Expected a JSON array");
+            assert_eq!(backlinks.len(), 2);
+            assert_eq!(backlinks[0]["line"], 2);
+            assert_eq!(backlinks[1]["link"], "file:notes.org::*Daily Tasks");
+            assert_eq!(backlinks[1]["heading_path"], "Weekend");
+        } else {
+            panic!("Expected text content in org-links result");
+        }
+    }
+
+    service.cancel().await?;
+    Ok(())
+}
+
+// --- org-query tool tests ---
+
+/// Tests running a query expression over the agenda headings.
+#[tokio::test]
+#[traced_test]
+async fn test_org_query_tool() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_test_org_files()?;
+    let service = create_mcp_service!(&temp_dir);
+
+    let mut args = Map::new();
+    args.insert(
+        "query".to_string(),
+        Value::String("(and (todo) (tags \"review\") (priority \"A\"))".into()),
+    );
+
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-query").with_arguments(args))
+        .await?;
+
+    if let Some(content) = result.content.first() {
+        if let Some(text) = content.as_text() {
+            let items: serde_json::Value =
+                serde_json::from_str(&text.text).expect("Should be valid JSON");
+            let items = items.as_array().expect("Expected a JSON array");
+            assert!(
+                items
+                    .iter()
+                    .any(|item| item["heading"] == "Code review session")
+            );
+            for item in items {
+                assert_eq!(item["todo_state"], "TODO");
+                assert_eq!(item["priority"], "A");
+            }
+        } else {
+            panic!("Expected text content in org-query result");
+        }
+    }
+
+    let mut args = Map::new();
+    args.insert("query".to_string(), Value::String("(frobnicate)".into()));
+    let result = service
+        .call_tool(CallToolRequestParams::new("org-query").with_arguments(args))
+        .await;
+    assert!(result.is_err(), "Expected error for an unknown predicate");
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -10,6 +10,10 @@ use std::{
 
 use tempfile::TempDir;
 
+/// Environment variable that, set to an empty string, keeps the server's
+/// metadata cache in memory, so test runs never write under `$HOME`.
+pub const NO_CACHE_ENV: &str = "ORG_ORG__ORG_CACHE_DIRECTORY";
+
 /// Gets the path to a compiled binary in the target directory.
 ///
 /// This function first checks for a Cargo-provided environment variable